@@ -1,13 +1,15 @@
 //! You can use async channel between threads(with `sync` feature).
-//! Remember: it is not efficient. You should rely thread local for hot paths.
+//! The receiving runtime is only unparked when needed, but you should still
+//! rely on thread local for hot paths.
 
 use std::time::Duration;
 
-use futures::channel::oneshot;
+use snowfallio::sync::remote::{mpsc, oneshot};
 
 #[snowfallio::main]
 async fn main() {
     let (tx, rx) = oneshot::channel::<u8>();
+    let (done_tx, mut done_rx) = mpsc::unbounded_channel::<&str>();
     let t = std::thread::spawn(move || {
         println!("remote thread created");
         let mut rt = snowfallio::RuntimeBuilder::<snowfallio::IoUringDriver>::new()
@@ -16,12 +18,13 @@ async fn main() {
         rt.block_on(async move {
             let n = rx.await;
             println!("await result: {n:?}");
+            done_tx.send("remote thread finished").unwrap();
         });
         println!("remote thread exit");
     });
 
     std::thread::sleep(Duration::from_secs(1));
     println!("send local: {:?}", tx.send(1));
-    println!("wait for remote thread");
+    println!("wait for remote thread: {:?}", done_rx.recv().await);
     let _ = t.join();
 }
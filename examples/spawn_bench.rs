@@ -0,0 +1,42 @@
+//! Measure the cost of spawning and completing short-lived tasks, with and
+//! without recycling the task allocations.
+//!
+//! Run it in release mode: `cargo run --release --example spawn-bench`.
+
+use std::time::Instant;
+
+use snowfallio::{IoUringDriver, RuntimeBuilder};
+
+const TASKS: usize = 1_000_000;
+const BATCH: usize = 64;
+
+fn bench(name: &str, cache_capacity: usize) {
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .task_cache_capacity(cache_capacity)
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let begin = Instant::now();
+        let mut handles = Vec::with_capacity(BATCH);
+        for _ in 0..TASKS / BATCH {
+            // A request sized future, completing on its first poll.
+            for i in 0..BATCH {
+                let buf = [i as u8; 256];
+                handles.push(snowfallio::spawn(async move { buf[0] as usize }));
+            }
+            for handle in handles.drain(..) {
+                std::hint::black_box(handle.await);
+            }
+        }
+        let elapsed = begin.elapsed();
+        println!(
+            "{name}: {TASKS} tasks in {elapsed:?}, {:.1} ns/task",
+            elapsed.as_nanos() as f64 / TASKS as f64
+        );
+    });
+}
+
+fn main() {
+    bench("global allocator", 0);
+    bench("task cache", BATCH);
+}
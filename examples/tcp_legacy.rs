@@ -1,9 +1,9 @@
 //! An example to show how to use TcpStream.
 
-use futures::channel::oneshot;
 use snowfallio::{
     io::{AsyncReadRent, AsyncWriteRentExt},
     net::{TcpListener, TcpStream},
+    sync::remote::oneshot,
     Buildable, Driver,
 };
 
@@ -21,7 +21,7 @@ where
     let client_thread = std::thread::spawn(|| {
         snowfallio::start::<D, _>(async move {
             println!("[Client] Waiting for server ready");
-            tx.cancellation().await;
+            tx.closed().await;
 
             println!("[Client] Server is ready, will connect and send data");
             let mut conn = TcpStream::connect(ADDRESS)
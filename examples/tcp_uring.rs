@@ -9,10 +9,10 @@ fn run<D>()
 where
     D: snowfallio::Buildable + snowfallio::Driver,
 {
-    use futures::channel::oneshot;
     use snowfallio::{
         io::{AsyncReadRent, AsyncWriteRentExt},
         net::{TcpListener, TcpStream},
+        sync::remote::oneshot,
     };
 
     const ADDRESS: &str = "127.0.0.1:50000";
@@ -21,7 +21,7 @@ where
     let client_thread = std::thread::spawn(|| {
         snowfallio::start::<D, _>(async move {
             println!("[Client] Waiting for server ready");
-            tx.cancellation().await;
+            tx.closed().await;
 
             println!("[Client] Server is ready, will connect and send data");
             let mut conn = TcpStream::connect(ADDRESS)
@@ -1,9 +1,9 @@
 //! A example to show how to use UnixStream.
 
-use local_sync::oneshot::channel;
 use snowfallio::{
     io::{AsyncReadRent, AsyncWriteRentExt},
     net::{UnixListener, UnixStream},
+    sync::oneshot::channel,
 };
 
 const ADDRESS: &str = "/tmp/monoio-unix-test.sock";
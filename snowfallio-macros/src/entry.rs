@@ -9,12 +9,14 @@ struct FinalConfig {
     entries: Option<u32>,
     timer_enabled: Option<bool>,
     threads: Option<u32>,
+    blocking_threads: Option<u32>,
 }
 
 struct Configuration {
     entries: Option<(u32, Span)>,
     timer_enabled: Option<(bool, Span)>,
     threads: Option<(u32, Span)>,
+    blocking_threads: Option<(u32, Span)>,
 }
 
 impl Configuration {
@@ -23,6 +25,7 @@ impl Configuration {
             entries: None,
             timer_enabled: None,
             threads: None,
+            blocking_threads: None,
         }
     }
 
@@ -39,6 +42,22 @@ impl Configuration {
         Ok(())
     }
 
+    fn set_blocking_threads(&mut self, threads: syn::Lit, span: Span) -> Result<(), syn::Error> {
+        if self.blocking_threads.is_some() {
+            return Err(syn::Error::new(
+                span,
+                "`blocking_threads` set multiple times.",
+            ));
+        }
+
+        let threads = parse_int(threads, span, "blocking_threads")? as u32;
+        if threads == 0 {
+            return Err(syn::Error::new(span, "`blocking_threads` may not be 0."));
+        }
+        self.blocking_threads = Some((threads, span));
+        Ok(())
+    }
+
     fn set_entries(&mut self, entries: syn::Lit, span: Span) -> Result<(), syn::Error> {
         if self.entries.is_some() {
             return Err(syn::Error::new(span, "`entries` set multiple times."));
@@ -67,6 +86,7 @@ impl Configuration {
             entries: self.entries.map(|(e, _)| e),
             timer_enabled: self.timer_enabled.map(|(t, _)| t),
             threads: self.threads.map(|(t, _)| t),
+            blocking_threads: self.blocking_threads.map(|(t, _)| t),
         })
     }
 }
@@ -146,10 +166,14 @@ fn parse_knobs(
                         namevalue.lit.clone(),
                         syn::spanned::Spanned::span(&namevalue.lit),
                     )?,
+                    "blocking_threads" => config.set_blocking_threads(
+                        namevalue.lit.clone(),
+                        syn::spanned::Spanned::span(&namevalue.lit),
+                    )?,
                     name => {
                         let msg = format!(
                             "Unknown attribute {name} is specified; expected one of: \
-                             `worker_threads`, `entries`, `timer_enabled`",
+                             `worker_threads`, `entries`, `timer_enabled`, `blocking_threads`",
                         );
                         return Err(syn::Error::new_spanned(namevalue, msg));
                     }
@@ -163,7 +187,7 @@ fn parse_knobs(
                     .to_lowercase();
                 let msg = format!(
                     "Unknown attribute {name} is specified; expected one of: `worker_threads`, \
-                     `entries`, `timer_enabled`"
+                     `entries`, `timer_enabled`, `blocking_threads`"
                 );
                 return Err(syn::Error::new_spanned(path, msg));
             }
@@ -201,6 +225,24 @@ fn parse_knobs(
     if let Some(entries) = config.entries {
         rt = quote! { #rt.with_entries(#entries) }
     }
+    let multi_thread = !matches!(config.threads, None | Some(1));
+    let (mut shared_pool, mut clone_pool) = (quote! {}, quote! {});
+    if multi_thread {
+        // Built once, so the runtimes of every thread share the pool
+        let pool = match config.blocking_threads {
+            Some(blocking_threads) => {
+                let blocking_threads = blocking_threads as usize;
+                quote! { snowfallio::blocking::BlockingPool::new(0, #blocking_threads) }
+            }
+            None => quote! { snowfallio::blocking::BlockingPool::builder().build() },
+        };
+        shared_pool = quote! { let blocking_pool = #pool; };
+        clone_pool = quote! { let blocking_pool = blocking_pool.clone(); };
+        rt = quote! { #rt.with_blocking_pool(blocking_pool.clone()) }
+    } else if let Some(blocking_threads) = config.blocking_threads {
+        let blocking_threads = blocking_threads as usize;
+        rt = quote! { #rt.blocking_threads(0, #blocking_threads) }
+    }
     if Some(true) == config.timer_enabled {
         rt = quote! { #rt.enable_timer() }
     }
@@ -220,7 +262,7 @@ fn parse_knobs(
         _ => (quote! {}, quote! {}),
     };
 
-    if matches!(config.threads, None | Some(1)) {
+    if !multi_thread {
         input.block = syn::parse2(quote_spanned! {last_stmt_end_span=>
             {
                 let body = async #body;
@@ -245,11 +287,13 @@ fn parse_knobs(
         input.block = syn::parse2(quote_spanned! {last_stmt_end_span=>
             {
                 let body = async #body;
+                #shared_pool
 
                 #[allow(clippy::needless_collect)]
                 let threads: Vec<_> = (0 .. #threads)
                     .map(|_| {
-                        ::std::thread::spawn(|| {
+                        #clone_pool
+                        ::std::thread::spawn(move || {
                             #rt.build()
                                 .expect("Failed building the Runtime")
                                 .block_on(async #body);
@@ -4,24 +4,34 @@ use std::{future::Future, sync::Arc, task::Poll};
 
 use threadpool::{Builder as ThreadPoolBuilder, ThreadPool as ThreadPoolImpl};
 
+mod pool;
+pub use self::pool::{BlockingPool, BlockingPoolBuilder, BlockingPoolMetrics};
 use crate::{
     task::{new_task, JoinHandle},
     utils::thread_id::DEFAULT_THREAD_ID,
 };
 
 /// Users may implement a ThreadPool and attach it to runtime.
-/// We also provide an implementation based on threadpool crate, you can use DefaultThreadPool.
+/// We also provide [`BlockingPool`], and an implementation based on threadpool crate,
+/// DefaultThreadPool.
 pub trait ThreadPool {
     /// Monoio runtime will call `schedule_task` on `spawn_blocking`.
     /// ThreadPool impl must execute it now or later.
     fn schedule_task(&self, task: BlockingTask);
 }
 
-/// Error on waiting blocking task.
+/// Error on waiting blocking or remote task.
+///
+/// More variants may be added, matches on it need a wildcard arm. This is a
+/// breaking change from the versions where `Canceled` was the only variant:
+/// exhaustive matches written against them no longer compile.
 #[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
 pub enum JoinError {
     /// Task is canceled.
     Canceled,
+    /// Task is rejected by the thread pool, e.g. because its queue is full.
+    Rejected,
 }
 
 /// BlockingTask is contrusted by monoio, ThreadPool impl
@@ -34,29 +44,37 @@ pub struct BlockingTask {
 unsafe impl Send for BlockingTask {}
 
 struct BlockingTaskVtable {
-    pub(crate) drop: unsafe fn(&mut crate::task::Task<NoopScheduler>),
+    pub(crate) finish: unsafe fn(&mut crate::task::Task<NoopScheduler>, JoinError),
 }
 
 fn blocking_vtable<V>() -> &'static BlockingTaskVtable {
     &BlockingTaskVtable {
-        drop: blocking_task_drop::<V>,
+        finish: blocking_task_finish::<V>,
     }
 }
 
-fn blocking_task_drop<V>(task: &mut crate::task::Task<NoopScheduler>) {
-    let mut opt: Option<Result<V, JoinError>> = Some(Err(JoinError::Canceled));
+fn blocking_task_finish<V>(task: &mut crate::task::Task<NoopScheduler>, err: JoinError) {
+    let mut opt: Option<Result<V, JoinError>> = Some(Err(err));
     unsafe { task.finish((&mut opt) as *mut _ as *mut ()) };
 }
 
 impl Drop for BlockingTask {
     fn drop(&mut self) {
         if let Some(task) = self.task.as_mut() {
-            unsafe { (self.blocking_vtable.drop)(task) };
+            unsafe { (self.blocking_vtable.finish)(task, JoinError::Canceled) };
         }
     }
 }
 
 impl BlockingTask {
+    /// Reject task without running it, its handle resolves to
+    /// [`JoinError::Rejected`].
+    pub fn reject(mut self) {
+        if let Some(mut task) = self.task.take() {
+            unsafe { (self.blocking_vtable.finish)(&mut task, JoinError::Rejected) };
+        }
+    }
+
     /// Run task.
     #[inline]
     pub fn run(mut self) {
@@ -0,0 +1,339 @@
+//! Built-in blocking thread pool.
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    time::Duration,
+};
+
+use super::{BlockingTask, ThreadPool};
+
+const DEFAULT_MAX_THREADS: usize = 512;
+const DEFAULT_KEEP_ALIVE: Duration = Duration::from_secs(10);
+const DEFAULT_THREAD_NAME: &str = "snowfallio-blocking";
+
+/// A thread pool executing `spawn_blocking` tasks.
+///
+/// The pool keeps at least `min_threads` threads alive and spawns more on
+/// demand, up to `max_threads`. Threads above the minimum exit after being
+/// idle for the keep-alive duration. Tasks that can not be started right away
+/// wait in a queue, which may be bounded: when it is full, new tasks are
+/// rejected and their handle resolves to [`JoinError::Rejected`].
+///
+/// The pool is cheap to clone and can be shared by many runtimes. It shuts
+/// down once every clone is dropped, after running the queued tasks.
+///
+/// [`JoinError::Rejected`]: super::JoinError::Rejected
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use snowfallio::blocking::BlockingPool;
+///
+/// let pool = BlockingPool::builder()
+///     .min_threads(2)
+///     .max_threads(16)
+///     .keep_alive(Duration::from_secs(30))
+///     .queue_capacity(1024)
+///     .thread_name("my-blocking")
+///     .build();
+///
+/// let mut rt = snowfallio::RuntimeBuilder::<snowfallio::IoUringDriver>::new()
+///     .with_blocking_pool(pool.clone())
+///     .build()
+///     .unwrap();
+/// rt.block_on(async {
+///     let n = snowfallio::spawn_blocking(|| 1 + 1).await.unwrap();
+///     assert_eq!(n, 2);
+/// });
+/// println!("{:?}", pool.metrics());
+/// ```
+#[derive(Clone)]
+pub struct BlockingPool {
+    handle: Arc<PoolHandle>,
+}
+
+/// Builder of a [`BlockingPool`].
+#[derive(Debug, Clone)]
+pub struct BlockingPoolBuilder {
+    min_threads: usize,
+    max_threads: usize,
+    keep_alive: Duration,
+    queue_capacity: Option<usize>,
+    thread_name: String,
+    stack_size: Option<usize>,
+}
+
+/// A snapshot of the state of a [`BlockingPool`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct BlockingPoolMetrics {
+    /// Number of live threads.
+    pub threads: usize,
+    /// Number of threads waiting for a task.
+    pub idle_threads: usize,
+    /// Number of tasks waiting in the queue.
+    pub queue_depth: usize,
+    /// Number of tasks run.
+    pub completed_tasks: u64,
+    /// Number of tasks rejected because the queue was full.
+    pub rejected_tasks: u64,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    condvar: Condvar,
+    config: BlockingPoolBuilder,
+}
+
+#[derive(Default)]
+struct State {
+    queue: VecDeque<BlockingTask>,
+    threads: usize,
+    idle_threads: usize,
+    // Idle threads that have been handed a task but have not woken yet.
+    notified: usize,
+    completed_tasks: u64,
+    rejected_tasks: u64,
+    shutdown: bool,
+}
+
+/// Shuts the pool down when the last `BlockingPool` is dropped. Worker threads
+/// only hold `Shared`.
+struct PoolHandle {
+    shared: Arc<Shared>,
+}
+
+impl Default for BlockingPoolBuilder {
+    fn default() -> Self {
+        Self {
+            min_threads: 0,
+            max_threads: DEFAULT_MAX_THREADS,
+            keep_alive: DEFAULT_KEEP_ALIVE,
+            queue_capacity: None,
+            thread_name: DEFAULT_THREAD_NAME.to_string(),
+            stack_size: None,
+        }
+    }
+}
+
+impl BlockingPoolBuilder {
+    /// Number of threads kept alive even when idle, `0` by default. They are
+    /// spawned when the pool is built.
+    #[must_use]
+    pub fn min_threads(mut self, min: usize) -> Self {
+        self.min_threads = min;
+        self
+    }
+
+    /// Maximum number of threads, `512` by default.
+    #[must_use]
+    pub fn max_threads(mut self, max: usize) -> Self {
+        self.max_threads = max;
+        self
+    }
+
+    /// How long a thread above the minimum stays idle before exiting, 10
+    /// seconds by default.
+    #[must_use]
+    pub fn keep_alive(mut self, keep_alive: Duration) -> Self {
+        self.keep_alive = keep_alive;
+        self
+    }
+
+    /// Bound the number of tasks waiting for a thread. The queue is unbounded
+    /// by default.
+    #[must_use]
+    pub fn queue_capacity(mut self, capacity: usize) -> Self {
+        self.queue_capacity = Some(capacity);
+        self
+    }
+
+    /// Name of the pool threads, `snowfallio-blocking` by default.
+    #[must_use]
+    pub fn thread_name(mut self, name: impl Into<String>) -> Self {
+        self.thread_name = name.into();
+        self
+    }
+
+    /// Stack size of the pool threads, the std default if not set.
+    #[must_use]
+    pub fn stack_size(mut self, size: usize) -> Self {
+        self.stack_size = Some(size);
+        self
+    }
+
+    /// Build the pool and spawn its minimum threads.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_threads` is zero or smaller than `min_threads`, or if a
+    /// thread can not be spawned.
+    pub fn build(self) -> BlockingPool {
+        assert!(
+            self.max_threads > 0 && self.max_threads >= self.min_threads,
+            "max_threads must be greater than zero and min_threads"
+        );
+        let min_threads = self.min_threads;
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State::default()),
+            condvar: Condvar::new(),
+            config: self,
+        });
+        {
+            let mut state = shared.lock();
+            for _ in 0..min_threads {
+                shared.spawn_thread(&mut state);
+            }
+        }
+        BlockingPool {
+            handle: Arc::new(PoolHandle { shared }),
+        }
+    }
+}
+
+impl BlockingPool {
+    /// Create a pool with `min` to `max` threads and the default settings.
+    pub fn new(min: usize, max: usize) -> Self {
+        Self::builder().min_threads(min).max_threads(max).build()
+    }
+
+    /// Create a [`BlockingPoolBuilder`].
+    pub fn builder() -> BlockingPoolBuilder {
+        BlockingPoolBuilder::default()
+    }
+
+    /// Take a snapshot of the pool state.
+    pub fn metrics(&self) -> BlockingPoolMetrics {
+        let state = self.handle.shared.lock();
+        BlockingPoolMetrics {
+            threads: state.threads,
+            idle_threads: state.idle_threads,
+            queue_depth: state.queue.len(),
+            completed_tasks: state.completed_tasks,
+            rejected_tasks: state.rejected_tasks,
+        }
+    }
+}
+
+impl ThreadPool for BlockingPool {
+    fn schedule_task(&self, task: BlockingTask) {
+        let shared = &self.handle.shared;
+        let mut state = shared.lock();
+        if let Some(capacity) = shared.config.queue_capacity {
+            let can_start =
+                state.idle_threads > state.notified || state.threads < shared.config.max_threads;
+            // Tasks handed to idle threads do not count as waiting.
+            let waiting = state.queue.len().saturating_sub(state.notified);
+            if !can_start && waiting >= capacity {
+                state.rejected_tasks += 1;
+                drop(state);
+                task.reject();
+                return;
+            }
+        }
+
+        state.queue.push_back(task);
+        if state.idle_threads > state.notified {
+            state.notified += 1;
+            shared.condvar.notify_one();
+        } else if state.threads < shared.config.max_threads {
+            shared.spawn_thread(&mut state);
+        }
+    }
+}
+
+impl std::fmt::Debug for BlockingPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("BlockingPool")
+            .field(&self.metrics())
+            .finish()
+    }
+}
+
+impl Drop for PoolHandle {
+    fn drop(&mut self) {
+        self.shared.lock().shutdown = true;
+        self.shared.condvar.notify_all();
+    }
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .expect("Unable to lock blocking pool, which is unexpected")
+    }
+
+    fn spawn_thread(self: &Arc<Self>, state: &mut State) {
+        let mut builder = std::thread::Builder::new().name(self.config.thread_name.clone());
+        if let Some(size) = self.config.stack_size {
+            builder = builder.stack_size(size);
+        }
+        let shared = self.clone();
+        builder
+            .spawn(move || shared.run())
+            .expect("Unable to spawn blocking thread");
+        state.threads += 1;
+    }
+
+    fn run(self: &Arc<Self>) {
+        let mut worker = Worker {
+            shared: self,
+            running: false,
+        };
+        let mut state = self.lock();
+        loop {
+            if let Some(task) = state.queue.pop_front() {
+                drop(state);
+                worker.running = true;
+                task.run();
+                worker.running = false;
+                state = self.lock();
+                state.completed_tasks += 1;
+                continue;
+            }
+            if state.shutdown {
+                break;
+            }
+
+            state.idle_threads += 1;
+            let (mut guard, timeout) = self
+                .condvar
+                .wait_timeout(state, self.config.keep_alive)
+                .expect("Unable to lock blocking pool, which is unexpected");
+            guard.idle_threads -= 1;
+            if guard.notified > 0 {
+                guard.notified -= 1;
+            } else if timeout.timed_out() && guard.threads > self.config.min_threads {
+                state = guard;
+                break;
+            }
+            state = guard;
+        }
+        state.threads -= 1;
+    }
+}
+
+/// Accounts for a worker thread unwinding out of a panicking task, and
+/// replaces it if tasks are still queued.
+struct Worker<'a> {
+    shared: &'a Arc<Shared>,
+    running: bool,
+}
+
+impl Drop for Worker<'_> {
+    fn drop(&mut self) {
+        if !self.running {
+            return;
+        }
+        let mut state = self.shared.lock();
+        state.threads -= 1;
+        state.completed_tasks += 1;
+        if !state.queue.is_empty() && state.threads < self.shared.config.max_threads {
+            self.shared.spawn_thread(&mut state);
+        }
+    }
+}
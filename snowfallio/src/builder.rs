@@ -1,7 +1,11 @@
-use std::{io, marker::PhantomData};
+use std::{io, marker::PhantomData, time::Duration};
 
 use crate::{
     driver::{Driver, IoUringDriver},
+    metrics::Metrics,
+    runtime::Hooks,
+    scheduler::{PriorityWeights, DEFAULT_PRIORITY_WEIGHTS},
+    task::alloc::TaskCache,
     time::{driver::TimeDriver, Clock},
     utils::thread_id::gen_id,
     Runtime,
@@ -16,15 +20,53 @@ pub struct RuntimeBuilder<D> {
 
     urb: io_uring::Builder,
 
+    // task queue weights per priority
+    priority_weights: PriorityWeights,
+
+    // record task poll durations
+    poll_time_histogram: bool,
+
+    // register live tasks for dumps
+    task_dump: bool,
+
+    // freed task allocations kept per size class
+    task_cache_capacity: usize,
+
+    // lifecycle callbacks
+    hooks: Hooks,
+
+    // busy poll the driver before parking
+    spin_before_park: Option<Duration>,
+
     // blocking handle
     #[cfg(feature = "sync")]
     blocking_handle: crate::blocking::BlockingHandle,
     // driver mark
-    _mark: PhantomData<D>,
+    _mark: PhantomData<fn() -> D>,
 }
 
 scoped_thread_local!(pub(crate) static BUILD_THREAD_ID: usize);
 
+impl<T> Clone for RuntimeBuilder<T> {
+    /// Clone the builder. Runtimes built from the clones share the blocking
+    /// thread pool.
+    fn clone(&self) -> Self {
+        Self {
+            entries: self.entries,
+            urb: self.urb.clone(),
+            priority_weights: self.priority_weights,
+            poll_time_histogram: self.poll_time_histogram,
+            task_dump: self.task_dump,
+            task_cache_capacity: self.task_cache_capacity,
+            hooks: self.hooks.clone(),
+            spin_before_park: self.spin_before_park,
+            #[cfg(feature = "sync")]
+            blocking_handle: self.blocking_handle.clone(),
+            _mark: PhantomData,
+        }
+    }
+}
+
 impl<T> Default for RuntimeBuilder<T> {
     /// Create a default runtime builder
     #[must_use]
@@ -42,6 +84,13 @@ impl<T> RuntimeBuilder<T> {
 
             urb: io_uring::IoUring::builder(),
 
+            priority_weights: DEFAULT_PRIORITY_WEIGHTS,
+            poll_time_histogram: false,
+            task_dump: false,
+            task_cache_capacity: 0,
+            hooks: Hooks::default(),
+            spin_before_park: None,
+
             #[cfg(feature = "sync")]
             blocking_handle: crate::blocking::BlockingStrategy::Panic.into(),
             _mark: PhantomData,
@@ -82,14 +131,33 @@ impl Buildable for IoUringDriver {
 
         BUILD_THREAD_ID.set(&thread_id, || {
             let driver = match this.entries {
-                Some(entries) => IoUringDriver::new_with_entries(&this.urb, entries)?,
-                None => IoUringDriver::new(&this.urb)?,
+                Some(entries) => IoUringDriver::new_with_entries(&this.urb, entries),
+                None => IoUringDriver::new(&this.urb),
             };
+            // The driver releases the thread id when dropped.
             #[cfg(feature = "sync")]
-            let context = crate::runtime::Context::new(blocking_handle);
+            if driver.is_err() {
+                crate::driver::thread::release_id(thread_id);
+            }
+            let driver = driver?;
+            #[cfg(feature = "sync")]
+            let mut context = crate::runtime::Context::new(blocking_handle);
             #[cfg(not(feature = "sync"))]
-            let context = crate::runtime::Context::new();
-            Ok(Runtime { driver, context })
+            let mut context = crate::runtime::Context::new();
+            context.tasks.set_weights(this.priority_weights);
+            context.metrics = Metrics::new(this.poll_time_histogram);
+            if this.task_dump {
+                context.task_registry = Some(crate::task::dump::registry());
+            }
+            if this.task_cache_capacity > 0 {
+                context.task_cache = Some(TaskCache::new(this.task_cache_capacity));
+            }
+            Ok(Runtime {
+                driver,
+                context: std::rc::Rc::new(context),
+                hooks: this.hooks.clone(),
+                spin_before_park: this.spin_before_park,
+            })
         })
     }
 }
@@ -119,6 +187,123 @@ impl<D> RuntimeBuilder<D> {
         self.urb = urb.clone();
         self
     }
+
+    /// Set how many tasks are polled from each [`Priority`] queue before the
+    /// runtime moves on to the next one. The default weights are `8`, `4` and
+    /// `1` for high, normal and background tasks.
+    ///
+    /// Queues without ready tasks are skipped, so a lone background task is
+    /// never starved. Higher priority work arriving while a lower queue is
+    /// drained restarts the cycle at most once, so every queue with ready tasks
+    /// still runs in each cycle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the weights is zero.
+    ///
+    /// [`Priority`]: crate::task::Priority
+    #[must_use]
+    pub fn with_priority_weights(mut self, high: u32, normal: u32, background: u32) -> Self {
+        assert!(
+            high > 0 && normal > 0 && background > 0,
+            "priority weights must be greater than zero"
+        );
+        self.priority_weights = [high, normal, background];
+        self
+    }
+
+    /// Enable the histogram of task poll durations in the runtime metrics.
+    ///
+    /// This reads the clock twice per poll, so it is disabled by default.
+    #[must_use]
+    pub fn enable_poll_time_histogram(mut self) -> Self {
+        self.poll_time_histogram = true;
+        self
+    }
+
+    /// Register every spawned task so it can be listed with
+    /// [`runtime::dump`](crate::runtime::dump).
+    ///
+    /// Each poll is timestamped, so this is meant for debugging.
+    #[must_use]
+    pub fn enable_task_dump(mut self) -> Self {
+        self.task_dump = true;
+        self
+    }
+
+    /// Recycle the memory of completed tasks.
+    ///
+    /// Up to `capacity` freed task allocations are kept per size class and
+    /// reused by the next spawned tasks of a similar size, saving a call to
+    /// the global allocator per spawn. Futures of up to 8 KiB are recycled.
+    /// The cache is disabled by default.
+    #[must_use]
+    pub fn task_cache_capacity(mut self, capacity: usize) -> Self {
+        self.task_cache_capacity = capacity;
+        self
+    }
+
+    /// Busy poll the driver for up to `duration` before parking.
+    ///
+    /// When it runs out of tasks, the runtime keeps submitting and reaping
+    /// completions without waiting, and only parks if nothing became ready
+    /// during `duration`. This trades a fully busy core for lower wakeup
+    /// latency. The wakeups are counted in [`RuntimeMetrics::spin_wakeups`]
+    /// and [`RuntimeMetrics::parks`].
+    ///
+    /// [`RuntimeMetrics::spin_wakeups`]: crate::metrics::RuntimeMetrics::spin_wakeups
+    /// [`RuntimeMetrics::parks`]: crate::metrics::RuntimeMetrics::parks
+    #[must_use]
+    pub fn spin_before_park(mut self, duration: Duration) -> Self {
+        self.spin_before_park = Some(duration);
+        self
+    }
+
+    /// Set a callback invoked on the runtime thread when
+    /// [`Runtime::block_on`] starts, e.g. to set up per-thread state.
+    #[must_use]
+    pub fn on_thread_start<F>(mut self, f: F) -> Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.hooks.on_thread_start = Some(std::sync::Arc::new(f));
+        self
+    }
+
+    /// Set a callback invoked on the runtime thread when
+    /// [`Runtime::block_on`] returns or unwinds.
+    #[must_use]
+    pub fn on_thread_stop<F>(mut self, f: F) -> Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.hooks.on_thread_stop = Some(std::sync::Arc::new(f));
+        self
+    }
+
+    /// Set a callback invoked right before the runtime parks to wait for io,
+    /// timers or remote wakeups.
+    ///
+    /// The callback runs inside the runtime, so it may spawn or wake tasks, in
+    /// which case the runtime runs them instead of parking.
+    #[must_use]
+    pub fn before_park<F>(mut self, f: F) -> Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.hooks.before_park = Some(std::sync::Arc::new(f));
+        self
+    }
+
+    /// Set a callback invoked right after the runtime unparks.
+    #[must_use]
+    pub fn after_unpark<F>(mut self, f: F) -> Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.hooks.after_unpark = Some(std::sync::Arc::new(f));
+        self
+    }
 }
 
 // ===== enable_timer related =====
@@ -137,19 +322,31 @@ where
         let Runtime {
             driver,
             mut context,
+            hooks,
+            spin_before_park,
         } = Buildable::build(&RuntimeBuilder::<D> {
             entries: this.entries,
             urb: this.urb.clone(),
+            priority_weights: this.priority_weights,
+            poll_time_histogram: this.poll_time_histogram,
+            task_dump: this.task_dump,
+            task_cache_capacity: this.task_cache_capacity,
+            hooks: this.hooks.clone(),
+            spin_before_park: this.spin_before_park,
             #[cfg(feature = "sync")]
             blocking_handle: this.blocking_handle.clone(),
             _mark: PhantomData,
         })?;
 
         let timer_driver = TimeDriver::new(driver, Clock::new());
-        context.time_handle = Some(timer_driver.handle.clone());
+        std::rc::Rc::get_mut(&mut context)
+            .expect("runtime context is not shared before the runtime is built")
+            .time_handle = Some(timer_driver.handle.clone());
         Ok(Runtime {
             driver: timer_driver,
             context,
+            hooks,
+            spin_before_park,
         })
     }
 }
@@ -167,6 +364,12 @@ impl<D: time_wrap::TimeWrapable> RuntimeBuilder<D> {
         let Self {
             entries,
             urb,
+            priority_weights,
+            poll_time_histogram,
+            task_dump,
+            task_cache_capacity,
+            hooks,
+            spin_before_park,
             #[cfg(feature = "sync")]
             blocking_handle,
             ..
@@ -174,6 +377,12 @@ impl<D: time_wrap::TimeWrapable> RuntimeBuilder<D> {
         RuntimeBuilder {
             entries,
             urb,
+            priority_weights,
+            poll_time_histogram,
+            task_dump,
+            task_cache_capacity,
+            hooks,
+            spin_before_park,
             #[cfg(feature = "sync")]
             blocking_handle,
             _mark: PhantomData,
@@ -192,6 +401,29 @@ impl<D: time_wrap::TimeWrapable> RuntimeBuilder<D> {
         self
     }
 
+    /// Attach a [`BlockingPool`] with `min` to `max` threads and the default
+    /// settings, this will overwrite blocking strategy.
+    ///
+    /// The pool is shared by every runtime built from this builder or its
+    /// clones. Use [`with_blocking_pool`](Self::with_blocking_pool) to
+    /// configure it further.
+    ///
+    /// [`BlockingPool`]: crate::blocking::BlockingPool
+    #[cfg(feature = "sync")]
+    #[must_use]
+    pub fn blocking_threads(self, min: usize, max: usize) -> Self {
+        self.with_blocking_pool(crate::blocking::BlockingPool::new(min, max))
+    }
+
+    /// Attach a [`BlockingPool`], this will overwrite blocking strategy.
+    ///
+    /// [`BlockingPool`]: crate::blocking::BlockingPool
+    #[cfg(feature = "sync")]
+    #[must_use]
+    pub fn with_blocking_pool(self, pool: crate::blocking::BlockingPool) -> Self {
+        self.attach_thread_pool(std::sync::Arc::new(pool))
+    }
+
     /// Set blocking strategy, this will overwrite thread pool setting.
     /// If `BlockingStrategy::Panic` is used, it will panic if `spawn_blocking` on this thread.
     /// If `BlockingStrategy::ExecuteLocal` is used, it will execute with current thread, and may
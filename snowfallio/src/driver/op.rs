@@ -10,6 +10,7 @@ use crate::driver;
 pub(crate) mod close;
 
 mod accept;
+mod cmsg;
 mod connect;
 mod fsync;
 mod open;
@@ -17,11 +18,15 @@ mod poll;
 mod read;
 mod recv;
 mod send;
+mod timeout;
 mod write;
 
 #[cfg(feature = "splice")]
 mod splice;
 
+pub(crate) use cmsg::{space as cmsg_space, ControlBuf};
+pub(crate) use timeout::Timeout;
+
 /// In-flight operation
 pub(crate) struct Op<T: 'static> {
     // Driver running the operation
@@ -30,6 +35,9 @@ pub(crate) struct Op<T: 'static> {
     // Operation index in the slab(useless for legacy)
     pub(super) index: usize,
 
+    // Submitted io_uring opcode
+    pub(super) opcode: u8,
+
     // Per-operation data
     pub(super) data: Option<T>,
 }
@@ -50,6 +58,9 @@ pub(crate) struct CompletionMeta {
 }
 
 pub(crate) trait OpAble {
+    /// Opcode of the entry built by `uring_op`.
+    const OPCODE: u8;
+
     fn uring_op(&mut self) -> io_uring::squeue::Entry;
 }
 
@@ -93,9 +104,22 @@ where
     type Output = Completion<T>;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let coop = ready!(crate::task::coop::poll_proceed(cx));
         let me = &mut *self;
         let data_mut = me.data.as_mut().expect("unexpected operation state");
-        let meta = ready!(me.driver.poll_op::<T>(data_mut, me.index, cx));
+        let meta = match me.driver.poll_op::<T>(data_mut, me.index, cx) {
+            Poll::Ready(meta) => meta,
+            Poll::Pending => {
+                let opcode = me.opcode;
+                crate::runtime::CURRENT.try_with(|maybe_ctx| {
+                    if let Some(registry) = maybe_ctx.and_then(|ctx| ctx.task_registry.as_ref()) {
+                        registry.record_op_wait(opcode);
+                    }
+                });
+                return Poll::Pending;
+            }
+        };
+        coop.made_progress();
 
         me.index = usize::MAX;
         let data = me.data.take().expect("unexpected operation state");
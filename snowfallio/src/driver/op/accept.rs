@@ -28,6 +28,8 @@ impl Op<Accept> {
 }
 
 impl OpAble for Accept {
+    const OPCODE: u8 = opcode::Accept::CODE;
+
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         opcode::Accept::new(
             types::Fd(self.fd.raw_fd()),
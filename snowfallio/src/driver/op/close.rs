@@ -16,6 +16,8 @@ impl Op<Close> {
 }
 
 impl OpAble for Close {
+    const OPCODE: u8 = opcode::Close::CODE;
+
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         opcode::Close::new(types::Fd(self.fd)).build()
     }
@@ -0,0 +1,114 @@
+//! Control message (ancillary data) buffers of `sendmsg` and `recvmsg`.
+
+use std::mem::size_of;
+
+const ALIGN: usize = size_of::<usize>();
+const HEADER_LEN: usize = align(size_of::<libc::cmsghdr>());
+
+const fn align(len: usize) -> usize {
+    (len + ALIGN - 1) & !(ALIGN - 1)
+}
+
+/// Bytes taken in a control buffer by a message of `len` data bytes, as
+/// `CMSG_SPACE`.
+pub(crate) const fn space(len: usize) -> usize {
+    HEADER_LEN + align(len)
+}
+
+/// A control message buffer, aligned for `cmsghdr`.
+///
+/// The storage lives on the heap, so a pointer to it stays valid while the
+/// buffer is moved along with its op.
+#[derive(Debug, Default)]
+pub(crate) struct ControlBuf {
+    storage: Vec<usize>,
+    len: usize,
+}
+
+impl ControlBuf {
+    /// Create a buffer to receive up to `capacity` bytes of control messages.
+    pub(crate) fn with_capacity(capacity: usize) -> ControlBuf {
+        let words = capacity.div_ceil(ALIGN);
+        ControlBuf {
+            storage: vec![0; words],
+            len: words * ALIGN,
+        }
+    }
+
+    /// Append a control message.
+    pub(crate) fn push(&mut self, level: libc::c_int, ty: libc::c_int, data: &[u8]) {
+        let start = self.len;
+        self.len += space(data.len());
+        self.storage.resize(self.len / ALIGN, 0);
+
+        let header = libc::cmsghdr {
+            cmsg_len: (HEADER_LEN + data.len()) as _,
+            cmsg_level: level,
+            cmsg_type: ty,
+        };
+        let bytes = self.bytes_mut();
+        // Safety: the header fits in the space reserved above, and `start` is
+        // aligned for `cmsghdr`.
+        unsafe {
+            std::ptr::write(bytes[start..].as_mut_ptr() as *mut libc::cmsghdr, header);
+        }
+        bytes[start + HEADER_LEN..start + HEADER_LEN + data.len()].copy_from_slice(data);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    pub(crate) fn as_mut_ptr(&mut self) -> *mut libc::c_void {
+        self.storage.as_mut_ptr() as *mut _
+    }
+
+    /// Set the length of the control messages written by the kernel.
+    pub(crate) fn set_len(&mut self, len: usize) {
+        self.len = len.min(self.storage.len() * ALIGN);
+    }
+
+    /// Iterate the messages as `(level, type, data)`.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (libc::c_int, libc::c_int, &[u8])> {
+        let bytes = &self.bytes()[..self.len];
+        let mut pos = 0;
+        std::iter::from_fn(move || {
+            if pos + HEADER_LEN > bytes.len() {
+                return None;
+            }
+            // Safety: `pos` is aligned for `cmsghdr`, which fits in the buffer.
+            let header = unsafe { std::ptr::read(bytes[pos..].as_ptr() as *const libc::cmsghdr) };
+            let len = header.cmsg_len as usize;
+            if len < HEADER_LEN || pos + len > bytes.len() {
+                return None;
+            }
+            let data = &bytes[pos + HEADER_LEN..pos + len];
+            pos += align(len);
+            Some((header.cmsg_level, header.cmsg_type, data))
+        })
+    }
+
+    fn bytes(&self) -> &[u8] {
+        // Safety: any initialized `usize` is valid as bytes.
+        unsafe {
+            std::slice::from_raw_parts(
+                self.storage.as_ptr() as *const u8,
+                self.storage.len() * ALIGN,
+            )
+        }
+    }
+
+    fn bytes_mut(&mut self) -> &mut [u8] {
+        // Safety: any initialized `usize` is valid as bytes.
+        unsafe {
+            std::slice::from_raw_parts_mut(
+                self.storage.as_mut_ptr() as *mut u8,
+                self.storage.len() * ALIGN,
+            )
+        }
+    }
+}
@@ -23,6 +23,8 @@ impl Op<Connect> {
 }
 
 impl OpAble for Connect {
+    const OPCODE: u8 = opcode::Connect::CODE;
+
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         opcode::Connect::new(
             types::Fd(self.fd.raw_fd()),
@@ -55,6 +57,8 @@ impl Op<ConnectUnix> {
 }
 
 impl OpAble for ConnectUnix {
+    const OPCODE: u8 = opcode::Connect::CODE;
+
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         opcode::Connect::new(
             types::Fd(self.fd.raw_fd()),
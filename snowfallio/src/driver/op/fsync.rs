@@ -27,6 +27,8 @@ impl Op<Fsync> {
 }
 
 impl OpAble for Fsync {
+    const OPCODE: u8 = opcode::Fsync::CODE;
+
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         let mut opc = opcode::Fsync::new(types::Fd(self.fd.raw_fd()));
         if self.data_sync {
@@ -28,6 +28,8 @@ impl Op<Open> {
 }
 
 impl OpAble for Open {
+    const OPCODE: u8 = opcode::OpenAt::CODE;
+
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         opcode::OpenAt::new(types::Fd(libc::AT_FDCWD), self.path.as_c_str().as_ptr())
             .flags(self.flags)
@@ -35,6 +35,8 @@ impl Op<PollAdd> {
 }
 
 impl OpAble for PollAdd {
+    const OPCODE: u8 = opcode::PollAdd::CODE;
+
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         opcode::PollAdd::new(
             types::Fd(self.fd.raw_fd()),
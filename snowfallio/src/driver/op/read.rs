@@ -49,6 +49,8 @@ impl<T: IoBufMut> Op<Read<T>> {
 }
 
 impl<T: IoBufMut> OpAble for Read<T> {
+    const OPCODE: u8 = opcode::Read::CODE;
+
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         opcode::Read::new(
             types::Fd(self.fd.raw_fd()),
@@ -91,6 +93,8 @@ impl<T: IoVecBufMut> Op<ReadVec<T>> {
 }
 
 impl<T: IoVecBufMut> OpAble for ReadVec<T> {
+    const OPCODE: u8 = opcode::Readv::CODE;
+
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         let ptr = self.buf_vec.write_iovec_ptr() as _;
         let len = self.buf_vec.write_iovec_len() as _;
@@ -6,7 +6,7 @@ use std::{
 
 use io_uring::{opcode, types};
 
-use super::{super::shared_fd::SharedFd, Op, OpAble};
+use super::{super::shared_fd::SharedFd, ControlBuf, Op, OpAble};
 use crate::{buf::IoBufMut, BufResult};
 
 pub(crate) struct Recv<T> {
@@ -48,6 +48,8 @@ impl<T: IoBufMut> Op<Recv<T>> {
 }
 
 impl<T: IoBufMut> OpAble for Recv<T> {
+    const OPCODE: u8 = opcode::Recv::CODE;
+
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         opcode::Recv::new(
             types::Fd(self.fd.raw_fd()),
@@ -71,10 +73,21 @@ pub(crate) struct RecvMsg<T> {
         [libc::iovec; 1],
         libc::msghdr,
     )>,
+    /// Receives the control messages, referenced by the msghdr.
+    control: ControlBuf,
 }
 
 impl<T: IoBufMut> Op<RecvMsg<T>> {
-    pub(crate) fn recv_msg(fd: SharedFd, mut buf: T) -> io::Result<Self> {
+    pub(crate) fn recv_msg(fd: SharedFd, buf: T) -> io::Result<Self> {
+        Self::recv_msg_control(fd, buf, ControlBuf::default())
+    }
+
+    /// Submit a recvmsg receiving control messages into `control`.
+    pub(crate) fn recv_msg_control(
+        fd: SharedFd,
+        mut buf: T,
+        mut control: ControlBuf,
+    ) -> io::Result<Self> {
         let iovec = [libc::iovec {
             iov_base: buf.write_ptr() as *mut _,
             iov_len: buf.bytes_total(),
@@ -89,14 +102,31 @@ impl<T: IoBufMut> Op<RecvMsg<T>> {
         info.2.msg_iovlen = 1;
         info.2.msg_name = &mut info.0 as *mut _ as *mut libc::c_void;
         info.2.msg_namelen = std::mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+        if !control.is_empty() {
+            info.2.msg_control = control.as_mut_ptr();
+            info.2.msg_controllen = control.len() as _;
+        }
 
-        Op::submit_with(RecvMsg { fd, buf, info })
+        Op::submit_with(RecvMsg {
+            fd,
+            buf,
+            info,
+            control,
+        })
     }
 
     pub(crate) async fn wait(self) -> BufResult<(usize, SocketAddr), T> {
+        let (res, buf, _) = self.wait_control().await;
+        (res, buf)
+    }
+
+    /// Wait for the message, returning the control messages received along.
+    pub(crate) async fn wait_control(self) -> (io::Result<(usize, SocketAddr)>, T, ControlBuf) {
         let complete = self.await;
         let res = complete.meta.result.map(|v| v as _);
         let mut buf = complete.data.buf;
+        let mut control = complete.data.control;
+        control.set_len(complete.data.info.2.msg_controllen as _);
 
         let res = res.map(|n| {
             let storage = unsafe { complete.data.info.0.assume_init() };
@@ -137,11 +167,13 @@ impl<T: IoBufMut> Op<RecvMsg<T>> {
 
             (n, addr)
         });
-        (res, buf)
+        (res, buf, control)
     }
 }
 
 impl<T: IoBufMut> OpAble for RecvMsg<T> {
+    const OPCODE: u8 = opcode::RecvMsg::CODE;
+
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         opcode::RecvMsg::new(types::Fd(self.fd.raw_fd()), &mut self.info.2 as *mut _).build()
     }
@@ -3,7 +3,7 @@ use std::{io, net::SocketAddr};
 use io_uring::{opcode, types};
 use socket2::SockAddr;
 
-use super::{super::shared_fd::SharedFd, Op, OpAble};
+use super::{super::shared_fd::SharedFd, ControlBuf, Op, OpAble};
 use crate::{buf::IoBuf, BufResult};
 
 pub(crate) struct Send<T> {
@@ -35,6 +35,8 @@ impl<T: IoBuf> Op<Send<T>> {
 }
 
 impl<T: IoBuf> OpAble for Send<T> {
+    const OPCODE: u8 = opcode::Send::CODE;
+
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         #[cfg(feature = "zero-copy")]
         fn zero_copy_flag_guard<T: IoBuf>(buf: &T) -> libc::c_int {
@@ -79,6 +81,9 @@ pub(crate) struct SendMsg<T> {
     /// Reference to the in-flight buffer.
     pub(crate) buf: T,
     pub(crate) info: Box<(Option<SockAddr>, [libc::iovec; 1], libc::msghdr)>,
+    /// Control messages sent along, referenced by the msghdr.
+    #[allow(unused)]
+    control: ControlBuf,
 }
 
 impl<T: IoBuf> Op<SendMsg<T>> {
@@ -86,6 +91,16 @@ impl<T: IoBuf> Op<SendMsg<T>> {
         fd: SharedFd,
         buf: T,
         socket_addr: Option<SocketAddr>,
+    ) -> io::Result<Self> {
+        Self::send_msg_control(fd, buf, socket_addr, ControlBuf::default())
+    }
+
+    /// Submit a sendmsg with the control messages of `control`.
+    pub(crate) fn send_msg_control(
+        fd: SharedFd,
+        buf: T,
+        socket_addr: Option<SocketAddr>,
+        mut control: ControlBuf,
     ) -> io::Result<Self> {
         let iovec = [libc::iovec {
             iov_base: buf.read_ptr() as *const _ as *mut _,
@@ -110,7 +125,17 @@ impl<T: IoBuf> Op<SendMsg<T>> {
             }
         }
 
-        Op::submit_with(SendMsg { fd, buf, info })
+        if !control.is_empty() {
+            info.2.msg_control = control.as_mut_ptr();
+            info.2.msg_controllen = control.len() as _;
+        }
+
+        Op::submit_with(SendMsg {
+            fd,
+            buf,
+            info,
+            control,
+        })
     }
 
     pub(crate) async fn wait(self) -> BufResult<usize, T> {
@@ -122,6 +147,8 @@ impl<T: IoBuf> Op<SendMsg<T>> {
 }
 
 impl<T: IoBuf> OpAble for SendMsg<T> {
+    const OPCODE: u8 = opcode::SendMsg::CODE;
+
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         opcode::SendMsg::new(types::Fd(self.fd.raw_fd()), &mut self.info.2 as *mut _).build()
     }
@@ -52,6 +52,8 @@ impl Op<Splice> {
 }
 
 impl OpAble for Splice {
+    const OPCODE: u8 = opcode::Splice::CODE;
+
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         const FLAG: u32 = libc::SPLICE_F_MOVE;
         opcode::Splice::new(
@@ -0,0 +1,55 @@
+use std::{io, time::Duration};
+
+use io_uring::{opcode, types};
+
+use super::{super::util::timespec, Op, OpAble};
+
+// Clock selection flags, not exposed by the io-uring crate yet (kernel 5.15+).
+const IORING_TIMEOUT_BOOTTIME: u32 = 1 << 2;
+const IORING_TIMEOUT_REALTIME: u32 = 1 << 3;
+
+pub(crate) struct Timeout {
+    /// Boxed so the kernel always reads it at a stable address, even if the
+    /// op is dropped and its data moved before submission.
+    timespec: Box<types::Timespec>,
+    flags: u32,
+}
+
+impl Op<Timeout> {
+    /// Fires after `duration` measured on `CLOCK_MONOTONIC`.
+    pub(crate) fn timeout(duration: Duration) -> io::Result<Op<Timeout>> {
+        Op::submit_with(Timeout {
+            timespec: Box::new(timespec(duration)),
+            flags: 0,
+        })
+    }
+
+    /// Fires when `CLOCK_REALTIME` reaches `since_epoch`.
+    pub(crate) fn timeout_realtime(since_epoch: Duration) -> io::Result<Op<Timeout>> {
+        Op::submit_with(Timeout {
+            timespec: Box::new(timespec(since_epoch)),
+            flags: types::TimeoutFlags::ABS.bits() | IORING_TIMEOUT_REALTIME,
+        })
+    }
+
+    /// Fires after `duration` measured on `CLOCK_BOOTTIME`.
+    pub(crate) fn timeout_boottime(duration: Duration) -> io::Result<Op<Timeout>> {
+        Op::submit_with(Timeout {
+            timespec: Box::new(timespec(duration)),
+            flags: IORING_TIMEOUT_BOOTTIME,
+        })
+    }
+}
+
+impl OpAble for Timeout {
+    const OPCODE: u8 = opcode::Timeout::CODE;
+
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        // Safety: the flags are valid timeout flags of the running kernel, or
+        // the op fails with EINVAL.
+        let flags = unsafe { types::TimeoutFlags::from_bits_unchecked(self.flags) };
+        opcode::Timeout::new(&*self.timespec as *const types::Timespec)
+            .flags(flags)
+            .build()
+    }
+}
@@ -34,6 +34,8 @@ impl<T: IoBuf> Op<Write<T>> {
 }
 
 impl<T: IoBuf> OpAble for Write<T> {
+    const OPCODE: u8 = opcode::Write::CODE;
+
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         opcode::Write::new(
             types::Fd(self.fd.raw_fd()),
@@ -77,6 +79,8 @@ impl<T: IoVecBuf> Op<WriteVec<T>> {
 }
 
 impl<T: IoVecBuf> OpAble for WriteVec<T> {
+    const OPCODE: u8 = opcode::Writev::CODE;
+
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         let ptr = self.buf_vec.read_iovec_ptr() as *const _;
         let len = self.buf_vec.read_iovec_len() as _;
@@ -1,46 +1,216 @@
+//! Registry of the runtime threads, used to wake and spawn across threads.
+//!
+//! The registry is a fixed-size table of slots. A runtime reserves a free slot
+//! when it is built, and its thread id encodes both the slot index and the
+//! generation of the slot, which is bumped every time the slot is released.
+//! Looking up a thread is lock-free: the slot entry is only read if its
+//! generation still matches the id, so the id of a dropped runtime never
+//! resolves to the runtime that reuses its slot.
+//!
+//! A lookup returns the shared [`Registration`] of the thread, which callers
+//! may cache. It is marked dead when the runtime is dropped, so stale cached
+//! registrations are detected with [`Registration::is_alive`].
+
 use std::{
-    sync::{LazyLock, Mutex},
+    sync::{
+        atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering},
+        Arc,
+    },
     task::Waker,
 };
 
 use flume::Sender;
-use fxhash::FxHashMap;
 
 use crate::driver::UnparkHandle;
 
-static UNPARK: LazyLock<Mutex<FxHashMap<usize, UnparkHandle>>> =
-    LazyLock::new(|| Mutex::new(FxHashMap::default()));
+/// A job sent by another thread, run by the driver on park to spawn a task.
+pub(crate) type RemoteSpawn = Box<dyn FnOnce() + Send>;
+
+const SLOT_BITS: u32 = 12;
+const SLOTS: usize = 1 << SLOT_BITS;
+const SLOT_MASK: usize = SLOTS - 1;
+// Slot 0 holds the default thread id, the first ones are kept unused so that
+// thread ids are never below 16.
+const FIRST_SLOT: usize = 16;
 
-static WAKER_SENDER: LazyLock<Mutex<FxHashMap<usize, Sender<Waker>>>> =
-    LazyLock::new(|| Mutex::new(FxHashMap::default()));
+/// The handles of a registered runtime thread.
+pub(crate) struct Registration {
+    alive: AtomicBool,
+    pub(crate) unpark: UnparkHandle,
+    pub(crate) waker_sender: Sender<Waker>,
+    pub(crate) spawner: Sender<RemoteSpawn>,
+}
 
-macro_rules! lock {
-    ($x: ident) => {
-        $x.lock()
-            .expect("Unable to lock global map, which is unexpected")
+impl Registration {
+    pub(crate) fn new(
+        unpark: UnparkHandle,
+        waker_sender: Sender<Waker>,
+        spawner: Sender<RemoteSpawn>,
+    ) -> Self {
+        Self {
+            alive: AtomicBool::new(true),
+            unpark,
+            waker_sender,
+            spawner,
+        }
+    }
+
+    /// Returns false once the runtime has been dropped.
+    pub(crate) fn is_alive(&self) -> bool {
+        self.alive.load(Ordering::Acquire)
+    }
+}
+
+struct Slot {
+    // Generation of the slot shifted left by one, the lowest bit is set while
+    // a runtime owns the slot.
+    state: AtomicUsize,
+    // `Arc::into_raw` of the registration, or null.
+    entry: AtomicPtr<Registration>,
+    // Lookups in progress, the entry is not released while there are some.
+    readers: AtomicUsize,
+}
+
+impl Slot {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const EMPTY: Slot = Slot {
+        state: AtomicUsize::new(0),
+        entry: AtomicPtr::new(std::ptr::null_mut()),
+        readers: AtomicUsize::new(0),
     };
 }
 
-pub(crate) fn register_unpark_handle(id: usize, unpark: UnparkHandle) {
-    lock!(UNPARK).insert(id, unpark);
+static SLOTS_TABLE: [Slot; SLOTS] = [Slot::EMPTY; SLOTS];
+static NEXT_SLOT: AtomicUsize = AtomicUsize::new(0);
+
+fn slot_of(id: usize) -> &'static Slot {
+    &SLOTS_TABLE[id & SLOT_MASK]
+}
+
+fn id_of(index: usize, state: usize) -> usize {
+    ((state >> 1) << SLOT_BITS) | index
 }
 
-pub(crate) fn unregister_unpark_handle(id: usize) {
-    lock!(UNPARK).remove(&id);
+/// Reserve a free slot and return the thread id for it.
+///
+/// # Panics
+///
+/// Panics if all the slots are used by live runtimes.
+pub(crate) fn reserve_id() -> usize {
+    for _ in 0..SLOTS {
+        let index = NEXT_SLOT.fetch_add(1, Ordering::Relaxed) & SLOT_MASK;
+        if index < FIRST_SLOT {
+            continue;
+        }
+        let slot = &SLOTS_TABLE[index];
+        let state = slot.state.load(Ordering::Relaxed);
+        if state & 1 == 0
+            && slot
+                .state
+                .compare_exchange(state, state | 1, Ordering::AcqRel, Ordering::Relaxed)
+                .is_ok()
+        {
+            return id_of(index, state);
+        }
+    }
+    panic!(
+        "too many runtimes, at most {} can be alive",
+        SLOTS - FIRST_SLOT
+    );
 }
 
-pub(crate) fn get_unpark_handle(id: usize) -> Option<UnparkHandle> {
-    lock!(UNPARK).get(&id).cloned()
+/// Release the slot of a thread id that was never registered.
+pub(crate) fn release_id(id: usize) {
+    let slot = slot_of(id);
+    // Bump the generation and clear the owned bit.
+    slot.state.fetch_add(1, Ordering::AcqRel);
 }
 
-pub(crate) fn register_waker_sender(id: usize, sender: Sender<Waker>) {
-    lock!(WAKER_SENDER).insert(id, sender);
+pub(crate) fn register(id: usize, registration: Registration) {
+    let slot = slot_of(id);
+    debug_assert_eq!(
+        id_of(id & SLOT_MASK, slot.state.load(Ordering::Acquire)),
+        id
+    );
+    let entry = Arc::into_raw(Arc::new(registration)) as *mut Registration;
+    let old = slot.entry.swap(entry, Ordering::SeqCst);
+    debug_assert!(old.is_null(), "slot registered twice");
 }
 
-pub(crate) fn unregister_waker_sender(id: usize) {
-    lock!(WAKER_SENDER).remove(&id);
+/// Remove the registration of the thread, mark it dead and release its slot.
+pub(crate) fn unregister(id: usize) {
+    let slot = slot_of(id);
+    let entry = slot.entry.swap(std::ptr::null_mut(), Ordering::SeqCst);
+    // Wait for the lookups that may have loaded the entry before the swap.
+    while slot.readers.load(Ordering::SeqCst) != 0 {
+        std::hint::spin_loop();
+    }
+    if !entry.is_null() {
+        let registration = unsafe { Arc::from_raw(entry) };
+        registration.alive.store(false, Ordering::Release);
+    }
+    release_id(id);
+}
+
+/// Look up the registration of a live thread.
+pub(crate) fn lookup(id: usize) -> Option<Arc<Registration>> {
+    let slot = slot_of(id);
+    if id_of(id & SLOT_MASK, slot.state.load(Ordering::Acquire)) != id {
+        // Dropped runtime, or never registered.
+        return None;
+    }
+    slot.readers.fetch_add(1, Ordering::SeqCst);
+    let entry = slot.entry.load(Ordering::SeqCst);
+    let registration = if entry.is_null() {
+        None
+    } else {
+        // Safety: the entry can not be released while we are a reader.
+        unsafe {
+            Arc::increment_strong_count(entry);
+            Some(Arc::from_raw(entry))
+        }
+    };
+    slot.readers.fetch_sub(1, Ordering::SeqCst);
+    // The slot may have been reused between the generation check and the load.
+    registration.filter(|_| id_of(id & SLOT_MASK, slot.state.load(Ordering::Acquire)) == id)
 }
 
-pub(crate) fn get_waker_sender(id: usize) -> Option<Sender<Waker>> {
-    lock!(WAKER_SENDER).get(&id).cloned()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registration() -> Registration {
+        let (waker_sender, _) = flume::unbounded();
+        let (spawner, _) = flume::unbounded();
+        Registration::new(UnparkHandle(std::sync::Weak::new()), waker_sender, spawner)
+    }
+
+    #[test]
+    fn stale_ids_are_not_resolved() {
+        let id = reserve_id();
+        assert!(id >= FIRST_SLOT);
+        assert!(lookup(id).is_none());
+        register(id, registration());
+        let cached = lookup(id).unwrap();
+        assert!(cached.is_alive());
+
+        unregister(id);
+        assert!(!cached.is_alive());
+        assert!(lookup(id).is_none());
+
+        // A new id for the same slot gets a new generation.
+        let slot = id & SLOT_MASK;
+        let reused = loop {
+            let new = reserve_id();
+            if new & SLOT_MASK == slot {
+                break new;
+            }
+            release_id(new);
+        };
+        assert_ne!(reused, id);
+        register(reused, registration());
+        assert!(lookup(id).is_none());
+        assert!(lookup(reused).is_some());
+        unregister(reused);
+    }
 }
@@ -68,6 +68,10 @@ pub(crate) struct UringInner {
     // Waker receiver
     #[cfg(feature = "sync")]
     waker_receiver: flume::Receiver<std::task::Waker>,
+
+    // Remote spawn receiver
+    #[cfg(feature = "sync")]
+    spawn_receiver: flume::Receiver<super::thread::RemoteSpawn>,
 }
 
 // When dropping the driver, all in-flight operations must have completed. This
@@ -118,6 +122,7 @@ impl IoUringDriver {
         };
 
         let (waker_sender, waker_receiver) = flume::unbounded::<std::task::Waker>();
+        let (spawn_sender, spawn_receiver) = flume::unbounded();
 
         let inner = Rc::new(UnsafeCell::new(UringInner {
             ops: Ops::new(),
@@ -125,6 +130,7 @@ impl IoUringDriver {
             shared_waker: std::sync::Arc::new(waker::EventWaker::new(waker)),
             eventfd_installed: false,
             waker_receiver,
+            spawn_receiver,
         }));
 
         let thread_id = crate::builder::BUILD_THREAD_ID.with(|id| *id);
@@ -136,8 +142,10 @@ impl IoUringDriver {
         };
 
         // Register unpark handle
-        super::thread::register_unpark_handle(thread_id, driver.unpark().into());
-        super::thread::register_waker_sender(thread_id, waker_sender);
+        super::thread::register(
+            thread_id,
+            super::thread::Registration::new(driver.unpark(), waker_sender, spawn_sender),
+        );
         Ok(driver)
     }
 
@@ -188,11 +196,14 @@ impl IoUringDriver {
         #[allow(unused_mut)]
         let mut need_wait = true;
 
+        #[cfg(feature = "sync")]
+        let mut remote_wakes = 0;
+
         #[cfg(feature = "sync")]
         {
-            // Process foreign wakers
-            while let Ok(w) = inner.waker_receiver.try_recv() {
-                w.wake();
+            // Process foreign wakers and spawns
+            remote_wakes += inner.process_remote();
+            if remote_wakes != 0 {
                 need_wait = false;
             }
 
@@ -204,9 +215,10 @@ impl IoUringDriver {
                     .store(false, std::sync::atomic::Ordering::Release);
             }
 
-            // Process foreign wakers left
-            while let Ok(w) = inner.waker_receiver.try_recv() {
-                w.wake();
+            // Process foreign wakers and spawns left
+            let left = inner.process_remote();
+            if left != 0 {
+                remote_wakes += left;
                 need_wait = false;
             }
         }
@@ -253,10 +265,24 @@ impl IoUringDriver {
         // Process CQ
         inner.tick();
 
+        #[cfg(feature = "sync")]
+        report_remote_wakes(remote_wakes);
+
         Ok(())
     }
 }
 
+#[cfg(feature = "sync")]
+fn report_remote_wakes(n: u64) {
+    if n != 0 {
+        crate::runtime::CURRENT.try_with(|maybe_ctx| {
+            if let Some(ctx) = maybe_ctx {
+                ctx.metrics.remote_wakes(n);
+            }
+        });
+    }
+}
+
 impl Driver for IoUringDriver {
     /// Enter the driver context. This enables using uring types.
     fn with<R>(&self, f: impl FnOnce() -> R) -> R {
@@ -269,6 +295,12 @@ impl Driver for IoUringDriver {
         let inner = unsafe { &mut *self.inner.get() };
         inner.submit()?;
         inner.tick();
+
+        // The runtime is awake, so foreign threads do not signal the eventfd
+        // and only push to the channels. Process them here as well, otherwise
+        // a busy or spinning runtime would not see them until it parks.
+        #[cfg(feature = "sync")]
+        report_remote_wakes(inner.process_remote());
         Ok(())
     }
 
@@ -291,6 +323,22 @@ impl Driver for IoUringDriver {
 }
 
 impl UringInner {
+    /// Wake the tasks woken and run the spawns sent by other threads, returns
+    /// how many were processed.
+    #[cfg(feature = "sync")]
+    fn process_remote(&mut self) -> u64 {
+        let mut n = 0;
+        while let Ok(w) = self.waker_receiver.try_recv() {
+            w.wake();
+            n += 1;
+        }
+        while let Ok(spawn) = self.spawn_receiver.try_recv() {
+            spawn();
+            n += 1;
+        }
+        n
+    }
+
     fn tick(&mut self) {
         let mut cq = self.uring.completion();
         cq.sync();
@@ -332,6 +380,7 @@ impl UringInner {
         Op {
             driver,
             index: inner.ops.insert(),
+            opcode: 0,
             data: Some(data),
         }
     }
@@ -355,6 +404,7 @@ impl UringInner {
         // Configure the SQE
         let data_mut = unsafe { op.data.as_mut().unwrap_unchecked() };
         let sqe = OpAble::uring_op(data_mut).user_data(op.index as _);
+        op.opcode = T::OPCODE;
 
         {
             let mut sq = inner.uring.submission();
@@ -448,11 +498,7 @@ impl Drop for IoUringDriver {
 
         // Deregister thread id
         #[cfg(feature = "sync")]
-        {
-            use crate::driver::thread::{unregister_unpark_handle, unregister_waker_sender};
-            unregister_unpark_handle(self.thread_id);
-            unregister_waker_sender(self.thread_id);
-        }
+        crate::driver::thread::unregister(self.thread_id);
     }
 }
 
@@ -26,7 +26,7 @@ pub trait StreamExt: Stream {
 
     /// Runs this stream to completion, executing the provided asynchronous
     /// closure for each element on the stream.
-    fn for_each<Fut, F>(mut self, mut f: F) -> ForEachFut<Self, Fut, F>
+    fn for_each<Fut, F>(mut self, mut f: F) -> impl Future<Output = ()>
     where
         F: FnMut(Self::Item) -> Fut,
         Fut: Future<Output = ()>,
@@ -42,8 +42,6 @@ pub trait StreamExt: Stream {
 
 impl<T> StreamExt for T where T: Stream {}
 
-type ForEachFut<T: Stream, Fut: Future<Output = ()>, F: FnMut(<T as Stream>::Item) -> Fut> =
-    impl Future<Output = ()>;
 
 #[must_use = "streams do nothing unless polled"]
 pub struct Map<St, F> {
@@ -6,6 +6,7 @@ mod cancel;
 mod copy;
 mod prefixed_io;
 mod split;
+mod throttled;
 
 pub use buf_reader::BufReader;
 pub use buf_writer::BufWriter;
@@ -16,3 +17,4 @@ pub use copy::copy;
 pub use copy::zero_copy;
 pub use prefixed_io::PrefixedReadIo;
 pub use split::{OwnedReadHalf, OwnedWriteHalf, ReadHalf, Split, Splitable, WriteHalf};
+pub use throttled::Throttled;
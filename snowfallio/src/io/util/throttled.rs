@@ -0,0 +1,188 @@
+use std::rc::Rc;
+
+use crate::{
+    buf::{IoBuf, IoBufMut, IoVecBuf, IoVecBufMut, IoVecWrapper, IoVecWrapperMut, SliceMut},
+    io::{AsyncReadRent, AsyncWriteRent},
+    time::RateLimiter,
+};
+
+/// Wrapped IO with its throughput capped by [`RateLimiter`]s.
+///
+/// Every byte read or written takes a token of the limiter of its direction,
+/// so a limiter created with `RateLimiter::new(rate, burst)` caps the io to
+/// `rate` bytes per second. Reads and writes are cut to at most `burst` bytes.
+///
+/// Writes wait for their tokens before hitting the io. Reads can not know
+/// their size in advance: they wait for the debt of the previous reads to be
+/// repaid, then take the tokens of the bytes actually read.
+///
+/// The limiters can be shared, e.g. to cap all the connections of a peer
+/// together.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::rc::Rc;
+///
+/// use snowfallio::{
+///     io::{AsyncWriteRentExt, Throttled},
+///     net::TcpStream,
+///     time::RateLimiter,
+/// };
+///
+/// #[snowfallio::main(timer_enabled = true)]
+/// async fn main() {
+///     let stream = TcpStream::connect("127.0.0.1:8080").await.unwrap();
+///     // 1 MiB/s in each direction.
+///     let read = Rc::new(RateLimiter::new(1 << 20, 64 << 10));
+///     let write = Rc::new(RateLimiter::new(1 << 20, 64 << 10));
+///     let mut stream = Throttled::with_limiters(stream, Some(read), Some(write));
+///     let (res, _) = stream.write_all(vec![0; 4 << 20]).await;
+///     res.unwrap();
+/// }
+/// ```
+pub struct Throttled<I> {
+    io: I,
+    read: Option<Rc<RateLimiter>>,
+    write: Option<Rc<RateLimiter>>,
+}
+
+impl<I> Throttled<I> {
+    /// Create a Throttled io with one limiter shared by reads and writes.
+    pub fn new(io: I, limiter: Rc<RateLimiter>) -> Self {
+        Self {
+            io,
+            read: Some(limiter.clone()),
+            write: Some(limiter),
+        }
+    }
+
+    /// Create a Throttled io with a limiter per direction. `None` leaves the
+    /// direction unlimited.
+    pub fn with_limiters(
+        io: I,
+        read: Option<Rc<RateLimiter>>,
+        write: Option<Rc<RateLimiter>>,
+    ) -> Self {
+        Self { io, read, write }
+    }
+
+    /// Gets a reference to the underlying io.
+    pub fn get_ref(&self) -> &I {
+        &self.io
+    }
+
+    /// Gets a mutable reference to the underlying io.
+    pub fn get_mut(&mut self) -> &mut I {
+        &mut self.io
+    }
+
+    /// Into inner
+    pub fn into_inner(self) -> I {
+        self.io
+    }
+}
+
+fn chunk(limiter: &RateLimiter) -> usize {
+    usize::try_from(limiter.burst()).unwrap_or(usize::MAX)
+}
+
+impl<I: AsyncReadRent> AsyncReadRent for Throttled<I> {
+    type ReadFuture<'a, T> = impl std::future::Future<Output = crate::BufResult<usize, T>> + 'a
+    where
+        T: IoBufMut + 'a, Self: 'a;
+
+    type ReadvFuture<'a, T> = impl std::future::Future<Output = crate::BufResult<usize, T>> + 'a
+    where
+        T: IoVecBufMut + 'a, Self: 'a;
+
+    fn read<T: IoBufMut>(&mut self, mut buf: T) -> Self::ReadFuture<'_, T> {
+        async move {
+            let limiter = match &self.read {
+                Some(limiter) if buf.bytes_total() > 0 => limiter.clone(),
+                _ => return self.io.read(buf).await,
+            };
+            let n = buf.bytes_total().min(chunk(&limiter));
+
+            // Wait off the debt of the previous reads.
+            limiter.acquire(0).await;
+            // # Safety
+            // Begin is 0 and n is within the buffer capacity.
+            let slice = unsafe { SliceMut::new_unchecked(buf, 0, n) };
+            let (result, slice) = self.io.read(slice).await;
+            if let Ok(n) = result {
+                limiter.consume(n as u64);
+            }
+            (result, slice.into_inner())
+        }
+    }
+
+    fn readv<T: IoVecBufMut>(&mut self, mut buf: T) -> Self::ReadvFuture<'_, T> {
+        async move {
+            let slice = match IoVecWrapperMut::new(buf) {
+                Ok(slice) => slice,
+                Err(buf) => return (Ok(0), buf),
+            };
+
+            let (result, slice) = self.read(slice).await;
+            buf = slice.into_inner();
+            if let Ok(n) = result {
+                unsafe { buf.set_init(n) };
+            }
+            (result, buf)
+        }
+    }
+}
+
+impl<I: AsyncWriteRent> AsyncWriteRent for Throttled<I> {
+    type WriteFuture<'a, T> = impl std::future::Future<Output = crate::BufResult<usize, T>> + 'a
+    where
+        T: IoBuf + 'a, Self: 'a;
+
+    type WritevFuture<'a, T> = impl std::future::Future<Output = crate::BufResult<usize, T>> + 'a
+    where
+        T: IoVecBuf + 'a, Self: 'a;
+
+    type FlushFuture<'a> = I::FlushFuture<'a> where Self: 'a;
+
+    type ShutdownFuture<'a> = I::ShutdownFuture<'a> where Self: 'a;
+
+    fn write<T: IoBuf>(&mut self, buf: T) -> Self::WriteFuture<'_, T> {
+        async move {
+            let limiter = match &self.write {
+                Some(limiter) if buf.bytes_init() > 0 => limiter.clone(),
+                _ => return self.io.write(buf).await,
+            };
+            let n = buf.bytes_init().min(chunk(&limiter));
+
+            limiter.acquire(n as u64).await;
+            let (result, slice) = self.io.write(buf.slice(..n)).await;
+            // Give back the tokens of the bytes not written.
+            let written = *result.as_ref().unwrap_or(&0);
+            limiter.release((n - written.min(n)) as u64);
+            (result, slice.into_inner())
+        }
+    }
+
+    fn writev<T: IoVecBuf>(&mut self, buf_vec: T) -> Self::WritevFuture<'_, T> {
+        async move {
+            let slice = match IoVecWrapper::new(buf_vec) {
+                Ok(slice) => slice,
+                Err(buf_vec) => return (Ok(0), buf_vec),
+            };
+
+            let (result, slice) = self.write(slice).await;
+            (result, slice.into_inner())
+        }
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Self::FlushFuture<'_> {
+        self.io.flush()
+    }
+
+    #[inline]
+    fn shutdown(&mut self) -> Self::ShutdownFuture<'_> {
+        self.io.shutdown()
+    }
+}
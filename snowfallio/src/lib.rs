@@ -11,12 +11,14 @@
 #![allow(stable_features)]
 #![feature(generic_associated_types)]
 #![feature(type_alias_impl_trait)]
+#![feature(impl_trait_in_assoc_type)]
 #![feature(box_into_inner)]
 #![feature(new_uninit)]
 #![feature(io_error_more)]
 #![feature(stmt_expr_attributes)]
 #![feature(unboxed_closures)]
 #![feature(once_cell)]
+#![feature(min_specialization)]
 
 #[macro_use]
 pub mod macros;
@@ -26,7 +28,7 @@ pub use snowfallio_macros::select_priv_declare_output_enum;
 #[macro_use]
 mod driver;
 pub(crate) mod builder;
-pub(crate) mod runtime;
+pub mod runtime;
 mod scheduler;
 pub mod time;
 
@@ -38,7 +40,9 @@ pub mod blocking;
 pub mod buf;
 pub mod fs;
 pub mod io;
+pub mod metrics;
 pub mod net;
+pub mod sync;
 pub mod task;
 pub mod utils;
 
@@ -48,7 +52,9 @@ use std::future::Future;
 pub use blocking::spawn_blocking;
 pub use builder::{Buildable, RuntimeBuilder};
 pub use driver::{Driver, IoUringDriver};
-pub use runtime::{spawn, Runtime};
+#[cfg(feature = "sync")]
+pub use runtime::spawn_on;
+pub use runtime::{current_thread_id, spawn, spawn_with_priority, Handle, Runtime};
 #[cfg(feature = "macros")]
 pub use snowfallio_macros::{main, test};
 
@@ -0,0 +1,330 @@
+//! Runtime metrics.
+//!
+//! Every runtime keeps a set of counters describing its scheduler: how many
+//! tasks it owns, how deep its run queue is, how long it spends polling tasks
+//! versus parked in the driver, and how many tasks are woken from other
+//! threads.
+//!
+//! The counters are written only by the runtime thread. They can be read from
+//! the runtime thread with [`MetricsHandle::current`], or from any other thread
+//! through a [`MetricsHandle`] obtained with [`Runtime::metrics_handle`].
+//!
+//! [`Runtime::metrics_handle`]: crate::Runtime::metrics_handle
+//!
+//! # Examples
+//!
+//! ```no_run
+//! #[snowfallio::main]
+//! async fn main() {
+//!     snowfallio::spawn(async {}).await;
+//!     let metrics = snowfallio::metrics::MetricsHandle::current().snapshot();
+//!     println!("spawned {} tasks", metrics.spawned_tasks);
+//! }
+//! ```
+
+use std::{
+    cell::Cell,
+    ops::Range,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering::Relaxed},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// Number of buckets in the poll time histogram.
+const POLL_TIME_BUCKETS: usize = 16;
+
+/// Upper bound of the first poll time bucket, in nanoseconds. Each following
+/// bucket is twice as wide as the previous one.
+const POLL_TIME_FIRST_BUCKET_NANOS: u64 = 1_000;
+
+/// A point-in-time snapshot of a runtime's metrics.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct RuntimeMetrics {
+    /// Tasks that have been spawned but have not completed yet.
+    pub live_tasks: u64,
+    /// Total number of tasks spawned on the runtime.
+    pub spawned_tasks: u64,
+    /// Number of tasks in the run queues at the end of the last tick.
+    pub queue_depth: usize,
+    /// Number of scheduler ticks. A tick drains the run queues once and
+    /// polls the main future.
+    pub ticks: u64,
+    /// Total number of polls, including polls of the `block_on` future.
+    pub polls: u64,
+    /// Number of polls during the last tick.
+    pub last_tick_polls: u64,
+    /// Histogram of task poll durations. Empty unless enabled with
+    /// [`RuntimeBuilder::enable_poll_time_histogram`].
+    ///
+    /// [`RuntimeBuilder::enable_poll_time_histogram`]: crate::RuntimeBuilder::enable_poll_time_histogram
+    pub poll_time_histogram: PollTimeHistogram,
+    /// Time spent outside of `Driver::park`, running tasks and processing
+    /// completions.
+    pub busy_duration: Duration,
+    /// Time spent blocked in `Driver::park`.
+    pub park_duration: Duration,
+    /// Number of times the runtime parked, which is also the number of
+    /// wakeups from parking.
+    pub parks: u64,
+    /// Number of times spinning found work before the runtime had to park, see
+    /// [`RuntimeBuilder::spin_before_park`].
+    ///
+    /// [`RuntimeBuilder::spin_before_park`]: crate::RuntimeBuilder::spin_before_park
+    pub spin_wakeups: u64,
+    /// Number of tasks woken or spawned from other threads.
+    pub remote_wakes: u64,
+}
+
+impl RuntimeMetrics {
+    /// Average number of polls per tick.
+    pub fn polls_per_tick(&self) -> f64 {
+        if self.ticks == 0 {
+            return 0.0;
+        }
+        self.polls as f64 / self.ticks as f64
+    }
+
+    /// Fraction of time the runtime spent busy rather than parked, between
+    /// `0.0` and `1.0`.
+    pub fn busy_ratio(&self) -> f64 {
+        let total = self.busy_duration + self.park_duration;
+        if total.is_zero() {
+            return 0.0;
+        }
+        self.busy_duration.as_secs_f64() / total.as_secs_f64()
+    }
+}
+
+/// Histogram of task poll durations with exponentially growing buckets.
+///
+/// The first bucket counts polls shorter than 1µs, each following bucket is
+/// twice as wide as the previous one, and the last bucket is unbounded.
+#[derive(Debug, Clone, Default)]
+pub struct PollTimeHistogram {
+    buckets: Vec<u64>,
+}
+
+impl PollTimeHistogram {
+    /// Number of buckets, zero if the histogram is disabled.
+    pub fn num_buckets(&self) -> usize {
+        self.buckets.len()
+    }
+
+    /// Number of polls counted in the bucket.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bucket` is out of range.
+    pub fn bucket_count(&self, bucket: usize) -> u64 {
+        self.buckets[bucket]
+    }
+
+    /// Range of poll durations covered by the bucket.
+    pub fn bucket_range(&self, bucket: usize) -> Range<Duration> {
+        let start = match bucket {
+            0 => 0,
+            b => POLL_TIME_FIRST_BUCKET_NANOS << (b - 1),
+        };
+        let end = if bucket + 1 >= POLL_TIME_BUCKETS {
+            Duration::MAX
+        } else {
+            Duration::from_nanos(POLL_TIME_FIRST_BUCKET_NANOS << bucket)
+        };
+        Duration::from_nanos(start)..end
+    }
+
+    /// Iterate over the bucket counts.
+    pub fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+        self.buckets.iter().copied()
+    }
+}
+
+/// A `Send` and `Sync` handle to read the metrics of a runtime, possibly from
+/// another thread.
+#[derive(Clone)]
+pub struct MetricsHandle {
+    shared: Arc<Shared>,
+}
+
+impl MetricsHandle {
+    /// Returns the metrics handle of the runtime running on the current
+    /// thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a snowfallio runtime.
+    pub fn current() -> Self {
+        crate::runtime::CURRENT.with(|ctx| ctx.metrics.handle())
+    }
+
+    /// Take a snapshot of the metrics.
+    pub fn snapshot(&self) -> RuntimeMetrics {
+        let s = &self.shared;
+        let spawned_tasks = s.spawned.load(Relaxed);
+        RuntimeMetrics {
+            live_tasks: spawned_tasks.saturating_sub(s.completed.load(Relaxed)),
+            spawned_tasks,
+            queue_depth: s.queue_depth.load(Relaxed),
+            ticks: s.ticks.load(Relaxed),
+            polls: s.polls.load(Relaxed),
+            last_tick_polls: s.last_tick_polls.load(Relaxed),
+            poll_time_histogram: PollTimeHistogram {
+                buckets: s.poll_time.iter().map(|b| b.load(Relaxed)).collect(),
+            },
+            busy_duration: Duration::from_nanos(s.busy_nanos.load(Relaxed)),
+            park_duration: Duration::from_nanos(s.park_nanos.load(Relaxed)),
+            parks: s.parks.load(Relaxed),
+            spin_wakeups: s.spin_wakeups.load(Relaxed),
+            remote_wakes: s.remote_wakes.load(Relaxed),
+        }
+    }
+}
+
+impl std::fmt::Debug for MetricsHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("MetricsHandle")
+            .field(&self.snapshot())
+            .finish()
+    }
+}
+
+#[derive(Default)]
+struct Shared {
+    spawned: AtomicU64,
+    completed: AtomicU64,
+    queue_depth: AtomicUsize,
+    ticks: AtomicU64,
+    polls: AtomicU64,
+    last_tick_polls: AtomicU64,
+    poll_time: Box<[AtomicU64]>,
+    busy_nanos: AtomicU64,
+    park_nanos: AtomicU64,
+    parks: AtomicU64,
+    spin_wakeups: AtomicU64,
+    remote_wakes: AtomicU64,
+}
+
+/// Metrics collector owned by the runtime context.
+///
+/// All counters have a single writer, the runtime thread, so they are updated
+/// with plain loads and stores instead of read-modify-write operations.
+pub(crate) struct Metrics {
+    shared: Arc<Shared>,
+    poll_time_enabled: bool,
+    tick_polls: Cell<u64>,
+    last_unpark: Cell<Option<Instant>>,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+#[inline]
+fn add(counter: &AtomicU64, n: u64) {
+    counter.store(counter.load(Relaxed).wrapping_add(n), Relaxed);
+}
+
+impl Metrics {
+    pub(crate) fn new(poll_time_histogram: bool) -> Self {
+        let poll_time = if poll_time_histogram {
+            (0..POLL_TIME_BUCKETS).map(|_| AtomicU64::new(0)).collect()
+        } else {
+            Box::default()
+        };
+        Self {
+            shared: Arc::new(Shared {
+                poll_time,
+                ..Default::default()
+            }),
+            poll_time_enabled: poll_time_histogram,
+            tick_polls: Cell::new(0),
+            last_unpark: Cell::new(None),
+        }
+    }
+
+    pub(crate) fn handle(&self) -> MetricsHandle {
+        MetricsHandle {
+            shared: self.shared.clone(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn task_spawned(&self) {
+        add(&self.shared.spawned, 1);
+    }
+
+    #[inline]
+    pub(crate) fn task_completed(&self) {
+        add(&self.shared.completed, 1);
+    }
+
+    #[allow(unused)]
+    #[inline]
+    pub(crate) fn remote_wakes(&self, n: u64) {
+        add(&self.shared.remote_wakes, n);
+    }
+
+    /// Run a poll, counting it and recording its duration if enabled.
+    #[inline]
+    pub(crate) fn poll<R>(&self, f: impl FnOnce() -> R) -> R {
+        self.tick_polls.set(self.tick_polls.get() + 1);
+        add(&self.shared.polls, 1);
+        if !self.poll_time_enabled {
+            return f();
+        }
+
+        let begin = Instant::now();
+        let r = f();
+        let nanos = begin.elapsed().as_nanos() as u64;
+        let bucket = (u64::BITS - (nanos / POLL_TIME_FIRST_BUCKET_NANOS).leading_zeros()) as usize;
+        add(&self.shared.poll_time[bucket.min(POLL_TIME_BUCKETS - 1)], 1);
+        r
+    }
+
+    /// Called at the end of every scheduler tick.
+    #[inline]
+    pub(crate) fn end_tick(&self, queue_depth: usize) {
+        let polls = self.tick_polls.replace(0);
+        add(&self.shared.ticks, 1);
+        self.shared.last_tick_polls.store(polls, Relaxed);
+        self.shared.queue_depth.store(queue_depth, Relaxed);
+    }
+
+    /// Run a park of the driver, accounting the time spent since the last
+    /// park as busy.
+    pub(crate) fn park<R>(&self, f: impl FnOnce() -> R) -> R {
+        let begin = Instant::now();
+        if let Some(last) = self.last_unpark.get() {
+            add(&self.shared.busy_nanos, (begin - last).as_nanos() as u64);
+        }
+        let r = f();
+        let end = Instant::now();
+        add(&self.shared.park_nanos, (end - begin).as_nanos() as u64);
+        add(&self.shared.parks, 1);
+        self.last_unpark.set(Some(end));
+        r
+    }
+
+    #[inline]
+    pub(crate) fn spin_wakeup(&self) {
+        add(&self.shared.spin_wakeups, 1);
+    }
+
+    /// Mark the runtime as running, so time until the next park is accounted
+    /// as busy.
+    pub(crate) fn start(&self) {
+        self.last_unpark.set(Some(Instant::now()));
+    }
+
+    /// Account the time since the last park as busy.
+    pub(crate) fn stop(&self) {
+        if let Some(last) = self.last_unpark.take() {
+            add(&self.shared.busy_nanos, last.elapsed().as_nanos() as u64);
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use std::{io, net::SocketAddr};
+
+/// Converts or resolves without blocking to one or more `SocketAddr` values.
+///
+/// This is the non-blocking counterpart of [`std::net::ToSocketAddrs`], and it
+/// is implemented for every type implementing the std trait. Host names given
+/// as strings, alone or with a port, are resolved with
+/// [`lookup_host`](super::lookup_host) instead of blocking the runtime thread.
+/// Other implementations of the std trait are converted with
+/// [`to_socket_addrs`](std::net::ToSocketAddrs::to_socket_addrs), which may
+/// block if they resolve names themselves.
+///
+/// This trait is sealed and cannot be implemented for types outside of
+/// snowfallio.
+pub trait ToSocketAddrs: sealed::ToSocketAddrsPriv {}
+
+impl<T: std::net::ToSocketAddrs + ?Sized> ToSocketAddrs for T {}
+
+pub(crate) mod sealed {
+    //! The private part of `ToSocketAddrs`, so that it can not be implemented
+    //! or called outside of the crate.
+
+    use std::{
+        io,
+        net::{IpAddr, SocketAddr},
+    };
+
+    /// Addresses, or a host name which remains to be resolved.
+    #[derive(Debug)]
+    pub enum Target<'a> {
+        Addrs(Vec<SocketAddr>),
+        Host(&'a str, u16),
+    }
+
+    #[allow(unreachable_pub)]
+    pub trait ToSocketAddrsPriv {
+        fn to_target(&self) -> io::Result<Target<'_>>;
+    }
+
+    impl<T: std::net::ToSocketAddrs + ?Sized> ToSocketAddrsPriv for T {
+        default fn to_target(&self) -> io::Result<Target<'_>> {
+            Ok(Target::Addrs(self.to_socket_addrs()?.collect()))
+        }
+    }
+
+    // The std types holding a host name are resolved without blocking.
+
+    impl ToSocketAddrsPriv for (&str, u16) {
+        fn to_target(&self) -> io::Result<Target<'_>> {
+            host_port(self.0, self.1)
+        }
+    }
+
+    impl ToSocketAddrsPriv for (String, u16) {
+        fn to_target(&self) -> io::Result<Target<'_>> {
+            host_port(&self.0, self.1)
+        }
+    }
+
+    impl ToSocketAddrsPriv for str {
+        fn to_target(&self) -> io::Result<Target<'_>> {
+            if let Ok(addr) = self.parse() {
+                return Ok(Target::Addrs(vec![addr]));
+            }
+            let (host, port) = self
+                .rsplit_once(':')
+                .ok_or_else(|| invalid_input("invalid socket address"))?;
+            let port = port
+                .parse()
+                .map_err(|_| invalid_input("invalid port value"))?;
+            Ok(Target::Host(host, port))
+        }
+    }
+
+    impl ToSocketAddrsPriv for &str {
+        fn to_target(&self) -> io::Result<Target<'_>> {
+            (**self).to_target()
+        }
+    }
+
+    impl ToSocketAddrsPriv for String {
+        fn to_target(&self) -> io::Result<Target<'_>> {
+            self.as_str().to_target()
+        }
+    }
+
+    impl ToSocketAddrsPriv for &String {
+        fn to_target(&self) -> io::Result<Target<'_>> {
+            self.as_str().to_target()
+        }
+    }
+
+    fn host_port(host: &str, port: u16) -> io::Result<Target<'_>> {
+        match host.parse::<IpAddr>() {
+            Ok(ip) => Ok(Target::Addrs(vec![SocketAddr::new(ip, port)])),
+            Err(_) => Ok(Target::Host(host, port)),
+        }
+    }
+
+    fn invalid_input(msg: &'static str) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidInput, msg)
+    }
+}
+
+/// Resolve `addr` to socket addresses, without blocking the thread.
+pub(crate) async fn resolve<A: ToSocketAddrs + ?Sized>(addr: &A) -> io::Result<Vec<SocketAddr>> {
+    match addr.to_target()? {
+        sealed::Target::Addrs(addrs) => Ok(addrs),
+        sealed::Target::Host(host, port) => Ok(super::dns::lookup_ip(host)
+            .await?
+            .into_iter()
+            .map(|ip| SocketAddr::new(ip, port))
+            .collect()),
+    }
+}
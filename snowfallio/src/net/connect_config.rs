@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+/// Custom connect config
+///
+/// When a host resolves to several addresses, [`TcpStream::connect`] races
+/// them following RFC 8305 (Happy Eyeballs): the addresses are interleaved
+/// by family, and a new attempt starts each time the previous one fails or
+/// `attempt_delay` passes without it completing.
+///
+/// [`TcpStream::connect`]: crate::net::TcpStream::connect
+#[derive(Debug, Clone, Copy)]
+pub struct ConnectConfig {
+    /// Delay before racing the next address against the pending attempts.
+    pub attempt_delay: Duration,
+    /// Timeout of each attempt or None to wait for the kernel to give up.
+    pub attempt_timeout: Option<Duration>,
+}
+
+impl Default for ConnectConfig {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            // The Connection Attempt Delay recommended by RFC 8305.
+            attempt_delay: Duration::from_millis(250),
+            attempt_timeout: None,
+        }
+    }
+}
+
+impl ConnectConfig {
+    /// Specify the delay between attempts
+    #[must_use]
+    #[inline]
+    pub fn attempt_delay(mut self, attempt_delay: Duration) -> Self {
+        self.attempt_delay = attempt_delay;
+        self
+    }
+
+    /// Specify the timeout of each attempt
+    #[must_use]
+    #[inline]
+    pub fn attempt_timeout(mut self, attempt_timeout: Duration) -> Self {
+        self.attempt_timeout = Some(attempt_timeout);
+        self
+    }
+}
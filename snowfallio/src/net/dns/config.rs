@@ -0,0 +1,217 @@
+//! Resolver configuration, and the parsing of `/etc/resolv.conf` and
+//! `/etc/hosts`.
+
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+pub(super) const RESOLV_CONF: &str = "/etc/resolv.conf";
+const HOSTS: &str = "/etc/hosts";
+const DNS_PORT: u16 = 53;
+
+/// Resolver config used by [`lookup_host`](super::lookup_host).
+///
+/// The resolver of a thread follows `/etc/resolv.conf` unless a config is set
+/// with [`set_resolver_config`](super::set_resolver_config).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolverConfig {
+    /// Name servers, queried in order.
+    pub nameservers: Vec<SocketAddr>,
+    /// Domains appended to names with less than `ndots` dots.
+    pub search: Vec<String>,
+    /// Dots a name must have to be first tried as absolute.
+    pub ndots: usize,
+    /// Time to wait for the response of a name server.
+    pub timeout: Duration,
+    /// Rounds of queries over the name servers before giving up.
+    pub attempts: usize,
+    /// Hosts file looked up before the name servers, or None to skip it.
+    pub hosts_file: Option<PathBuf>,
+}
+
+impl Default for ResolverConfig {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            nameservers: vec![SocketAddr::new(Ipv4Addr::LOCALHOST.into(), DNS_PORT)],
+            search: Vec::new(),
+            ndots: 1,
+            timeout: Duration::from_secs(5),
+            attempts: 2,
+            hosts_file: Some(PathBuf::from(HOSTS)),
+        }
+    }
+}
+
+impl ResolverConfig {
+    /// Read the config of the system from `/etc/resolv.conf`. A missing file
+    /// gives the default config.
+    pub fn system() -> Self {
+        std::fs::read_to_string(RESOLV_CONF)
+            .map(|content| Self::parse(&content))
+            .unwrap_or_default()
+    }
+
+    /// Specify the name servers
+    #[must_use]
+    #[inline]
+    pub fn nameservers(mut self, nameservers: Vec<SocketAddr>) -> Self {
+        self.nameservers = nameservers;
+        self
+    }
+
+    /// Specify the search domains
+    #[must_use]
+    #[inline]
+    pub fn search(mut self, search: Vec<String>) -> Self {
+        self.search = search;
+        self
+    }
+
+    /// Specify ndots
+    #[must_use]
+    #[inline]
+    pub fn ndots(mut self, ndots: usize) -> Self {
+        self.ndots = ndots;
+        self
+    }
+
+    /// Specify the query timeout
+    #[must_use]
+    #[inline]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Specify the number of attempts
+    #[must_use]
+    #[inline]
+    pub fn attempts(mut self, attempts: usize) -> Self {
+        self.attempts = attempts;
+        self
+    }
+
+    /// Specify the hosts file, or None to skip it
+    #[must_use]
+    #[inline]
+    pub fn hosts_file(mut self, hosts_file: Option<PathBuf>) -> Self {
+        self.hosts_file = hosts_file;
+        self
+    }
+
+    /// Parse the content of a `resolv.conf`, following resolv.conf(5).
+    pub(super) fn parse(content: &str) -> Self {
+        let mut config = Self {
+            nameservers: Vec::new(),
+            ..Self::default()
+        };
+        for line in content.lines() {
+            let line = line.split(['#', ';']).next().unwrap_or_default();
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some("nameserver") => {
+                    // Scoped ipv6 addresses are not supported.
+                    if let Some(ip) = words.next().and_then(|ip| ip.parse::<IpAddr>().ok()) {
+                        config.nameservers.push(SocketAddr::new(ip, DNS_PORT));
+                    }
+                }
+                // The last of search and domain wins.
+                Some("search") => config.search = words.map(str::to_owned).collect(),
+                Some("domain") => config.search = words.take(1).map(str::to_owned).collect(),
+                Some("options") => {
+                    for option in words {
+                        let (name, value) = match option.split_once(':') {
+                            Some((name, value)) => match value.parse::<usize>() {
+                                Ok(value) => (name, value),
+                                Err(_) => continue,
+                            },
+                            None => continue,
+                        };
+                        match name {
+                            "ndots" => config.ndots = value.min(15),
+                            "timeout" => config.timeout = Duration::from_secs(value.min(30) as u64),
+                            "attempts" => config.attempts = value.clamp(1, 5),
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        if config.nameservers.is_empty() {
+            config.nameservers = Self::default().nameservers;
+        }
+        config
+    }
+}
+
+/// Addresses of the names of a hosts file.
+#[derive(Debug, Default)]
+pub(super) struct Hosts {
+    names: HashMap<String, Vec<IpAddr>>,
+}
+
+impl Hosts {
+    /// Parse the content of a hosts file, following hosts(5).
+    pub(super) fn parse(content: &str) -> Self {
+        let mut names = HashMap::<String, Vec<IpAddr>>::new();
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or_default();
+            let mut words = line.split_whitespace();
+            let ip = match words.next().and_then(|ip| ip.parse::<IpAddr>().ok()) {
+                Some(ip) => ip,
+                None => continue,
+            };
+            for name in words {
+                let addrs = names.entry(name.to_ascii_lowercase()).or_default();
+                if !addrs.contains(&ip) {
+                    addrs.push(ip);
+                }
+            }
+        }
+        Self { names }
+    }
+
+    pub(super) fn get(&self, name: &str) -> Option<&[IpAddr]> {
+        let name = name.strip_suffix('.').unwrap_or(name);
+        self.names
+            .get(&name.to_ascii_lowercase())
+            .map(Vec::as_slice)
+    }
+}
+
+/// A value parsed from a file, parsed again when the file is modified.
+#[derive(Debug)]
+pub(super) struct Watched<T> {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    value: Option<T>,
+}
+
+impl<T> Default for Watched<T> {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::new(),
+            modified: None,
+            value: None,
+        }
+    }
+}
+
+impl<T> Watched<T> {
+    /// Get the value of the file at `path`. A missing file is parsed as empty.
+    pub(super) fn get(&mut self, path: &Path, parse: impl FnOnce(&str) -> T) -> &T {
+        let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        if self.value.is_none() || self.path != path || self.modified != modified {
+            let content = std::fs::read_to_string(path).unwrap_or_default();
+            self.path = path.to_owned();
+            self.modified = modified;
+            self.value = Some(parse(&content));
+        }
+        self.value.as_ref().unwrap()
+    }
+}
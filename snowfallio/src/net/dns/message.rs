@@ -0,0 +1,175 @@
+//! Encoding of DNS queries and decoding of their responses (RFC 1035).
+
+use std::{
+    io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+
+pub(super) const TYPE_A: u16 = 1;
+pub(super) const TYPE_AAAA: u16 = 28;
+const CLASS_IN: u16 = 1;
+
+const HEADER_LEN: usize = 12;
+const FLAG_QR: u16 = 0x8000;
+const FLAG_TC: u16 = 0x0200;
+const FLAG_RD: u16 = 0x0100;
+const RCODE_NXDOMAIN: u16 = 3;
+
+/// Records of an answer.
+#[derive(Debug, Default)]
+pub(super) struct Answer {
+    pub(super) addrs: Vec<IpAddr>,
+    /// Lowest ttl of the records, in seconds.
+    pub(super) ttl: u32,
+}
+
+/// Outcome of a query.
+#[derive(Debug)]
+pub(super) enum Response {
+    Answer(Answer),
+    /// The name does not exist.
+    NxDomain,
+    /// The answer did not fit in the response, the query must be retried
+    /// over TCP.
+    Truncated,
+}
+
+/// Build a recursive query for the records of type `qtype` of `name`.
+pub(super) fn build_query(id: u16, name: &str, qtype: u16) -> io::Result<Vec<u8>> {
+    let mut buf = Vec::with_capacity(HEADER_LEN + name.len() + 6);
+    buf.extend_from_slice(&id.to_be_bytes());
+    buf.extend_from_slice(&FLAG_RD.to_be_bytes());
+    // One question, no other records.
+    buf.extend_from_slice(&[0, 1, 0, 0, 0, 0, 0, 0]);
+
+    let name = name.strip_suffix('.').unwrap_or(name);
+    if name.is_empty() || name.len() > 253 {
+        return Err(invalid_name());
+    }
+    for label in name.split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return Err(invalid_name());
+        }
+        buf.push(label.len() as u8);
+        buf.extend_from_slice(label.as_bytes());
+    }
+    buf.push(0);
+    buf.extend_from_slice(&qtype.to_be_bytes());
+    buf.extend_from_slice(&CLASS_IN.to_be_bytes());
+    Ok(buf)
+}
+
+/// Parse the response to the query `id` for records of type `qtype`.
+///
+/// Returns `None` if `buf` is not a response to the query, e.g. a late
+/// response to a previous attempt.
+pub(super) fn parse_response(id: u16, qtype: u16, buf: &[u8]) -> io::Result<Option<Response>> {
+    let mut reader = Reader { buf, pos: 0 };
+    if buf.len() < HEADER_LEN || reader.u16()? != id {
+        return Ok(None);
+    }
+    let flags = reader.u16()?;
+    if flags & FLAG_QR == 0 {
+        return Ok(None);
+    }
+    if flags & FLAG_TC != 0 {
+        return Ok(Some(Response::Truncated));
+    }
+    match flags & 0xf {
+        0 => {}
+        RCODE_NXDOMAIN => return Ok(Some(Response::NxDomain)),
+        rcode => {
+            return Err(io::Error::other(format!(
+                "dns server failure, rcode {rcode}"
+            )))
+        }
+    }
+    let questions = reader.u16()?;
+    let answers = reader.u16()?;
+    reader.skip(4)?;
+
+    for _ in 0..questions {
+        reader.skip_name()?;
+        reader.skip(4)?;
+    }
+    let mut answer = Answer {
+        addrs: Vec::new(),
+        ttl: u32::MAX,
+    };
+    for _ in 0..answers {
+        reader.skip_name()?;
+        let rtype = reader.u16()?;
+        let class = reader.u16()?;
+        let ttl = reader.u32()?;
+        let len = reader.u16()? as usize;
+        let data = reader.take(len)?;
+        if class != CLASS_IN || rtype != qtype {
+            // E.g. the CNAME records leading to the addresses.
+            continue;
+        }
+        let addr = match (rtype, data.len()) {
+            (TYPE_A, 4) => IpAddr::V4(Ipv4Addr::new(data[0], data[1], data[2], data[3])),
+            (TYPE_AAAA, 16) => {
+                let mut octets = [0; 16];
+                octets.copy_from_slice(data);
+                IpAddr::V6(Ipv6Addr::from(octets))
+            }
+            _ => return Err(malformed()),
+        };
+        answer.addrs.push(addr);
+        answer.ttl = answer.ttl.min(ttl);
+    }
+    if answer.addrs.is_empty() {
+        answer.ttl = 0;
+    }
+    Ok(Some(Response::Answer(answer)))
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> io::Result<&'a [u8]> {
+        let data = self.buf.get(self.pos..self.pos + n).ok_or_else(malformed)?;
+        self.pos += n;
+        Ok(data)
+    }
+
+    fn skip(&mut self, n: usize) -> io::Result<()> {
+        self.take(n).map(drop)
+    }
+
+    fn u16(&mut self) -> io::Result<u16> {
+        let data = self.take(2)?;
+        Ok(u16::from_be_bytes([data[0], data[1]]))
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        let data = self.take(4)?;
+        Ok(u32::from_be_bytes([data[0], data[1], data[2], data[3]]))
+    }
+
+    /// Skip a possibly compressed name.
+    fn skip_name(&mut self) -> io::Result<()> {
+        loop {
+            let len = self.take(1)?[0];
+            match len {
+                0 => return Ok(()),
+                // A pointer ends the name.
+                len if len & 0xc0 == 0xc0 => return self.skip(1),
+                len if len & 0xc0 == 0 => self.skip(len as usize)?,
+                _ => return Err(malformed()),
+            }
+        }
+    }
+}
+
+fn invalid_name() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, "invalid dns name")
+}
+
+fn malformed() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "malformed dns response")
+}
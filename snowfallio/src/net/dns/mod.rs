@@ -0,0 +1,342 @@
+//! Asynchronous DNS resolution.
+//!
+//! Host names are looked up in the hosts file, then queried over UDP to the
+//! name servers of `/etc/resolv.conf`, without blocking the thread. Truncated
+//! answers are asked again over TCP. Results are cached per thread for the TTL
+//! of their records.
+
+mod config;
+mod message;
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    future::{poll_fn, Future},
+    io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    path::Path,
+    task::Poll,
+    time::{Duration, Instant},
+};
+
+pub use config::ResolverConfig;
+use config::{Hosts, Watched, RESOLV_CONF};
+use message::{Answer, Response, TYPE_A, TYPE_AAAA};
+
+use super::{addr::resolve, udp::UdpSocket, TcpStream, ToSocketAddrs};
+use crate::{
+    io::{AsyncReadRentExt, AsyncWriteRentExt, Canceller},
+    time::sleep_monotonic,
+    utils::thread_rng_n,
+};
+
+/// Largest response read, as advertised by most resolvers over EDNS.
+const MAX_RESPONSE_LEN: usize = 1232;
+
+thread_local! {
+    static RESOLVER: RefCell<Resolver> = RefCell::new(Resolver::default());
+}
+
+#[derive(Default)]
+struct Resolver {
+    /// Config set with `set_resolver_config`, replacing the system one.
+    custom: Option<ResolverConfig>,
+    system: Watched<ResolverConfig>,
+    hosts: Watched<Hosts>,
+    cache: HashMap<String, Cached>,
+}
+
+struct Cached {
+    addrs: Vec<IpAddr>,
+    expires: Instant,
+}
+
+/// Performs a DNS resolution.
+///
+/// The returned iterator may not actually yield any values depending on the
+/// outcome of any resolution performed.
+///
+/// This API is not intended to cover all DNS use cases. Anything beyond the
+/// basic use case should be done with a specialized library.
+///
+/// # Examples
+///
+/// ```no_run
+/// use snowfallio::net;
+///
+/// #[snowfallio::main]
+/// async fn main() -> std::io::Result<()> {
+///     for addr in net::lookup_host("localhost:3000").await? {
+///         println!("socket address is {}", addr);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub async fn lookup_host<T: ToSocketAddrs>(
+    host: T,
+) -> io::Result<impl Iterator<Item = SocketAddr>> {
+    Ok(resolve(&host).await?.into_iter())
+}
+
+/// Set the resolver config of the current thread, in place of the one read
+/// from `/etc/resolv.conf`. This also clears the cache of the thread.
+pub fn set_resolver_config(config: ResolverConfig) {
+    RESOLVER.with(|resolver| {
+        let mut resolver = resolver.borrow_mut();
+        resolver.custom = Some(config);
+        resolver.cache.clear();
+    });
+}
+
+/// Clear the resolution cache of the current thread.
+pub fn clear_cache() {
+    RESOLVER.with(|resolver| resolver.borrow_mut().cache.clear());
+}
+
+enum Lookup {
+    Done(Vec<IpAddr>),
+    Query(ResolverConfig),
+}
+
+/// Resolve `host` to ip addresses.
+pub(crate) async fn lookup_ip(host: &str) -> io::Result<Vec<IpAddr>> {
+    let name = host.to_ascii_lowercase();
+    let lookup = RESOLVER.with(|resolver| {
+        let resolver = &mut *resolver.borrow_mut();
+        if let Some(cached) = resolver.cache.get(&name) {
+            if cached.expires > Instant::now() {
+                return Lookup::Done(cached.addrs.clone());
+            }
+            resolver.cache.remove(&name);
+        }
+
+        let config = match &resolver.custom {
+            Some(config) => config,
+            None => resolver
+                .system
+                .get(Path::new(RESOLV_CONF), ResolverConfig::parse),
+        };
+        if let Some(path) = &config.hosts_file {
+            if let Some(addrs) = resolver.hosts.get(path, Hosts::parse).get(&name) {
+                return Lookup::Done(addrs.to_vec());
+            }
+        }
+        Lookup::Query(config.clone())
+    });
+    let config = match lookup {
+        Lookup::Done(addrs) => return Ok(addrs),
+        Lookup::Query(config) => config,
+    };
+
+    let mut last_err = None;
+    for candidate in candidates(&name, &config) {
+        match query(&config, &candidate).await {
+            Ok(Some(answer)) => {
+                if answer.ttl > 0 {
+                    let expires = Instant::now() + Duration::from_secs(answer.ttl as u64);
+                    let cached = Cached {
+                        addrs: answer.addrs.clone(),
+                        expires,
+                    };
+                    RESOLVER.with(|resolver| resolver.borrow_mut().cache.insert(name, cached));
+                }
+                return Ok(answer.addrs);
+            }
+            Ok(None) => {}
+            Err(e) => last_err = Some(e),
+        }
+    }
+    Err(last_err.unwrap_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("failed to lookup address information for {host}"),
+        )
+    }))
+}
+
+/// Names to query for `name`, following the search list and ndots rules of
+/// resolv.conf(5).
+fn candidates(name: &str, config: &ResolverConfig) -> Vec<String> {
+    if let Some(absolute) = name.strip_suffix('.') {
+        return vec![absolute.to_owned()];
+    }
+    let searched = config
+        .search
+        .iter()
+        .map(|domain| format!("{name}.{}", domain.trim_end_matches('.')));
+    if name.matches('.').count() >= config.ndots {
+        std::iter::once(name.to_owned()).chain(searched).collect()
+    } else {
+        searched.chain(std::iter::once(name.to_owned())).collect()
+    }
+}
+
+/// Query the addresses of `name`, trying the name servers in turn. Returns
+/// None if the name has no address.
+async fn query(config: &ResolverConfig, name: &str) -> io::Result<Option<Answer>> {
+    let mut last_err = None;
+    for _ in 0..config.attempts.max(1) {
+        for server in &config.nameservers {
+            match query_server(*server, name, config.timeout).await {
+                Ok(answer) => return Ok(answer),
+                Err(e) => last_err = Some(e),
+            }
+        }
+    }
+    Err(last_err.unwrap_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidInput, "no name server configured")
+    }))
+}
+
+/// Query the A and AAAA records of `name` together to `server`.
+async fn query_server(
+    server: SocketAddr,
+    name: &str,
+    timeout: Duration,
+) -> io::Result<Option<Answer>> {
+    let local: SocketAddr = match server {
+        SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+        SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+    };
+    let socket = UdpSocket::bind(local)?;
+    socket.connect(server).await?;
+
+    let id = thread_rng_n(u16::MAX as u32 + 1) as u16;
+    let queries = [(id, TYPE_A), (id.wrapping_add(1), TYPE_AAAA)];
+    for (id, qtype) in queries {
+        let (res, _) = socket.send(message::build_query(id, name, qtype)?).await;
+        res?;
+    }
+
+    let deadline = Instant::now() + timeout;
+    let mut responses: [Option<io::Result<Response>>; 2] = [None, None];
+    let mut buf = vec![0; MAX_RESPONSE_LEN];
+    while responses.iter().any(Option::is_none) {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let (res, b) = recv_timeout(&socket, buf, remaining).await;
+        buf = b;
+        let n = match res {
+            Ok(n) => n,
+            Err(e) if e.raw_os_error() == Some(libc::ECANCELED) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("dns query to {server} timed out"),
+                ))
+            }
+            Err(e) => return Err(e),
+        };
+        for ((id, qtype), response) in queries.iter().zip(responses.iter_mut()) {
+            if response.is_none() {
+                // A failed or malformed reply only settles its own query.
+                if let Some(parsed) = message::parse_response(*id, *qtype, &buf[..n]).transpose() {
+                    *response = Some(parsed);
+                    break;
+                }
+            }
+        }
+    }
+
+    for ((id, qtype), response) in queries.iter().zip(responses.iter_mut()) {
+        if let Some(Ok(Response::Truncated)) = response {
+            // A partial answer would silently drop addresses, the server is
+            // given up on if TCP fails too.
+            *response = Some(Ok(query_tcp(server, *id, name, *qtype, timeout).await?));
+        }
+    }
+
+    let mut merged = Answer {
+        addrs: Vec::new(),
+        ttl: u32::MAX,
+    };
+    let mut last_err = None;
+    let mut failed = 0;
+    for response in responses.into_iter().flatten() {
+        match response {
+            Ok(Response::Answer(answer)) if !answer.addrs.is_empty() => {
+                merged.addrs.extend(answer.addrs);
+                merged.ttl = merged.ttl.min(answer.ttl);
+            }
+            Ok(_) => {}
+            Err(e) => {
+                failed += 1;
+                last_err = Some(e);
+            }
+        }
+    }
+    // Only give up on the server when no query got an answer, a failed one
+    // counts as empty otherwise.
+    match last_err {
+        Some(e) if failed == queries.len() => Err(e),
+        _ => Ok((!merged.addrs.is_empty()).then_some(merged)),
+    }
+}
+
+/// Query the records of type `qtype` of `name` to `server` over TCP.
+async fn query_tcp(
+    server: SocketAddr,
+    id: u16,
+    name: &str,
+    qtype: u16,
+    timeout: Duration,
+) -> io::Result<Response> {
+    let exchange = async {
+        let query = message::build_query(id, name, qtype)?;
+        // Messages are prefixed with their length over TCP.
+        let mut framed = Vec::with_capacity(2 + query.len());
+        framed.extend_from_slice(&(query.len() as u16).to_be_bytes());
+        framed.extend_from_slice(&query);
+
+        let mut stream = TcpStream::connect_addr(server).await?;
+        let (res, _) = stream.write_all(framed).await;
+        res?;
+        let len = stream.read_u16().await?;
+        let (res, buf) = stream.read_exact(vec![0; len as usize]).await;
+        res?;
+        match message::parse_response(id, qtype, &buf)? {
+            Some(Response::Truncated) | None => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid dns response over tcp from {server}"),
+            )),
+            Some(response) => Ok(response),
+        }
+    };
+    let sleep = sleep_monotonic(timeout);
+    crate::pin!(exchange, sleep);
+
+    poll_fn(|cx| match exchange.as_mut().poll(cx) {
+        Poll::Ready(res) => Poll::Ready(res),
+        Poll::Pending => sleep.as_mut().poll(cx).map(|_| {
+            Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("dns query to {server} timed out"),
+            ))
+        }),
+    })
+    .await
+}
+
+/// Receive on `socket`, canceling the receive after `timeout`.
+async fn recv_timeout(
+    socket: &UdpSocket,
+    buf: Vec<u8>,
+    timeout: Duration,
+) -> crate::BufResult<usize, Vec<u8>> {
+    let canceller = Canceller::new();
+    let recv = socket.cancelable_recv(buf, canceller.handle());
+    let sleep = sleep_monotonic(timeout);
+    crate::pin!(recv, sleep);
+
+    let res = poll_fn(|cx| match recv.as_mut().poll(cx) {
+        Poll::Ready(res) => Poll::Ready(Some(res)),
+        Poll::Pending => sleep.as_mut().poll(cx).map(|_| None),
+    })
+    .await;
+    match res {
+        Some(res) => res,
+        None => {
+            // Wait for the canceled receive to give the buffer back.
+            canceller.cancel();
+            recv.await
+        }
+    }
+}
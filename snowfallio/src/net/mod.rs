@@ -1,13 +1,20 @@
 //! Network related
-//! Currently, TCP/UnixStream/UnixDatagram are implemented.
+//! Currently, TCP/UDP/UnixStream/UnixDatagram and DNS resolution are
+//! implemented.
 
+mod addr;
+mod connect_config;
+pub mod dns;
 mod listener_config;
 pub mod tcp;
 pub mod udp;
 pub mod unix;
 
+pub use addr::ToSocketAddrs;
+pub use connect_config::ConnectConfig;
+pub use dns::lookup_host;
 pub use listener_config::ListenerConfig;
-pub use tcp::{TcpListener, TcpStream};
+pub use tcp::{TcpListener, TcpSocket, TcpStream};
 pub use unix::{Pipe, UnixDatagram, UnixListener, UnixStream};
 
 // Copied from mio.
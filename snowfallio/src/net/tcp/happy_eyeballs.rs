@@ -0,0 +1,132 @@
+//! Connection racing over the addresses of a host (RFC 8305).
+
+use std::{
+    future::{poll_fn, Future},
+    io,
+    net::SocketAddr,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use super::TcpStream;
+use crate::{
+    io::Canceller,
+    net::ConnectConfig,
+    time::{sleep_monotonic, ClockSleep},
+};
+
+/// Connect to the first of `addrs` to accept, racing them with a staggered
+/// delay. Returns the error of the last attempt if all of them fail.
+pub(super) async fn connect(
+    addrs: Vec<SocketAddr>,
+    config: &ConnectConfig,
+) -> io::Result<TcpStream> {
+    let mut addrs = interleave(addrs).into_iter();
+    let mut attempts: Vec<Attempt> = Vec::new();
+    let mut delay: Option<ClockSleep> = None;
+    let mut last_err = None;
+
+    loop {
+        if delay.is_none() {
+            if let Some(addr) = addrs.next() {
+                attempts.push(Attempt::new(addr, config));
+                delay = Some(sleep_monotonic(config.attempt_delay));
+            }
+        }
+        if attempts.is_empty() {
+            return Err(last_err
+                .unwrap_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "empty address")));
+        }
+
+        let event = poll_fn(|cx| {
+            for (index, attempt) in attempts.iter_mut().enumerate() {
+                if let Poll::Ready(res) = attempt.poll(cx) {
+                    return Poll::Ready(Some((index, res)));
+                }
+            }
+            match &mut delay {
+                Some(sleep) => Pin::new(sleep).poll(cx).map(|_| None),
+                None => Poll::Pending,
+            }
+        })
+        .await;
+
+        match event {
+            Some((index, Ok(stream))) => {
+                attempts.swap_remove(index);
+                // Losers are canceled before being dropped, as dropping an op
+                // leaves it running in the kernel.
+                for attempt in attempts {
+                    attempt.canceller.cancel();
+                }
+                return Ok(stream);
+            }
+            Some((index, Err(e))) => {
+                attempts.swap_remove(index);
+                last_err = Some(e);
+                // Start the next attempt right away.
+                delay = None;
+            }
+            None => delay = None,
+        }
+    }
+}
+
+/// Alternate the address families, keeping the order of each family and
+/// starting with the family of the first address.
+fn interleave(addrs: Vec<SocketAddr>) -> Vec<SocketAddr> {
+    let first_v6 = addrs.first().is_some_and(SocketAddr::is_ipv6);
+    let (preferred, other): (Vec<_>, Vec<_>) = addrs
+        .into_iter()
+        .partition(|addr| addr.is_ipv6() == first_v6);
+    let mut other = other.into_iter();
+    let mut ordered = Vec::with_capacity(preferred.len() + other.len());
+    for addr in preferred {
+        ordered.push(addr);
+        ordered.extend(other.next());
+    }
+    ordered.extend(other);
+    ordered
+}
+
+/// A pending connection attempt.
+struct Attempt {
+    addr: SocketAddr,
+    canceller: Canceller,
+    connect: Pin<Box<dyn Future<Output = io::Result<TcpStream>>>>,
+    deadline: Option<ClockSleep>,
+    timed_out: bool,
+}
+
+impl Attempt {
+    fn new(addr: SocketAddr, config: &ConnectConfig) -> Attempt {
+        let canceller = Canceller::new();
+        let connect = Box::pin(TcpStream::cancelable_connect_addr(addr, canceller.handle()));
+        Attempt {
+            addr,
+            canceller,
+            connect,
+            deadline: config.attempt_timeout.map(sleep_monotonic),
+            timed_out: false,
+        }
+    }
+
+    fn poll(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<TcpStream>> {
+        if let Some(deadline) = &mut self.deadline {
+            if Pin::new(deadline).poll(cx).is_ready() {
+                self.deadline = None;
+                self.timed_out = true;
+                self.canceller = std::mem::take(&mut self.canceller).cancel();
+            }
+        }
+        match ready!(self.connect.as_mut().poll(cx)) {
+            Err(e) if self.timed_out && e.raw_os_error() == Some(libc::ECANCELED) => {
+                Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("connect to {} timed out", self.addr),
+                )))
+            }
+            res => Poll::Ready(res),
+        }
+    }
+}
@@ -1,10 +1,13 @@
 #![allow(unreachable_pub)]
 //! TCP related.
 
+mod happy_eyeballs;
 mod listener;
+mod socket;
 mod split;
 mod stream;
 
 pub use listener::TcpListener;
+pub use socket::TcpSocket;
 pub use split::{TcpOwnedReadHalf, TcpOwnedWriteHalf, TcpReadHalf, TcpWriteHalf};
 pub use stream::TcpStream;
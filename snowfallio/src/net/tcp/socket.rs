@@ -0,0 +1,189 @@
+use std::{
+    io,
+    net::SocketAddr,
+    os::unix::prelude::{AsRawFd, FromRawFd, IntoRawFd, RawFd},
+    time::Duration,
+};
+
+use super::{TcpListener, TcpStream};
+use crate::driver::shared_fd::SharedFd;
+
+/// A TCP socket that has not yet been converted to a `TcpStream` or
+/// `TcpListener`.
+///
+/// It exposes the socket options which have to be set before connecting or
+/// listening, e.g. binding a client to a source address:
+///
+/// ```no_run
+/// use snowfallio::net::TcpSocket;
+///
+/// #[snowfallio::main]
+/// async fn main() -> std::io::Result<()> {
+///     let socket = TcpSocket::new_v4()?;
+///     socket.set_reuseaddr(true)?;
+///     socket.bind("127.0.0.1:40000".parse().unwrap())?;
+///     let stream = socket.connect("127.0.0.1:8080".parse().unwrap()).await?;
+///     Ok(())
+/// }
+/// ```
+pub struct TcpSocket {
+    inner: socket2::Socket,
+}
+
+impl TcpSocket {
+    /// Create a new socket configured for IPv4.
+    pub fn new_v4() -> io::Result<TcpSocket> {
+        TcpSocket::new(socket2::Domain::IPV4)
+    }
+
+    /// Create a new socket configured for IPv6.
+    pub fn new_v6() -> io::Result<TcpSocket> {
+        TcpSocket::new(socket2::Domain::IPV6)
+    }
+
+    fn new(domain: socket2::Domain) -> io::Result<TcpSocket> {
+        let inner =
+            socket2::Socket::new(domain, socket2::Type::STREAM, Some(socket2::Protocol::TCP))?;
+        Ok(TcpSocket { inner })
+    }
+
+    /// Set the value of the `SO_REUSEADDR` option on this socket.
+    pub fn set_reuseaddr(&self, reuseaddr: bool) -> io::Result<()> {
+        self.inner.set_reuse_address(reuseaddr)
+    }
+
+    /// Get the value of the `SO_REUSEADDR` option on this socket.
+    pub fn reuseaddr(&self) -> io::Result<bool> {
+        self.inner.reuse_address()
+    }
+
+    /// Set the value of the `SO_REUSEPORT` option on this socket.
+    pub fn set_reuseport(&self, reuseport: bool) -> io::Result<()> {
+        self.inner.set_reuse_port(reuseport)
+    }
+
+    /// Get the value of the `SO_REUSEPORT` option on this socket.
+    pub fn reuseport(&self) -> io::Result<bool> {
+        self.inner.reuse_port()
+    }
+
+    /// Set the value of the `SO_SNDBUF` option on this socket.
+    pub fn set_send_buffer_size(&self, size: u32) -> io::Result<()> {
+        self.inner.set_send_buffer_size(size as usize)
+    }
+
+    /// Get the value of the `SO_SNDBUF` option on this socket. The kernel
+    /// doubles the value set to account for its bookkeeping.
+    pub fn send_buffer_size(&self) -> io::Result<u32> {
+        self.inner.send_buffer_size().map(|size| size as u32)
+    }
+
+    /// Set the value of the `SO_RCVBUF` option on this socket.
+    pub fn set_recv_buffer_size(&self, size: u32) -> io::Result<()> {
+        self.inner.set_recv_buffer_size(size as usize)
+    }
+
+    /// Get the value of the `SO_RCVBUF` option on this socket. The kernel
+    /// doubles the value set to account for its bookkeeping.
+    pub fn recv_buffer_size(&self) -> io::Result<u32> {
+        self.inner.recv_buffer_size().map(|size| size as u32)
+    }
+
+    /// Set the value of the `SO_LINGER` option on this socket.
+    ///
+    /// With `Some`, closing the socket waits up to the duration for the
+    /// pending data to be sent, and `Some(Duration::ZERO)` resets the
+    /// connection on close.
+    pub fn set_linger(&self, linger: Option<Duration>) -> io::Result<()> {
+        self.inner.set_linger(linger)
+    }
+
+    /// Get the value of the `SO_LINGER` option on this socket.
+    pub fn linger(&self) -> io::Result<Option<Duration>> {
+        self.inner.linger()
+    }
+
+    /// Set the value of the `IP_TOS` option on this socket.
+    pub fn set_tos(&self, tos: u32) -> io::Result<()> {
+        self.inner.set_tos(tos)
+    }
+
+    /// Get the value of the `IP_TOS` option on this socket.
+    pub fn tos(&self) -> io::Result<u32> {
+        self.inner.tos()
+    }
+
+    /// Set the value of the `SO_BINDTODEVICE` option on this socket, binding
+    /// it to the interface named `interface`. `None` removes the binding.
+    ///
+    /// This usually requires the `CAP_NET_RAW` capability.
+    pub fn bind_device(&self, interface: Option<&[u8]>) -> io::Result<()> {
+        self.inner.bind_device(interface)
+    }
+
+    /// Get the value of the `SO_BINDTODEVICE` option on this socket.
+    pub fn device(&self) -> io::Result<Option<Vec<u8>>> {
+        self.inner.device()
+    }
+
+    /// Bind the socket to `addr`.
+    pub fn bind(&self, addr: SocketAddr) -> io::Result<()> {
+        self.inner.bind(&addr.into())
+    }
+
+    /// Return the local address that this socket is bound to.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.inner
+            .local_addr()?
+            .as_socket()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid socket address"))
+    }
+
+    /// Establish a TCP connection with a peer at `addr`, consuming the socket.
+    pub async fn connect(self, addr: SocketAddr) -> io::Result<TcpStream> {
+        let fd = SharedFd::new(self.inner.into_raw_fd());
+        TcpStream::connect_fd(fd, addr, None).await
+    }
+
+    /// Convert the socket into a `TcpListener` with a queue of `backlog`
+    /// pending connections.
+    pub fn listen(self, backlog: u32) -> io::Result<TcpListener> {
+        self.inner.listen(backlog.min(i32::MAX as u32) as i32)?;
+        let fd = SharedFd::new(self.inner.into_raw_fd());
+        Ok(TcpListener::from_shared_fd(fd))
+    }
+}
+
+impl AsRawFd for TcpSocket {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+impl IntoRawFd for TcpSocket {
+    fn into_raw_fd(self) -> RawFd {
+        self.inner.into_raw_fd()
+    }
+}
+
+impl FromRawFd for TcpSocket {
+    /// Converts a `RawFd` to a `TcpSocket`.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure `fd` is an open TCP socket owned by nothing
+    /// else.
+    unsafe fn from_raw_fd(fd: RawFd) -> TcpSocket {
+        TcpSocket {
+            inner: socket2::Socket::from_raw_fd(fd),
+        }
+    }
+}
+
+impl std::fmt::Debug for TcpSocket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TcpSocket")
+            .field("fd", &self.inner)
+            .finish()
+    }
+}
@@ -2,11 +2,12 @@ use std::{
     cell::UnsafeCell,
     future::Future,
     io,
-    net::{SocketAddr, ToSocketAddrs},
+    net::SocketAddr,
     os::unix::prelude::{AsRawFd, FromRawFd, IntoRawFd, RawFd},
     time::Duration,
 };
 
+use super::happy_eyeballs;
 use crate::{
     buf::{IoBuf, IoBufMut, IoVecBuf, IoVecBufMut},
     driver::{op::Op, shared_fd::SharedFd},
@@ -15,6 +16,7 @@ use crate::{
         operation_canceled, AsyncReadRent, AsyncWriteRent, CancelHandle, CancelableAsyncReadRent,
         CancelableAsyncWriteRent, Split,
     },
+    net::{addr::resolve, ConnectConfig, ToSocketAddrs},
 };
 
 /// TcpStream
@@ -37,27 +39,68 @@ impl TcpStream {
     }
 
     /// Open a TCP connection to a remote host.
-    /// Note: This function may block the current thread while resolution is
-    /// performed.
-    // TODO(chihai): Fix it, maybe spawn_blocking like tokio.
+    /// Host names are resolved with [`lookup_host`](crate::net::lookup_host),
+    /// without blocking the current thread. The resolved addresses are raced
+    /// as described in [`ConnectConfig`], with its default values.
+    ///
+    /// `addr` can be any [`std::net::ToSocketAddrs`]. Custom implementations
+    /// of it are converted with their own, possibly blocking, `to_socket_addrs`,
+    /// see [`net::ToSocketAddrs`](crate::net::ToSocketAddrs). Names are read
+    /// from the hosts file and `/etc/resolv.conf` instead of going through NSS,
+    /// so other sources of `nsswitch.conf` (e.g. mDNS or LDAP) are not used.
+    /// Resolve such names with std and pass the addresses instead.
     pub async fn connect<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
-        // TODO(chihai): loop for all addrs
-        let addr = addr
-            .to_socket_addrs()?
-            .next()
-            .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "empty address"))?;
+        Self::connect_with_config(addr, &ConnectConfig::default()).await
+    }
 
-        Self::connect_addr(addr).await
+    /// Open a TCP connection to a remote host with config.
+    ///
+    /// If every address fails, the error of the last attempt is returned.
+    pub async fn connect_with_config<A: ToSocketAddrs>(
+        addr: A,
+        config: &ConnectConfig,
+    ) -> io::Result<Self> {
+        let addrs = resolve(&addr).await?;
+        match addrs[..] {
+            [addr] if config.attempt_timeout.is_none() => Self::connect_addr(addr).await,
+            _ => happy_eyeballs::connect(addrs, config).await,
+        }
     }
 
     /// Establishe a connection to the specified `addr`.
     pub async fn connect_addr(addr: SocketAddr) -> io::Result<Self> {
+        Self::connect_addr_inner(addr, None).await
+    }
+
+    /// Establish a connection to the specified `addr`, which is aborted with
+    /// `ECANCELED` once the handle is canceled.
+    pub(crate) async fn cancelable_connect_addr(
+        addr: SocketAddr,
+        c: CancelHandle,
+    ) -> io::Result<Self> {
+        Self::connect_addr_inner(addr, Some(c)).await
+    }
+
+    async fn connect_addr_inner(addr: SocketAddr, c: Option<CancelHandle>) -> io::Result<Self> {
+        if c.as_ref().is_some_and(CancelHandle::canceled) {
+            return Err(operation_canceled());
+        }
         let domain = match addr {
             SocketAddr::V4(_) => libc::AF_INET,
             SocketAddr::V6(_) => libc::AF_INET6,
         };
         let socket = crate::net::new_socket(domain, libc::SOCK_STREAM)?;
-        let op = Op::connect(SharedFd::new(socket), addr)?;
+        Self::connect_fd(SharedFd::new(socket), addr, c).await
+    }
+
+    /// Connect the socket `fd` to `addr`.
+    pub(crate) async fn connect_fd(
+        fd: SharedFd,
+        addr: SocketAddr,
+        c: Option<CancelHandle>,
+    ) -> io::Result<Self> {
+        let op = Op::connect(fd, addr)?;
+        let _guard = c.map(|c| c.assocate_op(op.op_canceller()));
         let completion = op.await;
         completion.meta.result?;
 
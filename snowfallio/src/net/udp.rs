@@ -2,7 +2,7 @@
 
 use std::{
     io,
-    net::{SocketAddr, ToSocketAddrs},
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs},
     os::{
         fd::{AsRawFd, FromRawFd},
         unix::prelude::IntoRawFd,
@@ -11,10 +11,17 @@ use std::{
 
 use crate::{
     buf::{IoBuf, IoBufMut},
-    driver::{op::Op, shared_fd::SharedFd},
+    driver::{
+        op::{ControlBuf, Op},
+        shared_fd::SharedFd,
+    },
     io::{operation_canceled, CancelHandle, Split},
 };
 
+mod cmsg;
+
+pub use cmsg::{ControlMessages, Ecn, PacketInfo};
+
 /// A UDP socket.
 ///
 /// After creating a `UdpSocket` by [`bind`]ing it to a socket address, data can be
@@ -244,3 +251,314 @@ impl UdpSocket {
         op.read().await
     }
 }
+
+/// Batched io related methods, using UDP segmentation offloads.
+impl UdpSocket {
+    /// Sends the datagrams packed in `buf` to the remote address to which it
+    /// is connected, with a single syscall. `buf` is cut into datagrams of
+    /// `segment_size` bytes, the last one may be shorter. On success, returns
+    /// the number of bytes written.
+    ///
+    /// The segmentation is offloaded with `UDP_SEGMENT` (kernel 4.18+). The
+    /// kernel caps a batch to 64 datagrams and to the maximum datagram size.
+    pub async fn send_batch<T: IoBuf>(
+        &self,
+        buf: T,
+        segment_size: u16,
+    ) -> crate::BufResult<usize, T> {
+        self.send_msg(buf, None, &segment(segment_size)).await
+    }
+
+    /// Sends the datagrams packed in `buf` to the given address, with a
+    /// single syscall. See [`send_batch`](UdpSocket::send_batch).
+    pub async fn send_batch_to<T: IoBuf>(
+        &self,
+        buf: T,
+        segment_size: u16,
+        socket_addr: SocketAddr,
+    ) -> crate::BufResult<usize, T> {
+        self.send_msg(buf, Some(socket_addr), &segment(segment_size))
+            .await
+    }
+
+    /// Receives datagrams of a same origin coalesced by the kernel. On
+    /// success, returns the number of bytes read, the origin and the segment
+    /// size: the datagrams in `buf` are `segment_size` bytes long, apart from
+    /// the last one which may be shorter.
+    ///
+    /// Datagrams are only coalesced once `UDP_GRO` is enabled with
+    /// [`set_gro`](UdpSocket::set_gro), otherwise a single datagram is read
+    /// and the segment size is its length.
+    pub async fn recv_batch<T: IoBufMut>(
+        &self,
+        buf: T,
+    ) -> crate::BufResult<(usize, SocketAddr, usize), T> {
+        let (res, buf) = self.recv_msg(buf).await;
+        let res = res.map(|(n, addr, control)| {
+            let segment_size = control.segment_size.map_or(n, usize::from);
+            (n, addr, segment_size)
+        });
+        (res, buf)
+    }
+
+    /// Set value for the `UDP_GRO` option on this socket, letting the kernel
+    /// coalesce the received datagrams for [`recv_batch`](UdpSocket::recv_batch).
+    pub fn set_gro(&self, gro: bool) -> io::Result<()> {
+        self.set_flag(libc::SOL_UDP, libc::UDP_GRO, gro)
+    }
+
+    /// Get value of the `UDP_GRO` option on this socket.
+    pub fn gro(&self) -> io::Result<bool> {
+        self.flag(libc::SOL_UDP, libc::UDP_GRO)
+    }
+}
+
+fn segment(segment_size: u16) -> ControlMessages {
+    ControlMessages {
+        segment_size: Some(segment_size),
+        ..Default::default()
+    }
+}
+
+/// Ancillary data related methods
+impl UdpSocket {
+    /// Receives a single datagram message on the socket, along with its
+    /// control messages. On success, returns the number of bytes read, the
+    /// origin and the control messages.
+    ///
+    /// Control messages are only received for the options enabled on the
+    /// socket, e.g. with [`set_recv_pktinfo`](UdpSocket::set_recv_pktinfo).
+    pub async fn recv_msg<T: IoBufMut>(
+        &self,
+        buf: T,
+    ) -> crate::BufResult<(usize, SocketAddr, ControlMessages), T> {
+        let control = ControlBuf::with_capacity(cmsg::RECV_CAPACITY);
+        let op = Op::recv_msg_control(self.fd.clone(), buf, control).unwrap();
+        let (res, buf, control) = op.wait_control().await;
+        let res = res.map(|(n, addr)| (n, addr, ControlMessages::decode(&control)));
+        (res, buf)
+    }
+
+    /// Sends data on the socket with control messages, to the given address or
+    /// to the remote address to which it is connected if `socket_addr` is
+    /// None. On success, returns the number of bytes written.
+    pub async fn send_msg<T: IoBuf>(
+        &self,
+        buf: T,
+        socket_addr: Option<SocketAddr>,
+        control: &ControlMessages,
+    ) -> crate::BufResult<usize, T> {
+        // Spare the family lookup to the plain segmented sends.
+        let ipv6 = match control.family_specific() {
+            true => match self.is_ipv6() {
+                Ok(ipv6) => ipv6,
+                Err(e) => return (Err(e), buf),
+            },
+            false => false,
+        };
+        let control = control.encode(ipv6);
+        let op = Op::send_msg_control(self.fd.clone(), buf, socket_addr, control).unwrap();
+        op.wait().await
+    }
+
+    /// Set value for the `IP_PKTINFO` or `IPV6_RECVPKTINFO` option on this
+    /// socket, receiving the destination address of the datagrams.
+    pub fn set_recv_pktinfo(&self, enabled: bool) -> io::Result<()> {
+        match self.is_ipv6()? {
+            false => self.set_flag(libc::IPPROTO_IP, libc::IP_PKTINFO, enabled),
+            true => self.set_flag(libc::IPPROTO_IPV6, libc::IPV6_RECVPKTINFO, enabled),
+        }
+    }
+
+    /// Set value for the `IP_RECVTTL` or `IPV6_RECVHOPLIMIT` option on this
+    /// socket, receiving the hop limit of the datagrams.
+    pub fn set_recv_ttl(&self, enabled: bool) -> io::Result<()> {
+        match self.is_ipv6()? {
+            false => self.set_flag(libc::IPPROTO_IP, libc::IP_RECVTTL, enabled),
+            true => self.set_flag(libc::IPPROTO_IPV6, libc::IPV6_RECVHOPLIMIT, enabled),
+        }
+    }
+
+    /// Set value for the `IP_RECVTOS` or `IPV6_RECVTCLASS` option on this
+    /// socket, receiving the ECN codepoint of the datagrams.
+    pub fn set_recv_ecn(&self, enabled: bool) -> io::Result<()> {
+        match self.is_ipv6()? {
+            false => self.set_flag(libc::IPPROTO_IP, libc::IP_RECVTOS, enabled),
+            true => self.set_flag(libc::IPPROTO_IPV6, libc::IPV6_RECVTCLASS, enabled),
+        }
+    }
+
+    /// Set value for the `SO_TIMESTAMPNS` option on this socket, receiving
+    /// the time the kernel received the datagrams.
+    pub fn set_recv_timestamp(&self, enabled: bool) -> io::Result<()> {
+        self.set_flag(libc::SOL_SOCKET, libc::SO_TIMESTAMPNS, enabled)
+    }
+
+    fn set_flag(&self, level: libc::c_int, name: libc::c_int, enabled: bool) -> io::Result<()> {
+        setsockopt(&self.fd, level, name, enabled as libc::c_int)
+    }
+
+    fn flag(&self, level: libc::c_int, name: libc::c_int) -> io::Result<bool> {
+        getsockopt::<libc::c_int>(&self.fd, level, name).map(|v| v != 0)
+    }
+
+    fn is_ipv6(&self) -> io::Result<bool> {
+        getsockopt::<libc::c_int>(&self.fd, libc::SOL_SOCKET, libc::SO_DOMAIN)
+            .map(|domain| domain == libc::AF_INET6)
+    }
+}
+
+/// Multicast and broadcast related methods
+impl UdpSocket {
+    /// Executes an operation of the `IP_ADD_MEMBERSHIP` type, joining the
+    /// multicast group `multiaddr` on the interface with address `interface`.
+    /// `Ipv4Addr::UNSPECIFIED` lets the system choose the interface.
+    pub fn join_multicast_v4(&self, multiaddr: Ipv4Addr, interface: Ipv4Addr) -> io::Result<()> {
+        setsockopt(
+            &self.fd,
+            libc::IPPROTO_IP,
+            libc::IP_ADD_MEMBERSHIP,
+            ip_mreq(multiaddr, interface),
+        )
+    }
+
+    /// Executes an operation of the `IPV6_ADD_MEMBERSHIP` type, joining the
+    /// multicast group `multiaddr` on the interface of index `interface`. 0
+    /// lets the system choose the interface.
+    pub fn join_multicast_v6(&self, multiaddr: &Ipv6Addr, interface: u32) -> io::Result<()> {
+        setsockopt(
+            &self.fd,
+            libc::IPPROTO_IPV6,
+            libc::IPV6_ADD_MEMBERSHIP,
+            ipv6_mreq(multiaddr, interface),
+        )
+    }
+
+    /// Executes an operation of the `IP_DROP_MEMBERSHIP` type, leaving a
+    /// group joined with [`join_multicast_v4`](UdpSocket::join_multicast_v4).
+    pub fn leave_multicast_v4(&self, multiaddr: Ipv4Addr, interface: Ipv4Addr) -> io::Result<()> {
+        setsockopt(
+            &self.fd,
+            libc::IPPROTO_IP,
+            libc::IP_DROP_MEMBERSHIP,
+            ip_mreq(multiaddr, interface),
+        )
+    }
+
+    /// Executes an operation of the `IPV6_DROP_MEMBERSHIP` type, leaving a
+    /// group joined with [`join_multicast_v6`](UdpSocket::join_multicast_v6).
+    pub fn leave_multicast_v6(&self, multiaddr: &Ipv6Addr, interface: u32) -> io::Result<()> {
+        setsockopt(
+            &self.fd,
+            libc::IPPROTO_IPV6,
+            libc::IPV6_DROP_MEMBERSHIP,
+            ipv6_mreq(multiaddr, interface),
+        )
+    }
+
+    /// Set value for the `IP_MULTICAST_LOOP` option on this socket, looping
+    /// the multicast datagrams sent back to the local sockets.
+    pub fn set_multicast_loop_v4(&self, on: bool) -> io::Result<()> {
+        self.set_flag(libc::IPPROTO_IP, libc::IP_MULTICAST_LOOP, on)
+    }
+
+    /// Get value of the `IP_MULTICAST_LOOP` option on this socket.
+    pub fn multicast_loop_v4(&self) -> io::Result<bool> {
+        self.flag(libc::IPPROTO_IP, libc::IP_MULTICAST_LOOP)
+    }
+
+    /// Set value for the `IPV6_MULTICAST_LOOP` option on this socket, looping
+    /// the multicast datagrams sent back to the local sockets.
+    pub fn set_multicast_loop_v6(&self, on: bool) -> io::Result<()> {
+        self.set_flag(libc::IPPROTO_IPV6, libc::IPV6_MULTICAST_LOOP, on)
+    }
+
+    /// Get value of the `IPV6_MULTICAST_LOOP` option on this socket.
+    pub fn multicast_loop_v6(&self) -> io::Result<bool> {
+        self.flag(libc::IPPROTO_IPV6, libc::IPV6_MULTICAST_LOOP)
+    }
+
+    /// Set value for the `IP_MULTICAST_TTL` option on this socket, the hop
+    /// limit of the multicast datagrams sent. It defaults to 1, keeping them
+    /// in the local network.
+    pub fn set_multicast_ttl_v4(&self, ttl: u32) -> io::Result<()> {
+        setsockopt(
+            &self.fd,
+            libc::IPPROTO_IP,
+            libc::IP_MULTICAST_TTL,
+            ttl as libc::c_int,
+        )
+    }
+
+    /// Get value of the `IP_MULTICAST_TTL` option on this socket.
+    pub fn multicast_ttl_v4(&self) -> io::Result<u32> {
+        getsockopt::<libc::c_int>(&self.fd, libc::IPPROTO_IP, libc::IP_MULTICAST_TTL)
+            .map(|ttl| ttl as u32)
+    }
+
+    /// Set value for the `SO_BROADCAST` option on this socket, allowing to
+    /// send datagrams to a broadcast address.
+    pub fn set_broadcast(&self, on: bool) -> io::Result<()> {
+        self.set_flag(libc::SOL_SOCKET, libc::SO_BROADCAST, on)
+    }
+
+    /// Get value of the `SO_BROADCAST` option on this socket.
+    pub fn broadcast(&self) -> io::Result<bool> {
+        self.flag(libc::SOL_SOCKET, libc::SO_BROADCAST)
+    }
+
+    /// Set value for the `IP_TTL` option on this socket, the hop limit of the
+    /// unicast datagrams sent.
+    pub fn set_ttl(&self, ttl: u32) -> io::Result<()> {
+        setsockopt(&self.fd, libc::IPPROTO_IP, libc::IP_TTL, ttl as libc::c_int)
+    }
+
+    /// Get value of the `IP_TTL` option on this socket.
+    pub fn ttl(&self) -> io::Result<u32> {
+        getsockopt::<libc::c_int>(&self.fd, libc::IPPROTO_IP, libc::IP_TTL).map(|ttl| ttl as u32)
+    }
+}
+
+fn ip_mreq(multiaddr: Ipv4Addr, interface: Ipv4Addr) -> libc::ip_mreq {
+    libc::ip_mreq {
+        imr_multiaddr: libc::in_addr {
+            s_addr: u32::from_ne_bytes(multiaddr.octets()),
+        },
+        imr_interface: libc::in_addr {
+            s_addr: u32::from_ne_bytes(interface.octets()),
+        },
+    }
+}
+
+fn ipv6_mreq(multiaddr: &Ipv6Addr, interface: u32) -> libc::ipv6_mreq {
+    libc::ipv6_mreq {
+        ipv6mr_multiaddr: libc::in6_addr {
+            s6_addr: multiaddr.octets(),
+        },
+        ipv6mr_interface: interface as _,
+    }
+}
+
+fn setsockopt<T>(fd: &SharedFd, level: libc::c_int, name: libc::c_int, value: T) -> io::Result<()> {
+    crate::syscall!(setsockopt(
+        fd.raw_fd(),
+        level,
+        name,
+        &value as *const T as *const libc::c_void,
+        std::mem::size_of::<T>() as libc::socklen_t,
+    ))
+    .map(drop)
+}
+
+fn getsockopt<T: Default>(fd: &SharedFd, level: libc::c_int, name: libc::c_int) -> io::Result<T> {
+    let mut value = T::default();
+    let mut len = std::mem::size_of::<T>() as libc::socklen_t;
+    crate::syscall!(getsockopt(
+        fd.raw_fd(),
+        level,
+        name,
+        &mut value as *mut T as *mut libc::c_void,
+        &mut len,
+    ))?;
+    Ok(value)
+}
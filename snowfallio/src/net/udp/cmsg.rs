@@ -0,0 +1,190 @@
+use std::{
+    mem::size_of,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    time::{Duration, SystemTime},
+};
+
+use crate::driver::op::{cmsg_space, ControlBuf};
+
+/// Room for every control message decoded by [`ControlMessages`].
+pub(super) const RECV_CAPACITY: usize = cmsg_space(size_of::<libc::in6_pktinfo>())
+    + 4 * cmsg_space(size_of::<libc::c_int>())
+    + cmsg_space(size_of::<libc::timespec>());
+
+/// Ancillary data of a datagram, received with [`recv_msg`] or sent with
+/// [`send_msg`].
+///
+/// A field is only filled on receive once the matching option is enabled on
+/// the socket, e.g. with [`set_recv_pktinfo`]. On send, every field set is
+/// attached to the datagram.
+///
+/// [`recv_msg`]: super::UdpSocket::recv_msg
+/// [`send_msg`]: super::UdpSocket::send_msg
+/// [`set_recv_pktinfo`]: super::UdpSocket::set_recv_pktinfo
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ControlMessages {
+    /// `IP_PKTINFO` or `IPV6_PKTINFO`: the destination address of a received
+    /// datagram, or the source address of a sent one.
+    pub pktinfo: Option<PacketInfo>,
+    /// `IP_TTL` or `IPV6_HOPLIMIT`: the hop limit of the datagram.
+    pub ttl: Option<u8>,
+    /// `IP_TOS` or `IPV6_TCLASS`: the ECN codepoint of the datagram. On send,
+    /// the rest of the TOS byte is zeroed.
+    pub ecn: Option<Ecn>,
+    /// `SO_TIMESTAMPNS`: the time the kernel received the datagram. It is
+    /// ignored on send.
+    pub timestamp: Option<SystemTime>,
+    /// `UDP_GRO` or `UDP_SEGMENT`: the size of the datagrams coalesced in the
+    /// buffer.
+    pub segment_size: Option<u16>,
+}
+
+/// Addressing information of a datagram.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PacketInfo {
+    /// Destination address on receive, source address on send.
+    pub addr: IpAddr,
+    /// Index of the interface the datagram was received on, or is to be sent
+    /// from. 0 lets the routing table decide on send.
+    pub interface: u32,
+}
+
+/// Explicit Congestion Notification codepoint (RFC 3168).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Ecn {
+    /// Not ECN-capable transport.
+    NotEct = 0b00,
+    /// ECN-capable transport, ECT(1).
+    Ect1 = 0b01,
+    /// ECN-capable transport, ECT(0).
+    Ect0 = 0b10,
+    /// Congestion experienced.
+    Ce = 0b11,
+}
+
+impl Ecn {
+    fn from_tos(tos: u8) -> Ecn {
+        match tos & 0b11 {
+            0b00 => Ecn::NotEct,
+            0b01 => Ecn::Ect1,
+            0b10 => Ecn::Ect0,
+            _ => Ecn::Ce,
+        }
+    }
+}
+
+impl ControlMessages {
+    /// Decode the control messages received.
+    pub(super) fn decode(control: &ControlBuf) -> ControlMessages {
+        let mut messages = ControlMessages::default();
+        for (level, ty, data) in control.iter() {
+            match (level, ty) {
+                (libc::IPPROTO_IP, libc::IP_PKTINFO) => {
+                    if let Some(info) = read::<libc::in_pktinfo>(data) {
+                        messages.pktinfo = Some(PacketInfo {
+                            addr: Ipv4Addr::from(info.ipi_addr.s_addr.to_ne_bytes()).into(),
+                            interface: info.ipi_ifindex as u32,
+                        });
+                    }
+                }
+                (libc::IPPROTO_IPV6, libc::IPV6_PKTINFO) => {
+                    if let Some(info) = read::<libc::in6_pktinfo>(data) {
+                        messages.pktinfo = Some(PacketInfo {
+                            addr: Ipv6Addr::from(info.ipi6_addr.s6_addr).into(),
+                            interface: info.ipi6_ifindex,
+                        });
+                    }
+                }
+                (libc::IPPROTO_IP, libc::IP_TTL) | (libc::IPPROTO_IPV6, libc::IPV6_HOPLIMIT) => {
+                    messages.ttl = read::<libc::c_int>(data).map(|ttl| ttl as u8);
+                }
+                // The TOS byte comes alone on IPv4, but as an int on IPv6.
+                (libc::IPPROTO_IP, libc::IP_TOS) => {
+                    messages.ecn = data.first().map(|tos| Ecn::from_tos(*tos));
+                }
+                (libc::IPPROTO_IPV6, libc::IPV6_TCLASS) => {
+                    messages.ecn = read::<libc::c_int>(data).map(|tos| Ecn::from_tos(tos as u8));
+                }
+                (libc::SOL_SOCKET, libc::SCM_TIMESTAMPNS) => {
+                    messages.timestamp = read::<libc::timespec>(data).map(|ts| {
+                        SystemTime::UNIX_EPOCH + Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32)
+                    });
+                }
+                (libc::SOL_UDP, libc::UDP_GRO) => {
+                    messages.segment_size = read::<libc::c_int>(data).map(|size| size as u16);
+                }
+                _ => {}
+            }
+        }
+        messages
+    }
+
+    /// Whether the encoding of the messages depends on the socket family.
+    pub(super) fn family_specific(&self) -> bool {
+        self.pktinfo.is_some() || self.ttl.is_some() || self.ecn.is_some()
+    }
+
+    /// Encode the control messages to send on a socket of the given family.
+    /// `ipv6` is ignored unless [`family_specific`](Self::family_specific).
+    pub(super) fn encode(&self, ipv6: bool) -> ControlBuf {
+        let mut control = ControlBuf::default();
+        if let Some(info) = self.pktinfo {
+            match info.addr {
+                IpAddr::V4(addr) if !ipv6 => {
+                    let mut pktinfo: libc::in_pktinfo = unsafe { std::mem::zeroed() };
+                    pktinfo.ipi_ifindex = info.interface as _;
+                    pktinfo.ipi_spec_dst.s_addr = u32::from_ne_bytes(addr.octets());
+                    control.push(libc::IPPROTO_IP, libc::IP_PKTINFO, bytes(&pktinfo));
+                }
+                addr => {
+                    let addr = match addr {
+                        IpAddr::V4(addr) => addr.to_ipv6_mapped(),
+                        IpAddr::V6(addr) => addr,
+                    };
+                    let mut pktinfo: libc::in6_pktinfo = unsafe { std::mem::zeroed() };
+                    pktinfo.ipi6_ifindex = info.interface;
+                    pktinfo.ipi6_addr.s6_addr = addr.octets();
+                    control.push(libc::IPPROTO_IPV6, libc::IPV6_PKTINFO, bytes(&pktinfo));
+                }
+            }
+        }
+        if let Some(ttl) = self.ttl {
+            let ttl = ttl as libc::c_int;
+            match ipv6 {
+                false => control.push(libc::IPPROTO_IP, libc::IP_TTL, bytes(&ttl)),
+                true => control.push(libc::IPPROTO_IPV6, libc::IPV6_HOPLIMIT, bytes(&ttl)),
+            }
+        }
+        if let Some(ecn) = self.ecn {
+            let tos = ecn as libc::c_int;
+            match ipv6 {
+                false => control.push(libc::IPPROTO_IP, libc::IP_TOS, bytes(&tos)),
+                true => control.push(libc::IPPROTO_IPV6, libc::IPV6_TCLASS, bytes(&tos)),
+            }
+        }
+        if let Some(segment_size) = self.segment_size {
+            control.push(
+                libc::SOL_UDP,
+                libc::UDP_SEGMENT,
+                &segment_size.to_ne_bytes(),
+            );
+        }
+        control
+    }
+}
+
+/// Read a `T` out of the data of a control message.
+fn read<T: Copy>(data: &[u8]) -> Option<T> {
+    if data.len() < size_of::<T>() {
+        return None;
+    }
+    // Safety: the data holds a `T`, read unaligned as the data is only
+    // aligned for `cmsghdr`.
+    Some(unsafe { std::ptr::read_unaligned(data.as_ptr() as *const T) })
+}
+
+fn bytes<T>(value: &T) -> &[u8] {
+    // Safety: the option values are plain C structs and ints.
+    unsafe { std::slice::from_raw_parts(value as *const T as *const u8, size_of::<T>()) }
+}
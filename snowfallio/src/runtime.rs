@@ -1,11 +1,24 @@
-use std::future::Future;
+//! Runtime and task spawning.
 
+use std::{
+    future::Future,
+    panic::Location,
+    rc::{Rc, Weak},
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "sync")]
+use crate::task::{remote_pair, RemoteJoinHandle};
 use crate::{
     driver::Driver,
-    scheduler::{LocalScheduler, TaskQueue},
+    metrics::{Metrics, MetricsHandle, RuntimeMetrics},
+    scheduler::{LocalScheduler, Priority, TaskQueue},
     task::{
+        alloc::TaskCache,
+        coop,
+        dump::Registry,
         new_task,
-        waker_fn::{dummy_waker, set_poll, should_poll},
+        waker_fn::{dummy_waker, poll_pending, set_poll, should_poll},
         JoinHandle,
     },
     time::driver::Handle as TimeHandle,
@@ -15,9 +28,12 @@ use crate::{
 thread_local! {
     pub(crate) static DEFAULT_CTX: Context = Context {
         thread_id: crate::utils::thread_id::DEFAULT_THREAD_ID,
-        unpark_cache: std::cell::RefCell::new(fxhash::FxHashMap::default()),
-        waker_sender_cache: std::cell::RefCell::new(fxhash::FxHashMap::default()),
+        thread_cache: std::cell::RefCell::new(fxhash::FxHashMap::default()),
+        deferred_unparks: std::cell::RefCell::new(Vec::new()),
         tasks: Default::default(),
+        metrics: Default::default(),
+        task_registry: None,
+        task_cache: None,
         time_handle: None,
         blocking_handle: crate::blocking::BlockingHandle::Empty(crate::blocking::BlockingStrategy::Panic),
     };
@@ -25,22 +41,34 @@ thread_local! {
 
 scoped_thread_local!(pub(crate) static CURRENT: Context);
 
+pub use crate::task::dump::dump;
+
 pub(crate) struct Context {
     /// Thread id(not the kernel thread id but a generated unique number)
     pub(crate) thread_id: usize,
 
-    /// Thread unpark handles
+    /// Registrations of the other threads
     #[cfg(feature = "sync")]
-    pub(crate) unpark_cache:
-        std::cell::RefCell<fxhash::FxHashMap<usize, crate::driver::UnparkHandle>>,
+    pub(crate) thread_cache: std::cell::RefCell<
+        fxhash::FxHashMap<usize, std::sync::Arc<crate::driver::thread::Registration>>,
+    >,
 
-    /// Waker sender cache
+    /// Threads to unpark at the end of the tick
     #[cfg(feature = "sync")]
-    pub(crate) waker_sender_cache:
-        std::cell::RefCell<fxhash::FxHashMap<usize, flume::Sender<std::task::Waker>>>,
+    pub(crate) deferred_unparks: std::cell::RefCell<Vec<usize>>,
 
     /// Owned task set and local run queue
     pub(crate) tasks: TaskQueue,
+
+    /// Scheduler metrics
+    pub(crate) metrics: Metrics,
+
+    /// Live task registry, if task dump is enabled
+    pub(crate) task_registry: Option<Rc<Registry>>,
+
+    /// Free task allocations, if task recycling is enabled
+    pub(crate) task_cache: Option<TaskCache>,
+
     /// Time Handle
     pub(crate) time_handle: Option<TimeHandle>,
 
@@ -56,9 +84,12 @@ impl Context {
 
         Self {
             thread_id,
-            unpark_cache: std::cell::RefCell::new(fxhash::FxHashMap::default()),
-            waker_sender_cache: std::cell::RefCell::new(fxhash::FxHashMap::default()),
+            thread_cache: std::cell::RefCell::new(fxhash::FxHashMap::default()),
+            deferred_unparks: std::cell::RefCell::new(Vec::new()),
             tasks: TaskQueue::default(),
+            metrics: Metrics::default(),
+            task_registry: None,
+            task_cache: None,
             time_handle: None,
             blocking_handle,
         }
@@ -71,124 +102,433 @@ impl Context {
         Self {
             thread_id,
             tasks: TaskQueue::default(),
+            metrics: Metrics::default(),
+            task_registry: None,
+            task_cache: None,
             time_handle: None,
         }
     }
 
-    #[allow(unused)]
+    /// Call `f` with the registration of a live thread, cached after the first
+    /// lookup. Returns `None` if the runtime of the thread has been dropped.
     #[cfg(feature = "sync")]
-    pub(crate) fn unpark_thread(&self, id: usize) {
-        use crate::driver::{thread::get_unpark_handle, unpark::Unpark};
-        if let Some(handle) = self.unpark_cache.borrow().get(&id) {
-            handle.unpark();
-            return;
-        }
-
-        if let Some(v) = get_unpark_handle(id) {
-            // Write back to local cache
-            let w = v.clone();
-            self.unpark_cache.borrow_mut().insert(id, w);
-            v.unpark();
-            return;
+    fn with_thread<R>(
+        &self,
+        id: usize,
+        f: impl FnOnce(&crate::driver::thread::Registration) -> R,
+    ) -> Option<R> {
+        let mut cache = self.thread_cache.borrow_mut();
+        if let Some(registration) = cache.get(&id) {
+            if registration.is_alive() {
+                return Some(f(registration));
+            }
+            // Stale, thread ids are never reused.
+            cache.remove(&id);
+            return None;
         }
+        let registration = crate::driver::thread::lookup(id)?;
+        let r = f(&registration);
+        cache.insert(id, registration);
+        Some(r)
+    }
 
-        panic!("thread to unpark has not been registered");
+    #[allow(unused)]
+    #[cfg(feature = "sync")]
+    pub(crate) fn unpark_thread(&self, id: usize) {
+        use crate::driver::unpark::Unpark;
+        self.with_thread(id, |thread| thread.unpark.unpark());
     }
 
+    /// Send a waker to its thread. It is dropped if the runtime of the thread
+    /// is gone.
     #[allow(unused)]
     #[cfg(feature = "sync")]
     pub(crate) fn send_waker(&self, id: usize, w: std::task::Waker) {
-        use crate::driver::thread::get_waker_sender;
-        if let Some(sender) = self.waker_sender_cache.borrow().get(&id) {
-            let _ = sender.send(w);
-            return;
-        }
+        let mut w = Some(w);
+        self.with_thread(id, |thread| thread.waker_sender.send(w.take().unwrap()));
+    }
 
-        if let Some(s) = get_waker_sender(id) {
-            // Write back to local cache
-            let _ = s.send(w);
-            self.waker_sender_cache.borrow_mut().insert(id, s);
-            return;
+    /// Wake `w` on the runtime thread `id`, or in place if `id` is not a live
+    /// runtime thread.
+    ///
+    /// With `defer`, the thread is unparked by the next
+    /// [`flush_unparks`](Self::flush_unparks) instead, so that all the wakes
+    /// sent to a thread during a tick share a single eventfd write.
+    #[cfg(feature = "sync")]
+    pub(crate) fn wake_on(&self, id: usize, w: std::task::Waker, defer: bool) {
+        use crate::driver::unpark::Unpark;
+        let mut w = Some(w);
+        self.with_thread(id, |thread| {
+            let _ = thread.waker_sender.send(w.take().unwrap());
+            if !defer {
+                let _ = thread.unpark.unpark();
+            }
+        });
+        match w {
+            Some(w) => w.wake(),
+            None if defer => {
+                let mut deferred = self.deferred_unparks.borrow_mut();
+                if !deferred.contains(&id) {
+                    deferred.push(id);
+                }
+            }
+            None => {}
         }
+    }
 
-        panic!("sender has not been registered");
+    /// Unpark the threads of the deferred wakes.
+    #[cfg(feature = "sync")]
+    pub(crate) fn flush_unparks(&self) {
+        let deferred = std::mem::take(&mut *self.deferred_unparks.borrow_mut());
+        for id in deferred {
+            self.unpark_thread(id);
+        }
     }
 }
 
 /// Monoio runtime
 pub struct Runtime<D> {
     pub(crate) driver: D,
-    pub(crate) context: Context,
+    pub(crate) context: Rc<Context>,
+    pub(crate) hooks: Hooks,
+    pub(crate) spin_before_park: Option<Duration>,
+}
+
+/// A user callback invoked by the runtime.
+pub(crate) type Callback = std::sync::Arc<dyn Fn() + Send + Sync>;
+
+/// Lifecycle callbacks set with the [`RuntimeBuilder`](crate::RuntimeBuilder).
+#[derive(Default, Clone)]
+pub(crate) struct Hooks {
+    pub(crate) on_thread_start: Option<Callback>,
+    pub(crate) on_thread_stop: Option<Callback>,
+    pub(crate) before_park: Option<Callback>,
+    pub(crate) after_unpark: Option<Callback>,
+}
+
+impl Hooks {
+    #[inline]
+    fn call(hook: &Option<Callback>) {
+        if let Some(f) = hook {
+            f();
+        }
+    }
+}
+
+/// Calls `on_thread_stop` when `block_on` returns or unwinds.
+struct ThreadStopGuard<'a>(&'a Hooks);
+
+impl Drop for ThreadStopGuard<'_> {
+    fn drop(&mut self) {
+        Hooks::call(&self.0.on_thread_stop);
+    }
 }
 
 impl<D> Runtime<D> {
-    /// Block on
-    pub fn block_on<F>(&mut self, future: F) -> F::Output
+    /// The id of the thread the runtime was built for, see
+    /// [`spawn_on`](crate::spawn_on).
+    pub fn thread_id(&self) -> usize {
+        self.context.thread_id
+    }
+
+    /// Take a snapshot of the runtime metrics.
+    pub fn metrics(&self) -> RuntimeMetrics {
+        self.context.metrics.handle().snapshot()
+    }
+
+    /// Get a handle to read the runtime metrics from any thread.
+    pub fn metrics_handle(&self) -> MetricsHandle {
+        self.context.metrics.handle()
+    }
+
+    /// Get a [`Handle`] to spawn tasks onto the runtime from its thread, while
+    /// it is not running.
+    pub fn handle(&self) -> Handle {
+        Handle {
+            context: Rc::downgrade(&self.context),
+        }
+    }
+
+    /// Run one pass over the ready tasks and submit their io without
+    /// blocking. Returns the number of polled tasks.
+    ///
+    /// This drives the runtime incrementally, e.g. from the event loop of
+    /// another library. Tasks spawned with a [`Handle`] before the call are
+    /// run. The pass is bounded, so a task that keeps waking itself does not
+    /// hold the caller: call it again while it returns a non zero count to
+    /// drain the runtime.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called inside a runtime.
+    pub fn run_until_idle(&mut self) -> usize
+    where
+        D: Driver,
+    {
+        self.enter(|| self.run_until_idle_inner())
+    }
+
+    /// Wait for io, timers or remote wakeups for up to `timeout`, forever if
+    /// `None`, unless some task is already ready, then run one pass over the
+    /// tasks like [`run_until_idle`](Self::run_until_idle). Returns whether any
+    /// task was polled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called inside a runtime.
+    pub fn turn(&mut self, timeout: Option<Duration>) -> bool
+    where
+        D: Driver,
+    {
+        self.enter(|| {
+            let metrics = &self.context.metrics;
+            // Time spent outside the runtime between turns is not busy time.
+            metrics.start();
+            let _ = self.driver.submit();
+            if self.context.tasks.is_empty() {
+                Hooks::call(&self.hooks.before_park);
+                #[cfg(feature = "sync")]
+                self.context.flush_unparks();
+                if self.context.tasks.is_empty() {
+                    let _ = metrics.park(|| match timeout {
+                        Some(timeout) => self.driver.park_timeout(timeout),
+                        None => self.driver.park(),
+                    });
+                    Hooks::call(&self.hooks.after_unpark);
+                }
+            }
+            let polled = self.run_until_idle_inner();
+            metrics.stop();
+            polled != 0
+        })
+    }
+
+    fn enter<R>(&self, f: impl FnOnce() -> R) -> R
     where
-        F: Future,
         D: Driver,
     {
         assert!(
             !CURRENT.is_set(),
             "Can not start a runtime inside a runtime"
         );
+        self.driver.with(|| CURRENT.set(&self.context, f))
+    }
+
+    fn run_until_idle_inner(&self) -> usize
+    where
+        D: Driver,
+    {
+        let polled = self.run_tasks();
+        self.context.metrics.end_tick(self.context.tasks.len());
+        let _ = self.driver.submit();
+        polled
+    }
+
+    /// Poll the ready tasks, returns how many were polled.
+    fn run_tasks(&self) -> usize {
+        let metrics = &self.context.metrics;
+        let mut polled = 0;
+        // Consume all tasks(with max round to prevent io starvation)
+        let mut max_round = self.context.tasks.len() * 2;
+        while let Some(t) = self.context.tasks.pop() {
+            match &self.context.task_registry {
+                None => metrics.poll(|| coop::budget(|| t.run())),
+                Some(registry) => {
+                    let key = t.key();
+                    metrics.poll(|| registry.poll(key, || coop::budget(|| t.run())))
+                }
+            }
+            polled += 1;
+            if max_round == 0 {
+                // maybe there's a looping task
+                break;
+            } else {
+                max_round -= 1;
+            }
+        }
+        #[cfg(feature = "sync")]
+        self.context.flush_unparks();
+        polled
+    }
 
+    /// Block on
+    pub fn block_on<F>(&mut self, future: F) -> F::Output
+    where
+        F: Future,
+        D: Driver,
+    {
         let waker = dummy_waker();
         let cx = &mut std::task::Context::from_waker(&waker);
 
-        self.driver.with(|| {
-            CURRENT.set(&self.context, || {
-                #[cfg(feature = "sync")]
-                let join = unsafe { spawn_without_static(future) };
-                #[cfg(not(feature = "sync"))]
-                let join = future;
-
-                pin!(join);
-                set_poll();
+        self.enter(|| {
+            #[cfg(feature = "sync")]
+            let join = unsafe { spawn_without_static(future) };
+            #[cfg(not(feature = "sync"))]
+            let join = future;
+
+            pin!(join);
+            set_poll();
+            let hooks = &self.hooks;
+            Hooks::call(&hooks.on_thread_start);
+            let _stop_guard = ThreadStopGuard(hooks);
+            let metrics = &self.context.metrics;
+            metrics.start();
+            loop {
                 loop {
-                    loop {
-                        // Consume all tasks(with max round to prevent io starvation)
-                        let mut max_round = self.context.tasks.len() * 2;
-                        while let Some(t) = self.context.tasks.pop() {
-                            t.run();
-                            if max_round == 0 {
-                                // maybe there's a looping task
-                                break;
-                            } else {
-                                max_round -= 1;
-                            }
+                    self.run_tasks();
+
+                    // Check main future. It is polled at most once per round
+                    // so that a main future which keeps waking itself (e.g.
+                    // after exhausting its budget) still lets tasks and io run.
+                    if should_poll() {
+                        let poll = metrics.poll(|| coop::budget(|| join.as_mut().poll(cx)));
+                        #[cfg(feature = "sync")]
+                        self.context.flush_unparks();
+                        // check if ready
+                        if let std::task::Poll::Ready(t) = poll {
+                            metrics.end_tick(self.context.tasks.len());
+                            metrics.stop();
+                            return t;
                         }
+                    }
+                    metrics.end_tick(self.context.tasks.len());
 
-                        // Check main future
-                        while should_poll() {
-                            // check if ready
-                            if let std::task::Poll::Ready(t) = join.as_mut().poll(cx) {
-                                return t;
-                            }
-                        }
+                    if self.context.tasks.is_empty() && !poll_pending() {
+                        // No task to execute, we should wait for io blockingly
+                        // Hot path
+                        break;
+                    }
 
-                        if self.context.tasks.is_empty() {
-                            // No task to execute, we should wait for io blockingly
-                            // Hot path
-                            break;
-                        }
+                    // Cold path
+                    let _ = self.driver.submit();
+                }
 
-                        // Cold path
-                        let _ = self.driver.submit();
+                if let Some(spin) = self.spin_before_park {
+                    if self.spin(spin) {
+                        metrics.spin_wakeup();
+                        continue;
                     }
+                }
 
-                    // Wait and Process CQ(the error is ignored for not debug mode)
-                    #[cfg(not(all(debug_assertions, feature = "debug")))]
-                    let _ = self.driver.park();
+                // Wait and Process CQ(the error is ignored for not debug mode)
+                Hooks::call(&hooks.before_park);
+                if !self.context.tasks.is_empty() || poll_pending() {
+                    // The callback scheduled some work or woke the main
+                    // future, run it first
+                    continue;
+                }
+                // Unpark the threads woken by the callbacks before sleeping.
+                #[cfg(feature = "sync")]
+                self.context.flush_unparks();
+                #[cfg(not(all(debug_assertions, feature = "debug")))]
+                let _ = metrics.park(|| self.driver.park());
 
-                    #[cfg(all(debug_assertions, feature = "debug"))]
-                    if let Err(e) = self.driver.park() {
-                        trace!("park error: {:?}", e);
-                    }
+                #[cfg(all(debug_assertions, feature = "debug"))]
+                if let Err(e) = metrics.park(|| self.driver.park()) {
+                    trace!("park error: {:?}", e);
                 }
-            })
+                Hooks::call(&hooks.after_unpark);
+            }
         })
     }
+
+    /// Reap completions without waiting for up to `duration`, returns whether
+    /// some work became ready.
+    fn spin(&self, duration: Duration) -> bool
+    where
+        D: Driver,
+    {
+        let deadline = Instant::now() + duration;
+        loop {
+            let _ = self.driver.submit();
+            if !self.context.tasks.is_empty() || poll_pending() {
+                return true;
+            }
+            if Instant::now() >= deadline {
+                return false;
+            }
+            std::hint::spin_loop();
+        }
+    }
+}
+
+/// A handle to spawn tasks onto a [`Runtime`] from its thread while it is not
+/// running, e.g. from callbacks of another event loop.
+///
+/// The handle is `!Send`. Spawned tasks are queued and run by the next
+/// [`Runtime::block_on`], [`Runtime::run_until_idle`] or [`Runtime::turn`].
+/// Inside the runtime, [`spawn`] can be used instead.
+///
+/// # Examples
+///
+/// ```no_run
+/// let mut rt = snowfallio::RuntimeBuilder::<snowfallio::IoUringDriver>::new()
+///     .build()
+///     .unwrap();
+/// let handle = rt.handle();
+///
+/// // e.g. in a callback of a GUI toolkit
+/// handle.spawn(async {
+///     println!("hello from the runtime");
+/// });
+///
+/// // e.g. in an idle callback of the same toolkit
+/// rt.run_until_idle();
+/// ```
+#[derive(Clone)]
+pub struct Handle {
+    context: Weak<Context>,
+}
+
+impl Handle {
+    /// Spawns a new asynchronous task, returning a [`JoinHandle`] for it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the runtime has been dropped, or if called from another
+    /// runtime.
+    #[track_caller]
+    pub fn spawn<T>(&self, future: T) -> JoinHandle<T::Output>
+    where
+        T: Future + 'static,
+        T::Output: 'static,
+    {
+        self.spawn_with_priority(Priority::Normal, future)
+    }
+
+    /// Spawns a new asynchronous task with the given [`Priority`], returning a
+    /// [`JoinHandle`] for it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the runtime has been dropped, or if called from another
+    /// runtime.
+    #[track_caller]
+    pub fn spawn_with_priority<T>(&self, priority: Priority, future: T) -> JoinHandle<T::Output>
+    where
+        T: Future + 'static,
+        T::Output: 'static,
+    {
+        let location = Location::caller();
+        let context = self
+            .context
+            .upgrade()
+            .expect("the runtime of the handle has been dropped");
+        if CURRENT.is_set() {
+            assert!(
+                CURRENT.with(|ctx| std::ptr::eq(ctx, &*context)),
+                "Can not spawn onto a runtime from another runtime"
+            );
+            return spawn_inner(future, priority, None, location);
+        }
+        CURRENT.set(&context, || spawn_inner(future, priority, None, location))
+    }
+}
+
+impl std::fmt::Debug for Handle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Handle").finish()
+    }
 }
 
 /// Spawns a new asynchronous task, returning a [`JoinHandle`] for it.
@@ -217,23 +557,148 @@ impl<D> Runtime<D> {
 ///     handle.await;
 /// }
 /// ```
+#[track_caller]
 pub fn spawn<T>(future: T) -> JoinHandle<T::Output>
 where
     T: Future + 'static,
     T::Output: 'static,
 {
+    spawn_inner(future, Priority::Normal, None, Location::caller())
+}
+
+/// Spawns a new asynchronous task with the given [`Priority`], returning a
+/// [`JoinHandle`] for it.
+///
+/// The task is pushed into the run queue of its priority and stays there every
+/// time it is woken. See [`spawn`] for more details.
+///
+/// [`JoinHandle`]: snowfallio::task::JoinHandle
+///
+/// # Examples
+///
+/// ```no_run
+/// use snowfallio::task::Priority;
+///
+/// #[snowfallio::main]
+/// async fn main() {
+///     let handle = snowfallio::spawn_with_priority(Priority::Background, async {
+///         println!("hello from a background task");
+///     });
+///
+///     handle.await;
+/// }
+/// ```
+#[track_caller]
+pub fn spawn_with_priority<T>(priority: Priority, future: T) -> JoinHandle<T::Output>
+where
+    T: Future + 'static,
+    T::Output: 'static,
+{
+    spawn_inner(future, priority, None, Location::caller())
+}
+
+pub(crate) fn spawn_inner<T>(
+    future: T,
+    priority: Priority,
+    name: Option<&str>,
+    location: &'static Location<'static>,
+) -> JoinHandle<T::Output>
+where
+    T: Future + 'static,
+    T::Output: 'static,
+{
+    let registry = CURRENT.with(|ctx| ctx.task_registry.as_ref().map(|r| r.release_queue()));
     let (task, join) = new_task(
         crate::utils::thread_id::get_current_thread_id(),
         future,
-        LocalScheduler,
+        LocalScheduler::new(priority, registry),
     );
 
     CURRENT.with(|ctx| {
-        ctx.tasks.push(task);
+        ctx.metrics.task_spawned();
+        if let Some(registry) = &ctx.task_registry {
+            registry.register(&task, name.map(ToOwned::to_owned), location);
+        }
+        ctx.tasks.push(task, priority);
     });
     join
 }
 
+/// Spawns a task on the runtime running on the thread `thread_id`, returning a
+/// [`RemoteJoinHandle`] for it.
+///
+/// `f` is sent to the target thread and called there to build the future, so
+/// the future itself does not need to be `Send`. The returned handle is `Send`
+/// and can be awaited from any runtime, or from the target runtime itself.
+///
+/// If there is no runtime on `thread_id`, or it is dropped before the task
+/// completes, the handle resolves to [`JoinError::Canceled`].
+///
+/// The id of a runtime thread is returned by [`current_thread_id`] or
+/// [`Runtime::thread_id`].
+///
+/// [`JoinError::Canceled`]: crate::blocking::JoinError::Canceled
+///
+/// # Examples
+///
+/// ```no_run
+/// let (tx, rx) = std::sync::mpsc::channel();
+/// std::thread::spawn(move || {
+///     snowfallio::start::<snowfallio::IoUringDriver, _>(async move {
+///         tx.send(snowfallio::current_thread_id()).unwrap();
+///         std::future::pending::<()>().await;
+///     });
+/// });
+/// let target = rx.recv().unwrap();
+///
+/// snowfallio::start::<snowfallio::IoUringDriver, _>(async move {
+///     let handle = snowfallio::spawn_on(target, || async {
+///         // `Rc` is fine, the future never leaves the target thread.
+///         let shard = std::rc::Rc::new(42);
+///         *shard
+///     });
+///     assert_eq!(handle.await.unwrap(), 42);
+/// });
+/// ```
+#[cfg(feature = "sync")]
+#[track_caller]
+pub fn spawn_on<F, Fut>(thread_id: usize, f: F) -> RemoteJoinHandle<Fut::Output>
+where
+    F: FnOnce() -> Fut + Send + 'static,
+    Fut: Future + 'static,
+    Fut::Output: Send + 'static,
+{
+    use crate::driver::unpark::Unpark;
+
+    let location = Location::caller();
+    let (completer, handle) = remote_pair();
+    let job = Box::new(move || {
+        spawn_inner(
+            async move { completer.complete(f().await) },
+            Priority::Normal,
+            None,
+            location,
+        );
+    });
+    // If the runtime is gone, the job is dropped together with the completer,
+    // which cancels the handle.
+    if let Some(thread) = crate::driver::thread::lookup(thread_id) {
+        if thread.spawner.send(job).is_ok() {
+            let _ = thread.unpark.unpark();
+        }
+    }
+    handle
+}
+
+/// Returns the id of the runtime thread the caller runs on.
+///
+/// # Panics
+///
+/// This function panics if called outside of a snowfallio runtime.
+pub fn current_thread_id() -> usize {
+    crate::utils::thread_id::get_current_thread_id()
+}
+
 #[cfg(feature = "sync")]
 unsafe fn spawn_without_static<T>(future: T) -> JoinHandle<T::Output>
 where
@@ -243,11 +708,12 @@ where
     let (task, join) = new_task_holding(
         crate::utils::thread_id::get_current_thread_id(),
         future,
-        LocalScheduler,
+        LocalScheduler::default(),
     );
 
     CURRENT.with(|ctx| {
-        ctx.tasks.push(task);
+        ctx.metrics.task_spawned();
+        ctx.tasks.push(task, Priority::Normal);
     });
     join
 }
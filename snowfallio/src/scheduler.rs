@@ -1,22 +1,88 @@
-use std::{cell::UnsafeCell, collections::VecDeque, marker::PhantomData};
+use std::{
+    cell::{Cell, UnsafeCell},
+    collections::VecDeque,
+    marker::PhantomData,
+};
 
-use crate::task::{Schedule, Task};
+use crate::task::{dump::ReleaseQueue, Schedule, Task};
 
-pub(crate) struct LocalScheduler;
+/// Scheduling priority of a task.
+///
+/// Each priority has its own run queue. The runtime drains the queues with a
+/// weighted round robin policy, see [`RuntimeBuilder::with_priority_weights`].
+///
+/// [`RuntimeBuilder::with_priority_weights`]: crate::RuntimeBuilder::with_priority_weights
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Priority {
+    /// Latency sensitive tasks, e.g. request handling.
+    High,
+    /// The priority used by [`spawn`](crate::spawn).
+    #[default]
+    Normal,
+    /// Tasks that must not delay the others, e.g. compaction or metrics.
+    Background,
+}
+
+impl Priority {
+    const COUNT: usize = 3;
+
+    #[inline]
+    fn index(self) -> usize {
+        match self {
+            Priority::High => 0,
+            Priority::Normal => 1,
+            Priority::Background => 2,
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct LocalScheduler {
+    priority: Priority,
+    // Release queue of the task dump registry the task is in, if any.
+    registry: Option<ReleaseQueue>,
+}
+
+impl LocalScheduler {
+    pub(crate) fn new(priority: Priority, registry: Option<ReleaseQueue>) -> Self {
+        Self { priority, registry }
+    }
+}
 
 impl Schedule for LocalScheduler {
     fn schedule(&self, task: Task<Self>) {
-        crate::runtime::CURRENT.with(|cx| cx.tasks.push(task));
+        crate::runtime::CURRENT.with(|cx| cx.tasks.push(task, self.priority));
     }
 
-    fn yield_now(&self, task: Task<Self>) {
-        crate::runtime::CURRENT.with(|cx| cx.tasks.push_front(task));
+    fn task_completed(&self) {
+        crate::runtime::CURRENT.with(|cx| {
+            cx.metrics.task_completed();
+            if let Some(registry) = &cx.task_registry {
+                registry.complete_current();
+            }
+        });
+    }
+
+    fn task_released(&self, key: usize) {
+        if let Some(queue) = &self.registry {
+            crate::task::dump::release(key, queue);
+        }
     }
 }
 
+/// Number of tasks popped from each queue before moving on to the next one.
+pub(crate) type PriorityWeights = [u32; Priority::COUNT];
+
+pub(crate) const DEFAULT_PRIORITY_WEIGHTS: PriorityWeights = [8, 4, 1];
+
 pub(crate) struct TaskQueue {
-    // Local queue.
-    queue: UnsafeCell<VecDeque<Task<LocalScheduler>>>,
+    // Local queues, one per priority.
+    queues: UnsafeCell<[VecDeque<Task<LocalScheduler>>; Priority::COUNT]>,
+    weights: PriorityWeights,
+    // Queue currently being drained and the number of pops left for it.
+    cursor: Cell<(usize, u32)>,
+    // Whether the current cycle was restarted for higher priority work.
+    restarted: Cell<bool>,
     // Make sure the type is `!Send` and `!Sync`.
     _marker: PhantomData<*const ()>,
 }
@@ -32,34 +98,69 @@ impl TaskQueue {
         const DEFAULT_TASK_QUEUE_SIZE: usize = 4096;
         Self::new_with_capacity(DEFAULT_TASK_QUEUE_SIZE)
     }
+
     pub(crate) fn new_with_capacity(capacity: usize) -> Self {
         Self {
-            queue: UnsafeCell::new(VecDeque::with_capacity(capacity)),
+            // Most tasks are spawned with the normal priority, so only that
+            // queue is pre-allocated.
+            queues: UnsafeCell::new([
+                VecDeque::new(),
+                VecDeque::with_capacity(capacity),
+                VecDeque::new(),
+            ]),
+            weights: DEFAULT_PRIORITY_WEIGHTS,
+            cursor: Cell::new((0, DEFAULT_PRIORITY_WEIGHTS[0])),
+            restarted: Cell::new(false),
             _marker: PhantomData,
         }
     }
 
+    pub(crate) fn set_weights(&mut self, weights: PriorityWeights) {
+        self.weights = weights;
+        self.cursor.set((0, weights[0]));
+    }
+
     pub(crate) fn len(&self) -> usize {
-        unsafe { (*self.queue.get()).len() }
+        unsafe { (*self.queues.get()).iter().map(VecDeque::len).sum() }
     }
 
     pub(crate) fn is_empty(&self) -> bool {
         self.len() == 0
     }
 
-    pub(crate) fn push(&self, runnable: Task<LocalScheduler>) {
+    pub(crate) fn push(&self, runnable: Task<LocalScheduler>, priority: Priority) {
         unsafe {
-            (*self.queue.get()).push_back(runnable);
+            (*self.queues.get())[priority.index()].push_back(runnable);
         }
-    }
-
-    pub(crate) fn push_front(&self, runnable: Task<LocalScheduler>) {
-        unsafe {
-            (*self.queue.get()).push_front(runnable);
+        // Work arrived above the queue being drained, restart the cycle so it
+        // does not wait for the lower priorities. Only once per cycle, so that
+        // the lower priorities still get their turn.
+        if priority.index() < self.cursor.get().0 && !self.restarted.get() {
+            self.cursor.set((0, self.weights[0]));
+            self.restarted.set(true);
         }
     }
 
     pub(crate) fn pop(&self) -> Option<Task<LocalScheduler>> {
-        unsafe { (*self.queue.get()).pop_front() }
+        let queues = unsafe { &mut *self.queues.get() };
+        // Visit the current queue and then every other queue once.
+        for _ in 0..=Priority::COUNT {
+            let (idx, credit) = self.cursor.get();
+            if credit > 0 {
+                if let Some(task) = queues[idx].pop_front() {
+                    self.cursor.set((idx, credit - 1));
+                    return Some(task);
+                }
+            }
+            let next = (idx + 1) % Priority::COUNT;
+            if next == 0 {
+                self.restarted.set(false);
+            }
+            self.cursor.set((next, self.weights[next]));
+        }
+        // All queues are empty, start over from the highest priority.
+        self.cursor.set((0, self.weights[0]));
+        self.restarted.set(false);
+        None
     }
 }
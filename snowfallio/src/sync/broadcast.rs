@@ -0,0 +1,289 @@
+//! A channel delivering every value to every receiver, between the tasks of
+//! a thread.
+//!
+//! The channel keeps the last `capacity` values. A receiver that falls
+//! further behind misses the oldest values: its next receive returns
+//! [`RecvError::Lagged`] with the number of missed values, then continues
+//! from the oldest value kept.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use snowfallio::sync::broadcast;
+//!
+//! #[snowfallio::main]
+//! async fn main() {
+//!     let (tx, mut rx1) = broadcast::channel(16);
+//!     let mut rx2 = tx.subscribe();
+//!     tx.send(10).unwrap();
+//!     assert_eq!(rx1.recv().await, Ok(10));
+//!     assert_eq!(rx2.recv().await, Ok(10));
+//! }
+//! ```
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    fmt,
+    rc::Rc,
+};
+
+use super::Notify;
+use crate::task::consume_budget;
+
+/// Create a broadcast channel keeping the last `capacity` values.
+///
+/// # Panics
+///
+/// Panics if `capacity` is zero.
+pub fn channel<T: Clone>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity > 0, "broadcast channel requires capacity > 0");
+    let shared = Rc::new(Shared {
+        buffer: RefCell::new(VecDeque::with_capacity(capacity)),
+        head: Cell::new(0),
+        capacity,
+        senders: Cell::new(1),
+        receivers: Cell::new(1),
+        sent: Notify::new(),
+    });
+    (
+        Sender {
+            shared: shared.clone(),
+        },
+        Receiver { shared, next: 0 },
+    )
+}
+
+struct Shared<T> {
+    buffer: RefCell<VecDeque<T>>,
+    // Position of the first value of the buffer.
+    head: Cell<u64>,
+    capacity: usize,
+    senders: Cell<usize>,
+    receivers: Cell<usize>,
+    sent: Notify,
+}
+
+/// Sends values to every [`Receiver`].
+pub struct Sender<T> {
+    shared: Rc<Shared<T>>,
+}
+
+/// Receives the values sent after it was created.
+pub struct Receiver<T> {
+    shared: Rc<Shared<T>>,
+    // Position of the next value to receive.
+    next: u64,
+}
+
+/// Error returned by [`Sender::send`] when there is no receiver, holding the
+/// value back.
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub struct SendError<T>(pub T);
+
+/// Error returned by [`Receiver::recv`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RecvError {
+    /// Every sender is dropped and every value was received.
+    Closed,
+    /// The receiver fell behind and missed this many values.
+    Lagged(u64),
+}
+
+/// Error returned by [`Receiver::try_recv`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TryRecvError {
+    /// There is no new value.
+    Empty,
+    /// Every sender is dropped and every value was received.
+    Closed,
+    /// The receiver fell behind and missed this many values.
+    Lagged(u64),
+}
+
+impl<T> Shared<T> {
+    fn tail(&self) -> u64 {
+        self.head.get() + self.buffer.borrow().len() as u64
+    }
+}
+
+impl<T> Sender<T> {
+    /// Send a value to every receiver, returning the number of receivers.
+    ///
+    /// Fails if there is no receiver, the value is then returned back.
+    pub fn send(&self, value: T) -> Result<usize, SendError<T>> {
+        let receivers = self.shared.receivers.get();
+        if receivers == 0 {
+            return Err(SendError(value));
+        }
+        let evicted = {
+            let mut buffer = self.shared.buffer.borrow_mut();
+            let evicted = if buffer.len() == self.shared.capacity {
+                self.shared.head.set(self.shared.head.get() + 1);
+                buffer.pop_front()
+            } else {
+                None
+            };
+            buffer.push_back(value);
+            evicted
+        };
+        drop(evicted);
+        self.shared.sent.notify_waiters();
+        Ok(receivers)
+    }
+
+    /// Create a receiver of the values sent from now on.
+    pub fn subscribe(&self) -> Receiver<T> {
+        self.shared.receivers.set(self.shared.receivers.get() + 1);
+        Receiver {
+            shared: self.shared.clone(),
+            next: self.shared.tail(),
+        }
+    }
+
+    /// Returns the number of receivers.
+    pub fn receiver_count(&self) -> usize {
+        self.shared.receivers.get()
+    }
+
+    /// Returns the number of values kept by the channel.
+    pub fn len(&self) -> usize {
+        self.shared.buffer.borrow().len()
+    }
+
+    /// Returns true if the channel keeps no value.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.senders.set(self.shared.senders.get() + 1);
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let senders = self.shared.senders.get() - 1;
+        self.shared.senders.set(senders);
+        if senders == 0 {
+            self.shared.sent.notify_waiters();
+        }
+    }
+}
+
+impl<T: Clone> Receiver<T> {
+    /// Receive the next value, waiting for it to be sent.
+    pub async fn recv(&mut self) -> Result<T, RecvError> {
+        consume_budget().await;
+        let shared = self.shared.clone();
+        loop {
+            let notified = shared.sent.notified();
+            match self.try_recv() {
+                Ok(value) => return Ok(value),
+                Err(TryRecvError::Closed) => return Err(RecvError::Closed),
+                Err(TryRecvError::Lagged(n)) => return Err(RecvError::Lagged(n)),
+                Err(TryRecvError::Empty) => notified.await,
+            }
+        }
+    }
+
+    /// Receive the next value if it was sent.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let head = self.shared.head.get();
+        if self.next < head {
+            let missed = head - self.next;
+            self.next = head;
+            return Err(TryRecvError::Lagged(missed));
+        }
+        let buffer = self.shared.buffer.borrow();
+        match buffer.get((self.next - head) as usize) {
+            Some(value) => {
+                self.next += 1;
+                Ok(value.clone())
+            }
+            None if self.shared.senders.get() == 0 => Err(TryRecvError::Closed),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Create a receiver of the values sent from now on.
+    pub fn resubscribe(&self) -> Self {
+        self.shared.receivers.set(self.shared.receivers.get() + 1);
+        Self {
+            shared: self.shared.clone(),
+            next: self.shared.tail(),
+        }
+    }
+
+    /// Returns the number of values not received yet.
+    pub fn len(&self) -> usize {
+        (self.shared.tail() - self.next.max(self.shared.head.get())) as usize
+    }
+
+    /// Returns true if every value was received.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.shared.receivers.set(self.shared.receivers.get() - 1);
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender").finish()
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver").finish()
+    }
+}
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SendError").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("channel closed")
+    }
+}
+
+impl<T> std::error::Error for SendError<T> {}
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecvError::Closed => f.write_str("channel closed"),
+            RecvError::Lagged(n) => write!(f, "channel lagged by {n}"),
+        }
+    }
+}
+
+impl std::error::Error for RecvError {}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryRecvError::Empty => f.write_str("channel empty"),
+            TryRecvError::Closed => f.write_str("channel closed"),
+            TryRecvError::Lagged(n) => write!(f, "channel lagged by {n}"),
+        }
+    }
+}
+
+impl std::error::Error for TryRecvError {}
@@ -0,0 +1,27 @@
+//! Synchronization primitives for the tasks of a thread.
+//!
+//! Every task of a runtime runs on the same thread, so these primitives use
+//! `Cell`, `RefCell` and `Rc` instead of atomics and locks. They are `!Send`
+//! and `!Sync`: they can not be shared with another thread, use
+//! `std::sync` or the channels of [`remote`] (with `sync` feature) for that.
+
+mod mutex;
+mod notify;
+mod rwlock;
+mod semaphore;
+
+pub mod broadcast;
+pub mod mpsc;
+pub mod oneshot;
+#[cfg(feature = "sync")]
+pub mod remote;
+pub mod watch;
+
+pub use self::{
+    mutex::{Mutex, MutexGuard, TryLockError},
+    notify::{Notified, Notify},
+    rwlock::{RwLock, RwLockReadGuard, RwLockWriteGuard},
+    semaphore::{
+        Acquire, AcquireError, OwnedSemaphorePermit, Semaphore, SemaphorePermit, TryAcquireError,
+    },
+};
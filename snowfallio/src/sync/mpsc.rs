@@ -0,0 +1,451 @@
+//! Multi-producer, single-consumer queues between the tasks of a thread.
+//!
+//! [`channel`] creates a bounded channel: senders wait, in FIFO order, while
+//! the channel is full. [`unbounded_channel`] creates a channel whose senders
+//! never wait.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use snowfallio::sync::mpsc;
+//!
+//! #[snowfallio::main]
+//! async fn main() {
+//!     let (tx, mut rx) = mpsc::channel(16);
+//!     for i in 0..4 {
+//!         let tx = tx.clone();
+//!         snowfallio::spawn(async move {
+//!             tx.send(i).await.unwrap();
+//!         });
+//!     }
+//!     drop(tx);
+//!     while let Some(i) = rx.recv().await {
+//!         println!("got {i}");
+//!     }
+//! }
+//! ```
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    fmt,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+use super::{Notify, Semaphore};
+use crate::{io::stream::Stream, task::coop};
+
+/// Create a bounded channel holding up to `buffer` values.
+///
+/// # Panics
+///
+/// Panics if `buffer` is zero.
+pub fn channel<T>(buffer: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(buffer > 0, "mpsc bounded channel requires buffer > 0");
+    let chan = Chan::new(Some(buffer));
+    (Sender { chan: chan.clone() }, Receiver { chan })
+}
+
+/// Create an unbounded channel.
+pub fn unbounded_channel<T>() -> (UnboundedSender<T>, UnboundedReceiver<T>) {
+    let chan = Chan::new(None);
+    (
+        UnboundedSender { chan: chan.clone() },
+        UnboundedReceiver { chan },
+    )
+}
+
+struct Chan<T> {
+    queue: RefCell<VecDeque<T>>,
+    // Free slots of a bounded channel.
+    slots: Option<Semaphore>,
+    bound: usize,
+    senders: Cell<usize>,
+    rx_closed: Cell<bool>,
+    rx_waker: Cell<Option<Waker>>,
+    // Notified when the receiver is closed.
+    closed: Notify,
+}
+
+/// Sends values to a bounded channel.
+pub struct Sender<T> {
+    chan: Rc<Chan<T>>,
+}
+
+/// Receives values from a bounded channel.
+pub struct Receiver<T> {
+    chan: Rc<Chan<T>>,
+}
+
+/// Sends values to an unbounded channel.
+pub struct UnboundedSender<T> {
+    chan: Rc<Chan<T>>,
+}
+
+/// Receives values from an unbounded channel.
+pub struct UnboundedReceiver<T> {
+    chan: Rc<Chan<T>>,
+}
+
+/// Future returned by [`Receiver::recv`] and [`UnboundedReceiver::recv`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Recv<'a, T> {
+    chan: &'a Chan<T>,
+}
+
+/// Error returned by sending to a channel whose receiver is closed, holding
+/// the value back.
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub struct SendError<T>(pub T);
+
+/// Error returned by [`Sender::try_send`].
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum TrySendError<T> {
+    /// The channel is full.
+    Full(T),
+    /// The receiver is closed.
+    Closed(T),
+}
+
+/// Error returned by `try_recv`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TryRecvError {
+    /// The channel is empty.
+    Empty,
+    /// The channel is empty and every sender is dropped, or the receiver was
+    /// closed.
+    Disconnected,
+}
+
+impl<T> Chan<T> {
+    fn new(capacity: Option<usize>) -> Rc<Self> {
+        Rc::new(Self {
+            queue: RefCell::new(VecDeque::new()),
+            slots: capacity.map(Semaphore::new),
+            bound: capacity.unwrap_or(usize::MAX),
+            senders: Cell::new(1),
+            rx_closed: Cell::new(false),
+            rx_waker: Cell::new(None),
+            closed: Notify::new(),
+        })
+    }
+
+    fn push(&self, value: T) {
+        self.queue.borrow_mut().push_back(value);
+        if let Some(waker) = self.rx_waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn try_recv(&self) -> Result<T, TryRecvError> {
+        let value = self.queue.borrow_mut().pop_front();
+        match value {
+            Some(value) => {
+                if let Some(slots) = &self.slots {
+                    slots.add_permits(1);
+                }
+                Ok(value)
+            }
+            // Nothing can be sent anymore once the receiver is closed.
+            None if self.senders.get() == 0 || self.rx_closed.get() => {
+                Err(TryRecvError::Disconnected)
+            }
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    fn poll_recv(&self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let coop = std::task::ready!(coop::poll_proceed(cx));
+        match self.try_recv() {
+            Ok(value) => {
+                coop.made_progress();
+                Poll::Ready(Some(value))
+            }
+            Err(TryRecvError::Disconnected) => {
+                coop.made_progress();
+                Poll::Ready(None)
+            }
+            Err(TryRecvError::Empty) => {
+                self.rx_waker.set(Some(cx.waker().clone()));
+                Poll::Pending
+            }
+        }
+    }
+
+    fn close(&self) {
+        if self.rx_closed.replace(true) {
+            return;
+        }
+        if let Some(slots) = &self.slots {
+            slots.close();
+        }
+        self.closed.notify_waiters();
+    }
+
+    async fn closed(&self) {
+        loop {
+            let notified = self.closed.notified();
+            if self.rx_closed.get() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    fn add_sender(self: &Rc<Self>) -> Rc<Self> {
+        self.senders.set(self.senders.get() + 1);
+        self.clone()
+    }
+
+    fn drop_sender(&self) {
+        let senders = self.senders.get() - 1;
+        self.senders.set(senders);
+        if senders == 0 {
+            if let Some(waker) = self.rx_waker.take() {
+                waker.wake();
+            }
+        }
+    }
+
+    fn drop_receiver(&self) {
+        self.close();
+        // Drop the values now, they can not be received anymore.
+        let values = std::mem::take(&mut *self.queue.borrow_mut());
+        drop(values);
+    }
+}
+
+impl<T> Sender<T> {
+    /// Send a value, waiting for a free slot if the channel is full.
+    ///
+    /// The value is returned back if the receiver is closed.
+    pub async fn send(&self, value: T) -> Result<(), SendError<T>> {
+        match self.slots().acquire().await {
+            Ok(permit) => permit.forget(),
+            Err(_) => return Err(SendError(value)),
+        }
+        self.chan.push(value);
+        Ok(())
+    }
+
+    /// Send a value if the channel has a free slot.
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        match self.slots().try_acquire() {
+            Ok(permit) => permit.forget(),
+            Err(super::TryAcquireError::Closed) => return Err(TrySendError::Closed(value)),
+            Err(super::TryAcquireError::NoPermits) => return Err(TrySendError::Full(value)),
+        }
+        self.chan.push(value);
+        Ok(())
+    }
+
+    /// Returns the number of free slots.
+    pub fn capacity(&self) -> usize {
+        self.slots().available_permits()
+    }
+
+    /// Returns the buffer size of the channel.
+    pub fn max_capacity(&self) -> usize {
+        self.chan.bound
+    }
+
+    /// Returns true if the receiver is closed or dropped.
+    pub fn is_closed(&self) -> bool {
+        self.chan.rx_closed.get()
+    }
+
+    /// Wait for the receiver to be closed or dropped.
+    pub async fn closed(&self) {
+        self.chan.closed().await
+    }
+
+    /// Returns true if both senders send to the same channel.
+    pub fn same_channel(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.chan, &other.chan)
+    }
+
+    fn slots(&self) -> &Semaphore {
+        self.chan
+            .slots
+            .as_ref()
+            .expect("a bounded channel has slots")
+    }
+}
+
+impl<T> UnboundedSender<T> {
+    /// Send a value. It is returned back if the receiver is closed.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        if self.chan.rx_closed.get() {
+            return Err(SendError(value));
+        }
+        self.chan.push(value);
+        Ok(())
+    }
+
+    /// Returns true if the receiver is closed or dropped.
+    pub fn is_closed(&self) -> bool {
+        self.chan.rx_closed.get()
+    }
+
+    /// Wait for the receiver to be closed or dropped.
+    pub async fn closed(&self) {
+        self.chan.closed().await
+    }
+
+    /// Returns true if both senders send to the same channel.
+    pub fn same_channel(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.chan, &other.chan)
+    }
+}
+
+macro_rules! impl_receiver {
+    ($rx: ident) => {
+        impl<T> $rx<T> {
+            /// Receive the next value, or `None` once the channel is empty and
+            /// every sender is dropped or the receiver closed.
+            pub fn recv(&mut self) -> Recv<'_, T> {
+                Recv { chan: &self.chan }
+            }
+
+            /// Receive the next value if there is one.
+            pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+                self.chan.try_recv()
+            }
+
+            /// Poll to receive the next value.
+            pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+                self.chan.poll_recv(cx)
+            }
+
+            /// Close the channel, the senders can no longer send.
+            ///
+            /// The values already sent can still be received, then `recv`
+            /// returns `None`.
+            pub fn close(&mut self) {
+                self.chan.close();
+            }
+
+            /// Returns the number of values in the channel.
+            pub fn len(&self) -> usize {
+                self.chan.queue.borrow().len()
+            }
+
+            /// Returns true if the channel is empty.
+            pub fn is_empty(&self) -> bool {
+                self.len() == 0
+            }
+        }
+
+        impl<T> Drop for $rx<T> {
+            fn drop(&mut self) {
+                self.chan.drop_receiver();
+            }
+        }
+
+        impl<T> Stream for $rx<T> {
+            type Item = T;
+
+            type NextFuture<'a>
+                = Recv<'a, T>
+            where
+                T: 'a;
+
+            fn next(&mut self) -> Self::NextFuture<'_> {
+                self.recv()
+            }
+        }
+
+        impl<T> fmt::Debug for $rx<T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_struct(stringify!($rx))
+                    .field("len", &self.len())
+                    .finish()
+            }
+        }
+    };
+}
+
+macro_rules! impl_sender {
+    ($tx: ident) => {
+        impl<T> Clone for $tx<T> {
+            fn clone(&self) -> Self {
+                Self {
+                    chan: self.chan.add_sender(),
+                }
+            }
+        }
+
+        impl<T> Drop for $tx<T> {
+            fn drop(&mut self) {
+                self.chan.drop_sender();
+            }
+        }
+
+        impl<T> fmt::Debug for $tx<T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_struct(stringify!($tx)).finish()
+            }
+        }
+    };
+}
+
+impl_receiver!(Receiver);
+impl_receiver!(UnboundedReceiver);
+impl_sender!(Sender);
+impl_sender!(UnboundedSender);
+
+impl<T> Future for Recv<'_, T> {
+    type Output = Option<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.chan.poll_recv(cx)
+    }
+}
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SendError").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("channel closed")
+    }
+}
+
+impl<T> std::error::Error for SendError<T> {}
+
+impl<T> fmt::Debug for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrySendError::Full(_) => f.write_str("Full(..)"),
+            TrySendError::Closed(_) => f.write_str("Closed(..)"),
+        }
+    }
+}
+
+impl<T> fmt::Display for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrySendError::Full(_) => f.write_str("channel full"),
+            TrySendError::Closed(_) => f.write_str("channel closed"),
+        }
+    }
+}
+
+impl<T> std::error::Error for TrySendError<T> {}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryRecvError::Empty => f.write_str("channel empty"),
+            TryRecvError::Disconnected => f.write_str("channel disconnected"),
+        }
+    }
+}
+
+impl std::error::Error for TryRecvError {}
@@ -0,0 +1,152 @@
+use std::{
+    cell::UnsafeCell,
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use super::Semaphore;
+
+/// An async mutex for the tasks of a thread.
+///
+/// Unlike `RefCell`, the guard can be held across `.await` points: the other
+/// tasks locking the mutex wait, in FIFO order, until it is released.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::rc::Rc;
+///
+/// use snowfallio::sync::Mutex;
+///
+/// #[snowfallio::main]
+/// async fn main() {
+///     let conn = Rc::new(Mutex::new(Vec::new()));
+///     let c = conn.clone();
+///     snowfallio::spawn(async move {
+///         let mut conn = c.lock().await;
+///         conn.push(1);
+///         snowfallio::task::yield_now().await;
+///         conn.push(2);
+///     });
+///     conn.lock().await.push(3);
+/// }
+/// ```
+pub struct Mutex<T: ?Sized> {
+    semaphore: Semaphore,
+    value: UnsafeCell<T>,
+}
+
+/// A guard giving access to the value of a [`Mutex`], which is unlocked when
+/// the guard is dropped.
+#[must_use = "if unused the Mutex will immediately unlock"]
+pub struct MutexGuard<'a, T: ?Sized> {
+    lock: &'a Mutex<T>,
+}
+
+/// Error returned by [`Mutex::try_lock`], [`RwLock::try_read`] and
+/// [`RwLock::try_write`] when the lock is held.
+///
+/// [`RwLock::try_read`]: super::RwLock::try_read
+/// [`RwLock::try_write`]: super::RwLock::try_write
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TryLockError(pub(super) ());
+
+impl<T> Mutex<T> {
+    /// Create an unlocked mutex holding `value`.
+    pub const fn new(value: T) -> Self {
+        Self {
+            semaphore: Semaphore::new(1),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Consume the mutex, returning the value.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+impl<T: ?Sized> Mutex<T> {
+    /// Lock the mutex, waiting for the current holder to release it.
+    pub async fn lock(&self) -> MutexGuard<'_, T> {
+        match self.semaphore.acquire().await {
+            Ok(permit) => permit.forget(),
+            Err(_) => unreachable!("the semaphore of a mutex is never closed"),
+        }
+        MutexGuard { lock: self }
+    }
+
+    /// Lock the mutex if it is not held.
+    pub fn try_lock(&self) -> Result<MutexGuard<'_, T>, TryLockError> {
+        match self.semaphore.try_acquire() {
+            Ok(permit) => {
+                permit.forget();
+                Ok(MutexGuard { lock: self })
+            }
+            Err(_) => Err(TryLockError(())),
+        }
+    }
+
+    /// Returns a mutable reference to the value. No locking is needed since
+    /// the mutex is borrowed mutably.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+}
+
+impl<T: Default> Default for Mutex<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> From<T> for Mutex<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for Mutex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("Mutex");
+        match self.try_lock() {
+            Ok(guard) => d.field("data", &&*guard),
+            Err(_) => d.field("data", &format_args!("<locked>")),
+        };
+        d.finish()
+    }
+}
+
+impl<T: ?Sized> Deref for MutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for MutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.value.get() }
+    }
+}
+
+impl<T: ?Sized> Drop for MutexGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.semaphore.add_permits(1);
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for MutexGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl fmt::Display for TryLockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("lock already held")
+    }
+}
+
+impl std::error::Error for TryLockError {}
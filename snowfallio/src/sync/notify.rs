@@ -0,0 +1,198 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    fmt,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+/// Notifies a task of an event, for the tasks of a thread.
+///
+/// [`notify_one`](Notify::notify_one) wakes the oldest waiter, or stores a
+/// permit consumed by the next [`notified`](Notify::notified) if nobody
+/// waits. [`notify_waiters`](Notify::notify_waiters) wakes every current
+/// waiter, including the [`Notified`] futures created but not polled yet,
+/// and stores nothing.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::rc::Rc;
+///
+/// use snowfallio::sync::Notify;
+///
+/// #[snowfallio::main]
+/// async fn main() {
+///     let notify = Rc::new(Notify::new());
+///     let n = notify.clone();
+///     snowfallio::spawn(async move {
+///         n.notified().await;
+///         println!("received notification");
+///     });
+///     notify.notify_one();
+/// }
+/// ```
+pub struct Notify {
+    state: RefCell<State>,
+}
+
+struct State {
+    permit: bool,
+    // Number of `notify_waiters` calls.
+    generation: u64,
+    waiters: VecDeque<Rc<Waiter>>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Notification {
+    None,
+    One,
+    All,
+}
+
+struct Waiter {
+    notification: Cell<Notification>,
+    waker: Cell<Option<Waker>>,
+}
+
+/// Future returned by [`Notify::notified`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Notified<'a> {
+    notify: &'a Notify,
+    generation: u64,
+    waiter: Option<Rc<Waiter>>,
+    done: bool,
+}
+
+impl Notify {
+    /// Create a `Notify` without a stored permit.
+    pub const fn new() -> Self {
+        Self {
+            state: RefCell::new(State {
+                permit: false,
+                generation: 0,
+                waiters: VecDeque::new(),
+            }),
+        }
+    }
+
+    /// Wait for a notification.
+    pub fn notified(&self) -> Notified<'_> {
+        Notified {
+            notify: self,
+            generation: self.state.borrow().generation,
+            waiter: None,
+            done: false,
+        }
+    }
+
+    /// Wake the oldest waiter, or store a permit if nobody waits.
+    pub fn notify_one(&self) {
+        let waker = {
+            let mut state = self.state.borrow_mut();
+            match state.waiters.pop_front() {
+                Some(waiter) => {
+                    waiter.notification.set(Notification::One);
+                    waiter.waker.take()
+                }
+                None => {
+                    state.permit = true;
+                    None
+                }
+            }
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    /// Wake every waiter, without storing a permit.
+    pub fn notify_waiters(&self) {
+        let waiters = {
+            let mut state = self.state.borrow_mut();
+            state.generation += 1;
+            std::mem::take(&mut state.waiters)
+        };
+        for waiter in waiters {
+            waiter.notification.set(Notification::All);
+            if let Some(waker) = waiter.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+impl Default for Notify {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Notify {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Notify").finish()
+    }
+}
+
+impl Future for Notified<'_> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.done {
+            return Poll::Ready(());
+        }
+        let this = &mut *self;
+        match &this.waiter {
+            None => {
+                let mut state = this.notify.state.borrow_mut();
+                if state.generation != this.generation || std::mem::take(&mut state.permit) {
+                    this.done = true;
+                    return Poll::Ready(());
+                }
+                let waiter = Rc::new(Waiter {
+                    notification: Cell::new(Notification::None),
+                    waker: Cell::new(Some(cx.waker().clone())),
+                });
+                state.waiters.push_back(waiter.clone());
+                this.waiter = Some(waiter);
+                Poll::Pending
+            }
+            Some(waiter) => {
+                if waiter.notification.get() == Notification::None {
+                    waiter.waker.set(Some(cx.waker().clone()));
+                    return Poll::Pending;
+                }
+                this.waiter = None;
+                this.done = true;
+                Poll::Ready(())
+            }
+        }
+    }
+}
+
+impl Drop for Notified<'_> {
+    fn drop(&mut self) {
+        let Some(waiter) = self.waiter.take() else {
+            return;
+        };
+        match waiter.notification.get() {
+            Notification::None => self
+                .notify
+                .state
+                .borrow_mut()
+                .waiters
+                .retain(|w| !Rc::ptr_eq(w, &waiter)),
+            // Do not lose a `notify_one` that was not observed.
+            Notification::One => self.notify.notify_one(),
+            Notification::All => {}
+        }
+    }
+}
+
+impl fmt::Debug for Notified<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Notified").finish()
+    }
+}
@@ -0,0 +1,205 @@
+//! A channel sending a single value between the tasks of a thread.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use snowfallio::sync::oneshot;
+//!
+//! #[snowfallio::main]
+//! async fn main() {
+//!     let (tx, rx) = oneshot::channel();
+//!     snowfallio::spawn(async move {
+//!         let _ = tx.send(3);
+//!     });
+//!     assert_eq!(rx.await, Ok(3));
+//! }
+//! ```
+
+use std::{
+    cell::Cell,
+    fmt,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+use crate::task::coop;
+
+/// Create a oneshot channel.
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let inner = Rc::new(Inner {
+        value: Cell::new(None),
+        tx_dropped: Cell::new(false),
+        rx_closed: Cell::new(false),
+        rx_waker: Cell::new(None),
+        tx_waker: Cell::new(None),
+    });
+    (
+        Sender {
+            inner: inner.clone(),
+        },
+        Receiver { inner },
+    )
+}
+
+struct Inner<T> {
+    value: Cell<Option<T>>,
+    tx_dropped: Cell<bool>,
+    rx_closed: Cell<bool>,
+    rx_waker: Cell<Option<Waker>>,
+    tx_waker: Cell<Option<Waker>>,
+}
+
+/// Sends the value to the [`Receiver`].
+pub struct Sender<T> {
+    inner: Rc<Inner<T>>,
+}
+
+/// Receives the value from the [`Sender`]. It is a future resolving to the
+/// value, or to an error if the sender is dropped without sending.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Receiver<T> {
+    inner: Rc<Inner<T>>,
+}
+
+/// Error returned by awaiting a [`Receiver`] whose sender was dropped without
+/// sending.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecvError(pub(crate) ());
+
+/// Error returned by [`Receiver::try_recv`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// The value was not sent yet.
+    Empty,
+    /// The sender was dropped without sending, or the value was already
+    /// received.
+    Closed,
+}
+
+impl<T> Sender<T> {
+    /// Send the value. It is returned back if the receiver is closed.
+    pub fn send(self, value: T) -> Result<(), T> {
+        if self.inner.rx_closed.get() {
+            return Err(value);
+        }
+        self.inner.value.set(Some(value));
+        if let Some(waker) = self.inner.rx_waker.take() {
+            waker.wake();
+        }
+        Ok(())
+    }
+
+    /// Returns true if the receiver is closed or dropped.
+    pub fn is_closed(&self) -> bool {
+        self.inner.rx_closed.get()
+    }
+
+    /// Wait for the receiver to be closed or dropped.
+    pub async fn closed(&mut self) {
+        std::future::poll_fn(|cx| self.poll_closed(cx)).await
+    }
+
+    /// Poll whether the receiver is closed or dropped.
+    pub fn poll_closed(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.inner.rx_closed.get() {
+            return Poll::Ready(());
+        }
+        self.inner.tx_waker.set(Some(cx.waker().clone()));
+        Poll::Pending
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.inner.tx_dropped.set(true);
+        if let Some(waker) = self.inner.rx_waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Close the channel, the sender can no longer send.
+    ///
+    /// A value sent before can still be received.
+    pub fn close(&mut self) {
+        self.inner.rx_closed.set(true);
+        if let Some(waker) = self.inner.tx_waker.take() {
+            waker.wake();
+        }
+    }
+
+    /// Receive the value if it was sent.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        match self.inner.value.take() {
+            Some(value) => {
+                // The value can be received once.
+                self.inner.tx_dropped.set(true);
+                Ok(value)
+            }
+            None if self.inner.tx_dropped.get() => Err(TryRecvError::Closed),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+}
+
+impl<T> Future for Receiver<T> {
+    type Output = Result<T, RecvError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let coop = std::task::ready!(coop::poll_proceed(cx));
+        match self.try_recv() {
+            Ok(value) => {
+                coop.made_progress();
+                Poll::Ready(Ok(value))
+            }
+            Err(TryRecvError::Closed) => {
+                coop.made_progress();
+                Poll::Ready(Err(RecvError(())))
+            }
+            Err(TryRecvError::Empty) => {
+                self.inner.rx_waker.set(Some(cx.waker().clone()));
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender").finish()
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver").finish()
+    }
+}
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("channel closed")
+    }
+}
+
+impl std::error::Error for RecvError {}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryRecvError::Empty => f.write_str("channel empty"),
+            TryRecvError::Closed => f.write_str("channel closed"),
+        }
+    }
+}
+
+impl std::error::Error for TryRecvError {}
@@ -0,0 +1,83 @@
+//! Channels between threads, whose receiving side runs on a runtime.
+//!
+//! The senders are `Send` and can be used from any thread, inside a runtime
+//! or not. The receiver is woken through the runtime thread it is polled on,
+//! and the wakeups are kept cheap:
+//!
+//! - sends are coalesced: after a wakeup, the following sends do not wake the receiver again until
+//!   it has polled the channel;
+//! - a sender running on a runtime defers the unpark of the receiving thread to the end of its
+//!   tick, so that all the wakeups it sends to a thread in one tick share a single eventfd write;
+//! - no eventfd write happens while the receiving runtime is awake, it picks up the wakeups before
+//!   parking.
+//!
+//! Prefer the thread local channels of [`sync`](crate::sync) when both sides
+//! run on the same thread.
+
+pub mod mpsc;
+pub mod oneshot;
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    task::Waker,
+};
+
+use crate::{runtime, utils::thread_id::try_get_current_thread_id};
+
+/// The waker of a task polling one side of a channel, and the runtime thread
+/// it runs on. Only the first wake after each registration is delivered.
+pub(crate) struct RemoteWaker {
+    // Set by the first wake after a registration.
+    notified: AtomicBool,
+    waker: Mutex<Option<(Waker, Option<usize>)>>,
+}
+
+impl RemoteWaker {
+    pub(crate) const fn new() -> Self {
+        Self {
+            notified: AtomicBool::new(false),
+            waker: Mutex::new(None),
+        }
+    }
+
+    /// Register the waker of the current task. The caller must check the
+    /// channel again afterwards, a wake may have been skipped before.
+    pub(crate) fn register(&self, waker: &Waker) {
+        let thread = try_get_current_thread_id();
+        {
+            let mut slot = self.waker.lock().unwrap();
+            match &*slot {
+                Some((w, t)) if *t == thread && w.will_wake(waker) => {}
+                _ => *slot = Some((waker.clone(), thread)),
+            }
+        }
+        // Cleared after the waker is stored, a wake observing it cleared
+        // finds the new waker. SeqCst orders the store before the check of the
+        // channel that follows, against a wake made after a state change that
+        // is not under a lock, e.g. the drop of the last sender.
+        self.notified.store(false, Ordering::SeqCst);
+    }
+
+    /// Wake the registered task, unless it was woken since it registered.
+    pub(crate) fn wake(&self) {
+        if self.notified.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let Some((waker, thread)) = self.waker.lock().unwrap().take() else {
+            return;
+        };
+        let Some(id) = thread else {
+            // Polled outside of a runtime.
+            waker.wake();
+            return;
+        };
+        runtime::CURRENT.try_with(|maybe_ctx| match maybe_ctx {
+            Some(ctx) if ctx.thread_id == id => waker.wake(),
+            Some(ctx) => ctx.wake_on(id, waker, true),
+            None => runtime::DEFAULT_CTX.with(|ctx| ctx.wake_on(id, waker, false)),
+        });
+    }
+}
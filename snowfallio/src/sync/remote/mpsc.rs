@@ -0,0 +1,254 @@
+//! Multi-producer, single-consumer unbounded queue between threads.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use snowfallio::sync::remote::mpsc;
+//!
+//! #[snowfallio::main]
+//! async fn main() {
+//!     let (tx, mut rx) = mpsc::unbounded_channel();
+//!     std::thread::spawn(move || {
+//!         for i in 0..4 {
+//!             tx.send(i).unwrap();
+//!         }
+//!     });
+//!     while let Some(i) = rx.recv().await {
+//!         println!("got {i}");
+//!     }
+//! }
+//! ```
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll},
+};
+
+use super::RemoteWaker;
+pub use crate::sync::mpsc::{SendError, TryRecvError};
+use crate::{io::stream::Stream, task::coop};
+
+/// Create an unbounded channel.
+pub fn unbounded_channel<T>() -> (UnboundedSender<T>, UnboundedReceiver<T>) {
+    let chan = Arc::new(Chan {
+        queue: Mutex::new(Queue {
+            values: VecDeque::new(),
+            rx_closed: false,
+        }),
+        senders: AtomicUsize::new(1),
+        rx_waker: RemoteWaker::new(),
+    });
+    (
+        UnboundedSender { chan: chan.clone() },
+        UnboundedReceiver { chan },
+    )
+}
+
+struct Chan<T> {
+    queue: Mutex<Queue<T>>,
+    senders: AtomicUsize,
+    rx_waker: RemoteWaker,
+}
+
+struct Queue<T> {
+    values: VecDeque<T>,
+    rx_closed: bool,
+}
+
+/// Sends values to an unbounded channel, from any thread.
+pub struct UnboundedSender<T> {
+    chan: Arc<Chan<T>>,
+}
+
+/// Receives values from an unbounded channel.
+pub struct UnboundedReceiver<T> {
+    chan: Arc<Chan<T>>,
+}
+
+/// Future returned by [`UnboundedReceiver::recv`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Recv<'a, T> {
+    chan: &'a Chan<T>,
+}
+
+impl<T> Chan<T> {
+    fn try_recv(&self) -> Result<T, TryRecvError> {
+        let (value, rx_closed) = {
+            let mut queue = self.queue.lock().unwrap();
+            (queue.values.pop_front(), queue.rx_closed)
+        };
+        match value {
+            Some(value) => Ok(value),
+            // Sends check `rx_closed` under the same lock, none can follow.
+            None if rx_closed => Err(TryRecvError::Disconnected),
+            None if self.senders.load(Ordering::SeqCst) == 0 => {
+                // The last sender may have sent right before being dropped.
+                self.queue
+                    .lock()
+                    .unwrap()
+                    .values
+                    .pop_front()
+                    .ok_or(TryRecvError::Disconnected)
+            }
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    fn poll_recv(&self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let coop = std::task::ready!(coop::poll_proceed(cx));
+        let mut registered = false;
+        loop {
+            match self.try_recv() {
+                Ok(value) => {
+                    coop.made_progress();
+                    return Poll::Ready(Some(value));
+                }
+                Err(TryRecvError::Disconnected) => {
+                    coop.made_progress();
+                    return Poll::Ready(None);
+                }
+                Err(TryRecvError::Empty) if registered => return Poll::Pending,
+                Err(TryRecvError::Empty) => {
+                    // Check again after registering, a send may have skipped
+                    // the wake in between.
+                    self.rx_waker.register(cx.waker());
+                    registered = true;
+                }
+            }
+        }
+    }
+}
+
+impl<T> UnboundedSender<T> {
+    /// Send a value. It is returned back if the receiver is closed.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        {
+            let mut queue = self.chan.queue.lock().unwrap();
+            if queue.rx_closed {
+                return Err(SendError(value));
+            }
+            queue.values.push_back(value);
+        }
+        self.chan.rx_waker.wake();
+        Ok(())
+    }
+
+    /// Returns true if the receiver is closed or dropped.
+    pub fn is_closed(&self) -> bool {
+        self.chan.queue.lock().unwrap().rx_closed
+    }
+
+    /// Returns true if both senders send to the same channel.
+    pub fn same_channel(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.chan, &other.chan)
+    }
+}
+
+impl<T> Clone for UnboundedSender<T> {
+    fn clone(&self) -> Self {
+        self.chan.senders.fetch_add(1, Ordering::Relaxed);
+        Self {
+            chan: self.chan.clone(),
+        }
+    }
+}
+
+impl<T> Drop for UnboundedSender<T> {
+    fn drop(&mut self) {
+        // SeqCst pairs with `RemoteWaker::register`: either the receiver sees
+        // no sender left, or this wake sees its registration.
+        if self.chan.senders.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.chan.rx_waker.wake();
+        }
+    }
+}
+
+impl<T> UnboundedReceiver<T> {
+    /// Receive the next value, or `None` once the channel is empty and every
+    /// sender is dropped or the receiver closed.
+    pub fn recv(&mut self) -> Recv<'_, T> {
+        Recv { chan: &self.chan }
+    }
+
+    /// Receive the next value if there is one.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        self.chan.try_recv()
+    }
+
+    /// Poll to receive the next value.
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.chan.poll_recv(cx)
+    }
+
+    /// Close the channel, the senders can no longer send.
+    ///
+    /// The values already sent can still be received, then `recv` returns
+    /// `None`.
+    pub fn close(&mut self) {
+        self.chan.queue.lock().unwrap().rx_closed = true;
+    }
+
+    /// Returns the number of values in the channel.
+    pub fn len(&self) -> usize {
+        self.chan.queue.lock().unwrap().values.len()
+    }
+
+    /// Returns true if the channel is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Drop for UnboundedReceiver<T> {
+    fn drop(&mut self) {
+        // Drop the values now, they can not be received anymore.
+        let values = {
+            let mut queue = self.chan.queue.lock().unwrap();
+            queue.rx_closed = true;
+            std::mem::take(&mut queue.values)
+        };
+        drop(values);
+    }
+}
+
+impl<T> Stream for UnboundedReceiver<T> {
+    type Item = T;
+
+    type NextFuture<'a>
+        = Recv<'a, T>
+    where
+        T: 'a;
+
+    fn next(&mut self) -> Self::NextFuture<'_> {
+        self.recv()
+    }
+}
+
+impl<T> Future for Recv<'_, T> {
+    type Output = Option<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.chan.poll_recv(cx)
+    }
+}
+
+impl<T> fmt::Debug for UnboundedSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UnboundedSender").finish()
+    }
+}
+
+impl<T> fmt::Debug for UnboundedReceiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UnboundedReceiver")
+            .field("len", &self.len())
+            .finish()
+    }
+}
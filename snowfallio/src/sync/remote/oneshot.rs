@@ -0,0 +1,184 @@
+//! A channel sending a single value between threads.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use snowfallio::sync::remote::oneshot;
+//!
+//! #[snowfallio::main]
+//! async fn main() {
+//!     let (tx, rx) = oneshot::channel();
+//!     std::thread::spawn(move || {
+//!         let _ = tx.send(3);
+//!     });
+//!     assert_eq!(rx.await, Ok(3));
+//! }
+//! ```
+
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use super::RemoteWaker;
+pub use crate::sync::oneshot::{RecvError, TryRecvError};
+use crate::task::coop;
+
+/// Create a oneshot channel.
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let inner = Arc::new(Inner {
+        state: Mutex::new(State {
+            value: None,
+            tx_done: false,
+            rx_closed: false,
+        }),
+        rx_waker: RemoteWaker::new(),
+        tx_waker: RemoteWaker::new(),
+    });
+    (
+        Sender {
+            inner: inner.clone(),
+        },
+        Receiver { inner },
+    )
+}
+
+struct Inner<T> {
+    state: Mutex<State<T>>,
+    rx_waker: RemoteWaker,
+    tx_waker: RemoteWaker,
+}
+
+struct State<T> {
+    value: Option<T>,
+    // The value was sent, or the sender dropped.
+    tx_done: bool,
+    rx_closed: bool,
+}
+
+/// Sends the value to the [`Receiver`], from any thread.
+pub struct Sender<T> {
+    inner: Arc<Inner<T>>,
+}
+
+/// Receives the value from the [`Sender`]. It is a future resolving to the
+/// value, or to an error if the sender is dropped without sending.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Receiver<T> {
+    inner: Arc<Inner<T>>,
+}
+
+impl<T> Sender<T> {
+    /// Send the value. It is returned back if the receiver is closed.
+    pub fn send(self, value: T) -> Result<(), T> {
+        {
+            let mut state = self.inner.state.lock().unwrap();
+            if state.rx_closed {
+                return Err(value);
+            }
+            state.value = Some(value);
+        }
+        // The receiver is woken when the sender drops.
+        Ok(())
+    }
+
+    /// Returns true if the receiver is closed or dropped.
+    pub fn is_closed(&self) -> bool {
+        self.inner.state.lock().unwrap().rx_closed
+    }
+
+    /// Wait for the receiver to be closed or dropped.
+    pub async fn closed(&mut self) {
+        std::future::poll_fn(|cx| self.poll_closed(cx)).await
+    }
+
+    /// Poll whether the receiver is closed or dropped.
+    pub fn poll_closed(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.is_closed() {
+            return Poll::Ready(());
+        }
+        self.inner.tx_waker.register(cx.waker());
+        if self.is_closed() {
+            return Poll::Ready(());
+        }
+        Poll::Pending
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.inner.state.lock().unwrap().tx_done = true;
+        self.inner.rx_waker.wake();
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Close the channel, the sender can no longer send.
+    ///
+    /// A value sent before can still be received.
+    pub fn close(&mut self) {
+        self.inner.state.lock().unwrap().rx_closed = true;
+        self.inner.tx_waker.wake();
+    }
+
+    /// Receive the value if it was sent.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let mut state = self.inner.state.lock().unwrap();
+        match state.value.take() {
+            Some(value) => {
+                // The value can be received once.
+                state.tx_done = true;
+                Ok(value)
+            }
+            None if state.tx_done => Err(TryRecvError::Closed),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+}
+
+impl<T> Future for Receiver<T> {
+    type Output = Result<T, RecvError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let coop = std::task::ready!(coop::poll_proceed(cx));
+        let mut registered = false;
+        loop {
+            match self.try_recv() {
+                Ok(value) => {
+                    coop.made_progress();
+                    return Poll::Ready(Ok(value));
+                }
+                Err(TryRecvError::Closed) => {
+                    coop.made_progress();
+                    return Poll::Ready(Err(RecvError(())));
+                }
+                Err(TryRecvError::Empty) if registered => return Poll::Pending,
+                Err(TryRecvError::Empty) => {
+                    self.inner.rx_waker.register(cx.waker());
+                    registered = true;
+                }
+            }
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender").finish()
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver").finish()
+    }
+}
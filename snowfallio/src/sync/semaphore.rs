@@ -0,0 +1,392 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    fmt,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+use crate::task::coop;
+
+/// A counting semaphore for the tasks of a thread.
+///
+/// Waiters are served in FIFO order: a task asking for many permits is not
+/// starved by tasks asking for few, they wait behind it.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::rc::Rc;
+///
+/// use snowfallio::sync::Semaphore;
+///
+/// #[snowfallio::main]
+/// async fn main() {
+///     // At most 2 requests in flight.
+///     let semaphore = Rc::new(Semaphore::new(2));
+///     for i in 0..8 {
+///         let permit = semaphore.clone().acquire_owned().await.unwrap();
+///         snowfallio::spawn(async move {
+///             println!("request {i}");
+///             drop(permit);
+///         });
+///     }
+/// }
+/// ```
+pub struct Semaphore {
+    state: RefCell<State>,
+}
+
+struct State {
+    permits: usize,
+    closed: bool,
+    waiters: VecDeque<Rc<Waiter>>,
+}
+
+struct Waiter {
+    // Permits still missing, the waiter is done when it reaches zero.
+    needed: Cell<usize>,
+    waker: Cell<Option<Waker>>,
+}
+
+/// A permit from a [`Semaphore`], released when dropped.
+#[must_use]
+pub struct SemaphorePermit<'a> {
+    semaphore: &'a Semaphore,
+    permits: usize,
+}
+
+/// An owned permit from a [`Semaphore`], released when dropped.
+#[must_use]
+pub struct OwnedSemaphorePermit {
+    semaphore: Rc<Semaphore>,
+    permits: usize,
+}
+
+/// Error returned by [`Semaphore::acquire`] when the semaphore is closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AcquireError(());
+
+/// Error returned by [`Semaphore::try_acquire`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryAcquireError {
+    /// The semaphore is closed.
+    Closed,
+    /// Not enough permits are available.
+    NoPermits,
+}
+
+/// Future returned by [`Semaphore::acquire`] and
+/// [`Semaphore::acquire_many`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Acquire<'a> {
+    semaphore: &'a Semaphore,
+    permits: usize,
+    waiter: Option<Rc<Waiter>>,
+}
+
+impl Semaphore {
+    /// The maximum number of permits of a semaphore.
+    pub const MAX_PERMITS: usize = usize::MAX >> 3;
+
+    /// Create a semaphore with the given number of permits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `permits` exceeds [`Semaphore::MAX_PERMITS`].
+    pub const fn new(permits: usize) -> Self {
+        assert!(permits <= Self::MAX_PERMITS, "too many permits");
+        Self {
+            state: RefCell::new(State {
+                permits,
+                closed: false,
+                waiters: VecDeque::new(),
+            }),
+        }
+    }
+
+    /// Returns the number of permits that can be acquired right away.
+    pub fn available_permits(&self) -> usize {
+        self.state.borrow().permits
+    }
+
+    /// Add permits, waking the waiters they satisfy.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the permits exceed [`Semaphore::MAX_PERMITS`].
+    pub fn add_permits(&self, n: usize) {
+        let mut wakers = Vec::new();
+        {
+            let mut state = self.state.borrow_mut();
+            let mut n = n;
+            while n > 0 {
+                let Some(waiter) = state.waiters.front() else {
+                    break;
+                };
+                let needed = waiter.needed.get();
+                let assigned = needed.min(n);
+                waiter.needed.set(needed - assigned);
+                n -= assigned;
+                if assigned == needed {
+                    let waiter = state.waiters.pop_front().unwrap();
+                    wakers.extend(waiter.waker.take());
+                }
+            }
+            assert!(state.permits + n <= Self::MAX_PERMITS, "too many permits");
+            state.permits += n;
+        }
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    /// Close the semaphore. Pending and future acquires fail, the permits
+    /// already acquired are not affected.
+    pub fn close(&self) {
+        let waiters = {
+            let mut state = self.state.borrow_mut();
+            state.closed = true;
+            std::mem::take(&mut state.waiters)
+        };
+        for waiter in waiters {
+            if let Some(waker) = waiter.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+
+    /// Returns true if the semaphore is closed.
+    pub fn is_closed(&self) -> bool {
+        self.state.borrow().closed
+    }
+
+    /// Wait for a permit.
+    pub fn acquire(&self) -> Acquire<'_> {
+        self.acquire_many(1)
+    }
+
+    /// Wait for `n` permits.
+    ///
+    /// The permits are reserved as they become available, so the waiters
+    /// behind wait until the `n` permits are acquired.
+    pub fn acquire_many(&self, n: usize) -> Acquire<'_> {
+        Acquire {
+            semaphore: self,
+            permits: n,
+            waiter: None,
+        }
+    }
+
+    /// Wait for a permit, returning a permit that holds the semaphore.
+    pub async fn acquire_owned(self: Rc<Self>) -> Result<OwnedSemaphorePermit, AcquireError> {
+        self.acquire_many_owned(1).await
+    }
+
+    /// Wait for `n` permits, returning a permit that holds the semaphore.
+    pub async fn acquire_many_owned(
+        self: Rc<Self>,
+        n: usize,
+    ) -> Result<OwnedSemaphorePermit, AcquireError> {
+        self.acquire_many(n).await?.forget();
+        Ok(OwnedSemaphorePermit {
+            semaphore: self,
+            permits: n,
+        })
+    }
+
+    /// Acquire a permit if one is available.
+    pub fn try_acquire(&self) -> Result<SemaphorePermit<'_>, TryAcquireError> {
+        self.try_acquire_many(1)
+    }
+
+    /// Acquire `n` permits if they are available.
+    pub fn try_acquire_many(&self, n: usize) -> Result<SemaphorePermit<'_>, TryAcquireError> {
+        self.try_take(n)?;
+        Ok(SemaphorePermit {
+            semaphore: self,
+            permits: n,
+        })
+    }
+
+    /// Acquire `n` permits if they are available, returning a permit that
+    /// holds the semaphore.
+    pub fn try_acquire_many_owned(
+        self: Rc<Self>,
+        n: usize,
+    ) -> Result<OwnedSemaphorePermit, TryAcquireError> {
+        self.try_take(n)?;
+        Ok(OwnedSemaphorePermit {
+            semaphore: self,
+            permits: n,
+        })
+    }
+
+    fn try_take(&self, n: usize) -> Result<(), TryAcquireError> {
+        let mut state = self.state.borrow_mut();
+        if state.closed {
+            return Err(TryAcquireError::Closed);
+        }
+        // Available permits imply that nobody waits.
+        if state.permits < n {
+            return Err(TryAcquireError::NoPermits);
+        }
+        state.permits -= n;
+        Ok(())
+    }
+}
+
+impl<'a> Future for Acquire<'a> {
+    type Output = Result<SemaphorePermit<'a>, AcquireError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let coop = std::task::ready!(coop::poll_proceed(cx));
+        let this = &mut *self;
+        let mut state = this.semaphore.state.borrow_mut();
+
+        match &this.waiter {
+            None => {
+                if state.closed {
+                    return Poll::Ready(Err(AcquireError(())));
+                }
+                if state.permits >= this.permits {
+                    state.permits -= this.permits;
+                } else {
+                    // Take what is there and wait for the rest.
+                    let waiter = Rc::new(Waiter {
+                        needed: Cell::new(this.permits - state.permits),
+                        waker: Cell::new(Some(cx.waker().clone())),
+                    });
+                    state.permits = 0;
+                    state.waiters.push_back(waiter.clone());
+                    this.waiter = Some(waiter);
+                    return Poll::Pending;
+                }
+            }
+            Some(waiter) => {
+                if waiter.needed.get() > 0 {
+                    if state.closed {
+                        return Poll::Ready(Err(AcquireError(())));
+                    }
+                    waiter.waker.set(Some(cx.waker().clone()));
+                    return Poll::Pending;
+                }
+                this.waiter = None;
+            }
+        }
+
+        coop.made_progress();
+        Poll::Ready(Ok(SemaphorePermit {
+            semaphore: this.semaphore,
+            permits: this.permits,
+        }))
+    }
+}
+
+impl Drop for Acquire<'_> {
+    fn drop(&mut self) {
+        let Some(waiter) = self.waiter.take() else {
+            return;
+        };
+        let assigned = self.permits - waiter.needed.get();
+        self.semaphore
+            .state
+            .borrow_mut()
+            .waiters
+            .retain(|w| !Rc::ptr_eq(w, &waiter));
+        // Hand the reserved permits to the next waiters.
+        if assigned > 0 {
+            self.semaphore.add_permits(assigned);
+        }
+    }
+}
+
+impl SemaphorePermit<'_> {
+    /// Forget the permit without releasing it to the semaphore.
+    pub fn forget(mut self) {
+        self.permits = 0;
+    }
+
+    /// Returns the number of permits held.
+    pub fn num_permits(&self) -> usize {
+        self.permits
+    }
+}
+
+impl Drop for SemaphorePermit<'_> {
+    fn drop(&mut self) {
+        if self.permits > 0 {
+            self.semaphore.add_permits(self.permits);
+        }
+    }
+}
+
+impl OwnedSemaphorePermit {
+    /// Forget the permit without releasing it to the semaphore.
+    pub fn forget(mut self) {
+        self.permits = 0;
+    }
+
+    /// Returns the number of permits held.
+    pub fn num_permits(&self) -> usize {
+        self.permits
+    }
+
+    /// Returns the semaphore the permit belongs to.
+    pub fn semaphore(&self) -> &Rc<Semaphore> {
+        &self.semaphore
+    }
+}
+
+impl Drop for OwnedSemaphorePermit {
+    fn drop(&mut self) {
+        if self.permits > 0 {
+            self.semaphore.add_permits(self.permits);
+        }
+    }
+}
+
+impl fmt::Debug for Semaphore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Semaphore")
+            .field("permits", &self.available_permits())
+            .finish()
+    }
+}
+
+impl fmt::Debug for SemaphorePermit<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SemaphorePermit")
+            .field("permits", &self.permits)
+            .finish()
+    }
+}
+
+impl fmt::Debug for OwnedSemaphorePermit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OwnedSemaphorePermit")
+            .field("permits", &self.permits)
+            .finish()
+    }
+}
+
+impl fmt::Display for AcquireError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("semaphore closed")
+    }
+}
+
+impl std::error::Error for AcquireError {}
+
+impl fmt::Display for TryAcquireError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryAcquireError::Closed => f.write_str("semaphore closed"),
+            TryAcquireError::NoPermits => f.write_str("no permits available"),
+        }
+    }
+}
+
+impl std::error::Error for TryAcquireError {}
@@ -0,0 +1,270 @@
+//! A channel publishing the latest value to the tasks of a thread.
+//!
+//! Receivers only see the most recent value; they are notified when it
+//! changes but intermediate values may be skipped.
+//!
+//! The value is stored in a `RefCell`: a [`Ref`] returned by `borrow` must
+//! not be held across an `.await` point or while sending, which would panic.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use snowfallio::sync::watch;
+//!
+//! #[snowfallio::main]
+//! async fn main() {
+//!     let (tx, mut rx) = watch::channel("initial");
+//!     snowfallio::spawn(async move {
+//!         while rx.changed().await.is_ok() {
+//!             println!("config: {}", *rx.borrow_and_update());
+//!         }
+//!     });
+//!     tx.send("updated").unwrap();
+//! }
+//! ```
+
+use std::{
+    cell::{Cell, Ref, RefCell},
+    fmt,
+    rc::Rc,
+};
+
+use super::Notify;
+
+/// Create a watch channel holding `init`.
+pub fn channel<T>(init: T) -> (Sender<T>, Receiver<T>) {
+    let shared = Rc::new(Shared {
+        value: RefCell::new(init),
+        version: Cell::new(0),
+        closed: Cell::new(false),
+        receivers: Cell::new(1),
+        changed: Notify::new(),
+        rx_dropped: Notify::new(),
+    });
+    (
+        Sender {
+            shared: shared.clone(),
+        },
+        Receiver { shared, version: 0 },
+    )
+}
+
+struct Shared<T> {
+    value: RefCell<T>,
+    version: Cell<u64>,
+    // The sender is dropped.
+    closed: Cell<bool>,
+    receivers: Cell<usize>,
+    changed: Notify,
+    rx_dropped: Notify,
+}
+
+/// Publishes values to the [`Receiver`]s.
+pub struct Sender<T> {
+    shared: Rc<Shared<T>>,
+}
+
+/// Observes the values published by the [`Sender`].
+pub struct Receiver<T> {
+    shared: Rc<Shared<T>>,
+    // Version of the last seen value.
+    version: u64,
+}
+
+/// Error returned by [`Sender::send`] when every receiver is dropped,
+/// holding the value back.
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub struct SendError<T>(pub T);
+
+/// Error returned by [`Receiver::changed`] and [`Receiver::has_changed`] when
+/// the sender is dropped.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct RecvError(());
+
+impl<T> Sender<T> {
+    /// Publish a value, failing if every receiver is dropped.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        if self.shared.receivers.get() == 0 {
+            return Err(SendError(value));
+        }
+        self.send_replace(value);
+        Ok(())
+    }
+
+    /// Publish a value even if there is no receiver, returning the previous
+    /// one.
+    pub fn send_replace(&self, value: T) -> T {
+        let old = self.shared.value.replace(value);
+        self.notify();
+        old
+    }
+
+    /// Modify the value in place and notify the receivers.
+    pub fn send_modify(&self, modify: impl FnOnce(&mut T)) {
+        self.send_if_modified(|value| {
+            modify(value);
+            true
+        });
+    }
+
+    /// Modify the value in place, and notify the receivers if `modify`
+    /// returns true.
+    pub fn send_if_modified(&self, modify: impl FnOnce(&mut T) -> bool) -> bool {
+        let modified = modify(&mut self.shared.value.borrow_mut());
+        if modified {
+            self.notify();
+        }
+        modified
+    }
+
+    /// Borrow the current value.
+    pub fn borrow(&self) -> Ref<'_, T> {
+        self.shared.value.borrow()
+    }
+
+    /// Create a receiver. The current value is marked as seen.
+    pub fn subscribe(&self) -> Receiver<T> {
+        self.shared.receivers.set(self.shared.receivers.get() + 1);
+        Receiver {
+            shared: self.shared.clone(),
+            version: self.shared.version.get(),
+        }
+    }
+
+    /// Returns the number of receivers.
+    pub fn receiver_count(&self) -> usize {
+        self.shared.receivers.get()
+    }
+
+    /// Returns true if every receiver is dropped.
+    pub fn is_closed(&self) -> bool {
+        self.receiver_count() == 0
+    }
+
+    /// Wait for every receiver to be dropped.
+    pub async fn closed(&self) {
+        loop {
+            let notified = self.shared.rx_dropped.notified();
+            if self.is_closed() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    fn notify(&self) {
+        self.shared.version.set(self.shared.version.get() + 1);
+        self.shared.changed.notify_waiters();
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.shared.closed.set(true);
+        self.shared.changed.notify_waiters();
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Borrow the current value, without marking it as seen.
+    pub fn borrow(&self) -> Ref<'_, T> {
+        self.shared.value.borrow()
+    }
+
+    /// Borrow the current value and mark it as seen.
+    pub fn borrow_and_update(&mut self) -> Ref<'_, T> {
+        self.version = self.shared.version.get();
+        self.shared.value.borrow()
+    }
+
+    /// Returns true if the value changed since it was last seen.
+    ///
+    /// Fails if the sender is dropped.
+    pub fn has_changed(&self) -> Result<bool, RecvError> {
+        if self.shared.closed.get() {
+            return Err(RecvError(()));
+        }
+        Ok(self.version != self.shared.version.get())
+    }
+
+    /// Wait for a value that was not seen, and mark it as seen.
+    ///
+    /// Fails if the sender is dropped and the current value was seen.
+    pub async fn changed(&mut self) -> Result<(), RecvError> {
+        loop {
+            let notified = self.shared.changed.notified();
+            let version = self.shared.version.get();
+            if version != self.version {
+                self.version = version;
+                return Ok(());
+            }
+            if self.shared.closed.get() {
+                return Err(RecvError(()));
+            }
+            notified.await;
+        }
+    }
+
+    /// Returns true if both receivers observe the same channel.
+    pub fn same_channel(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.shared, &other.shared)
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        self.shared.receivers.set(self.shared.receivers.get() + 1);
+        Self {
+            shared: self.shared.clone(),
+            version: self.version,
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let receivers = self.shared.receivers.get() - 1;
+        self.shared.receivers.set(receivers);
+        if receivers == 0 {
+            self.shared.rx_dropped.notify_waiters();
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender")
+            .field("value", &*self.borrow())
+            .finish()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver")
+            .field("value", &*self.borrow())
+            .finish()
+    }
+}
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SendError").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("channel closed")
+    }
+}
+
+impl<T> std::error::Error for SendError<T> {}
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("channel closed")
+    }
+}
+
+impl std::error::Error for RecvError {}
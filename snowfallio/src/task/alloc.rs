@@ -0,0 +1,173 @@
+//! Recycling of task allocations.
+//!
+//! Task cells of up to [`MAX_CLASS_SIZE`] bytes are rounded up to a power of
+//! two size class. When the runtime has a [`TaskCache`], freed cells of a class
+//! are kept in a per-thread free list and handed out to the next task of the
+//! same class instead of going through the global allocator.
+
+use std::{
+    alloc::{alloc, dealloc, handle_alloc_error, Layout},
+    cell::UnsafeCell,
+    ptr::NonNull,
+};
+
+use crate::runtime::CURRENT;
+
+/// Class of the cells allocated without the cache, freed with their own layout.
+pub(crate) const NO_CLASS: u8 = u8::MAX;
+
+const MIN_CLASS_SHIFT: u32 = 6;
+const CLASSES: usize = 8;
+const MAX_CLASS_SIZE: usize = 1 << (MIN_CLASS_SHIFT as usize + CLASSES - 1);
+const CLASS_ALIGN: usize = 16;
+
+/// Per-thread free lists of task cells, one per size class.
+pub(crate) struct TaskCache {
+    lists: UnsafeCell<[Vec<NonNull<u8>>; CLASSES]>,
+    capacity: usize,
+}
+
+impl TaskCache {
+    /// Create a cache keeping up to `capacity` cells per class.
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            lists: UnsafeCell::new(Default::default()),
+            capacity,
+        }
+    }
+
+    fn pop(&self, class: u8) -> Option<NonNull<u8>> {
+        unsafe { (*self.lists.get())[class as usize].pop() }
+    }
+
+    /// Returns the cell back if the list of its class is full.
+    fn push(&self, class: u8, ptr: NonNull<u8>) -> Result<(), NonNull<u8>> {
+        let list = unsafe { &mut (*self.lists.get())[class as usize] };
+        if list.len() >= self.capacity {
+            return Err(ptr);
+        }
+        list.push(ptr);
+        Ok(())
+    }
+
+    /// Number of cached cells of all classes.
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        unsafe { (*self.lists.get()).iter().map(Vec::len).sum() }
+    }
+}
+
+impl Drop for TaskCache {
+    fn drop(&mut self) {
+        for (class, list) in self.lists.get_mut().iter_mut().enumerate() {
+            for ptr in list.drain(..) {
+                unsafe { dealloc(ptr.as_ptr(), class_layout(class as u8)) };
+            }
+        }
+    }
+}
+
+fn size_class(layout: Layout) -> Option<u8> {
+    if layout.size() > MAX_CLASS_SIZE || layout.align() > CLASS_ALIGN {
+        return None;
+    }
+    let shift = layout.size().next_power_of_two().trailing_zeros();
+    Some(shift.saturating_sub(MIN_CLASS_SHIFT) as u8)
+}
+
+fn class_layout(class: u8) -> Layout {
+    let size = 1 << (MIN_CLASS_SHIFT + class as u32);
+    unsafe { Layout::from_size_align_unchecked(size, CLASS_ALIGN) }
+}
+
+fn alloc_layout(layout: Layout) -> NonNull<u8> {
+    NonNull::new(unsafe { alloc(layout) }).unwrap_or_else(|| handle_alloc_error(layout))
+}
+
+fn with_cache<R>(f: impl FnOnce(&TaskCache) -> R) -> Option<R> {
+    if !CURRENT.is_set() {
+        return None;
+    }
+    CURRENT.with(|ctx| ctx.task_cache.as_ref().map(f))
+}
+
+/// Allocate memory for a task cell, returns it with its size class.
+///
+/// The cell is taken from the cache of the current runtime if it has one.
+pub(crate) fn allocate(layout: Layout) -> (NonNull<u8>, u8) {
+    let cached = with_cache(|cache| {
+        size_class(layout).map(|class| {
+            let ptr = cache
+                .pop(class)
+                .unwrap_or_else(|| alloc_layout(class_layout(class)));
+            (ptr, class)
+        })
+    });
+    match cached.flatten() {
+        Some(cell) => cell,
+        None => (alloc_layout(layout), NO_CLASS),
+    }
+}
+
+/// Free memory returned by [`allocate`].
+///
+/// Cells with a size class go to the cache of the current runtime, which may be
+/// another than the one that allocated them, or to the global allocator if
+/// there is no room.
+///
+/// # Safety
+///
+/// `ptr` must have been returned by [`allocate`] with the same `layout` and
+/// `class`, and the cell must have been dropped.
+pub(crate) unsafe fn deallocate(ptr: NonNull<u8>, layout: Layout, class: u8) {
+    if class == NO_CLASS {
+        return dealloc(ptr.as_ptr(), layout);
+    }
+    let rest = with_cache(|cache| cache.push(class, ptr)).unwrap_or(Err(ptr));
+    if let Err(ptr) = rest {
+        dealloc(ptr.as_ptr(), class_layout(class));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn size_classes() {
+        let layout = |size, align| Layout::from_size_align(size, align).unwrap();
+        assert_eq!(size_class(layout(1, 8)), Some(0));
+        assert_eq!(size_class(layout(64, 8)), Some(0));
+        assert_eq!(size_class(layout(65, 8)), Some(1));
+        assert_eq!(
+            size_class(layout(MAX_CLASS_SIZE, 16)),
+            Some(CLASSES as u8 - 1)
+        );
+        assert_eq!(size_class(layout(MAX_CLASS_SIZE + 1, 8)), None);
+        assert_eq!(size_class(layout(64, 32)), None);
+        for class in 0..CLASSES as u8 {
+            assert_eq!(size_class(class_layout(class)), Some(class));
+        }
+    }
+
+    #[test]
+    fn cache_reuses_cells() {
+        let cache = TaskCache::new(2);
+        let layout = Layout::from_size_align(100, 8).unwrap();
+        let class = size_class(layout).unwrap();
+        let cells: Vec<_> = (0..3).map(|_| alloc_layout(class_layout(class))).collect();
+
+        for &cell in &cells[..2] {
+            assert!(cache.push(class, cell).is_ok());
+        }
+        // The list is full.
+        assert_eq!(cache.push(class, cells[2]), Err(cells[2]));
+        unsafe { dealloc(cells[2].as_ptr(), class_layout(class)) };
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.pop(class), Some(cells[1]));
+        assert_eq!(cache.pop(class + 1), None);
+        assert_eq!(cache.len(), 1);
+        // The remaining cell is freed with the cache.
+    }
+}
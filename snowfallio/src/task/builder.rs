@@ -0,0 +1,70 @@
+use std::future::Future;
+
+use super::{JoinHandle, Priority};
+
+/// Factory which is used to configure the properties of a new task.
+///
+/// # Examples
+///
+/// ```no_run
+/// use snowfallio::task::{Builder, Priority};
+///
+/// #[snowfallio::main]
+/// async fn main() {
+///     let handle = Builder::new()
+///         .name("compaction")
+///         .priority(Priority::Background)
+///         .spawn(async {
+///             // compact something
+///         });
+///     handle.await;
+/// }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Builder<'a> {
+    name: Option<&'a str>,
+    priority: Priority,
+}
+
+impl<'a> Builder<'a> {
+    /// Creates a new task builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Assigns a name to the task which will be spawned.
+    ///
+    /// The name is shown in task dumps when they are enabled with
+    /// [`RuntimeBuilder::enable_task_dump`](crate::RuntimeBuilder::enable_task_dump).
+    #[must_use]
+    pub fn name(mut self, name: &'a str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Sets the [`Priority`] of the task which will be spawned.
+    #[must_use]
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Spawns a task with this builder's settings on the current runtime.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if called outside of a snowfallio runtime.
+    #[track_caller]
+    pub fn spawn<T>(self, future: T) -> JoinHandle<T::Output>
+    where
+        T: Future + 'static,
+        T::Output: 'static,
+    {
+        crate::runtime::spawn_inner(
+            future,
+            self.priority,
+            self.name,
+            std::panic::Location::caller(),
+        )
+    }
+}
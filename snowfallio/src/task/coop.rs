@@ -0,0 +1,174 @@
+//! Cooperative task budgeting.
+//!
+//! Every time the runtime polls a task it hands the task a budget. Leaf futures
+//! (uring ops, timers, join handles) consume one unit each time they make
+//! progress. Once the budget is exhausted they return `Pending` and wake the
+//! task, so a task that keeps hitting immediately-ready resources still yields
+//! the thread to its siblings.
+// Heavily borrowed from tokio.
+// Copyright (c) 2021 Tokio Contributors, licensed under the MIT license.
+
+use std::{
+    cell::Cell,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+thread_local! {
+    static CURRENT: Cell<Budget> = const { Cell::new(Budget::unconstrained()) };
+}
+
+/// Opaque type tracking the amount of budget left for the running task.
+///
+/// `None` means the task is not constrained, e.g. when polled outside of a
+/// runtime.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Budget(Option<u8>);
+
+impl Budget {
+    /// Budget assigned to a task on each poll.
+    const fn initial() -> Budget {
+        Budget(Some(128))
+    }
+
+    const fn unconstrained() -> Budget {
+        Budget(None)
+    }
+
+    fn decrement(&mut self) -> bool {
+        match &mut self.0 {
+            Some(0) => false,
+            Some(n) => {
+                *n -= 1;
+                true
+            }
+            None => true,
+        }
+    }
+
+    fn is_unconstrained(self) -> bool {
+        self.0.is_none()
+    }
+}
+
+/// Run `f` with a fresh task budget, restoring the previous budget afterwards.
+#[inline]
+pub(crate) fn budget<R>(f: impl FnOnce() -> R) -> R {
+    struct ResetGuard(Budget);
+
+    impl Drop for ResetGuard {
+        fn drop(&mut self) {
+            CURRENT.with(|cell| cell.set(self.0));
+        }
+    }
+
+    let prev = CURRENT.with(|cell| cell.replace(Budget::initial()));
+    let _guard = ResetGuard(prev);
+    f()
+}
+
+/// Returns `true` if the running task has budget left.
+#[allow(unused)]
+#[inline]
+pub(crate) fn has_budget_remaining() -> bool {
+    CURRENT.with(|cell| cell.get().0 != Some(0))
+}
+
+/// Restores the consumed budget unit when dropped, unless the caller marks
+/// that progress was made.
+pub(crate) struct RestoreOnPending(Cell<Budget>);
+
+impl RestoreOnPending {
+    #[inline]
+    pub(crate) fn made_progress(&self) {
+        self.0.set(Budget::unconstrained());
+    }
+}
+
+impl Drop for RestoreOnPending {
+    fn drop(&mut self) {
+        let budget = self.0.get();
+        if !budget.is_unconstrained() {
+            CURRENT.with(|cell| cell.set(budget));
+        }
+    }
+}
+
+/// Consume one unit of budget, or return `Pending` and wake the task if the
+/// budget is exhausted.
+///
+/// Leaf futures should call this before doing any work, and call
+/// [`RestoreOnPending::made_progress`] once they are about to return `Ready`.
+#[inline]
+pub(crate) fn poll_proceed(cx: &mut Context<'_>) -> Poll<RestoreOnPending> {
+    CURRENT.with(|cell| {
+        let mut budget = cell.get();
+        if budget.decrement() {
+            let restore = RestoreOnPending(Cell::new(cell.get()));
+            cell.set(budget);
+            Poll::Ready(restore)
+        } else {
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    })
+}
+
+/// Consumes a unit of budget and returns the execution back to the runtime
+/// *if* the task's budget has been exhausted.
+///
+/// Use this in loops that do not otherwise await any snowfallio resource, so
+/// they still give other tasks a chance to run.
+///
+/// # Examples
+///
+/// ```no_run
+/// async fn sum_iterator(input: &mut impl Iterator<Item = i64>) -> i64 {
+///     let mut sum: i64 = 0;
+///     while let Some(i) = input.next() {
+///         sum += i;
+///         snowfallio::task::consume_budget().await
+///     }
+///     sum
+/// }
+/// ```
+pub async fn consume_budget() {
+    let mut status = Poll::Pending;
+
+    std::future::poll_fn(move |cx| {
+        if status.is_ready() {
+            return status;
+        }
+        status = poll_proceed(cx).map(|restore| {
+            restore.made_progress();
+        });
+        status
+    })
+    .await
+}
+
+/// Yields execution back to the runtime.
+///
+/// The current task is moved to the back of the run queue, so every other
+/// ready task runs before it is polled again.
+pub async fn yield_now() {
+    YieldNow { yielded: false }.await
+}
+
+struct YieldNow {
+    yielded: bool,
+}
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.yielded {
+            return Poll::Ready(());
+        }
+        self.yielded = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
@@ -1,11 +1,14 @@
 use std::{
+    alloc::Layout,
     cell::UnsafeCell,
     future::Future,
     pin::Pin,
+    ptr::NonNull,
     task::{Context, Poll, Waker},
 };
 
 use super::{
+    alloc,
     raw::{self, Vtable},
     state::State,
     utils::UnsafeCellExt,
@@ -43,6 +46,8 @@ pub(crate) struct Header {
     pub(crate) vtable: &'static Vtable,
     /// Thread ID(sync: used for wake task on its thread; sync disabled: do checking)
     pub(crate) owner_id: usize,
+    /// Size class of the allocation, see [`alloc`]
+    pub(crate) size_class: u8,
 }
 
 pub(crate) struct Trailer {
@@ -53,12 +58,15 @@ pub(crate) struct Trailer {
 impl<T: Future, S: Schedule> Cell<T, S> {
     /// Allocates a new task cell, containing the header, trailer, and core
     /// structures.
-    pub(crate) fn new(owner_id: usize, future: T, scheduler: S) -> Box<Cell<T, S>> {
-        Box::new(Cell {
+    pub(crate) fn new(owner_id: usize, future: T, scheduler: S) -> NonNull<Cell<T, S>> {
+        let (ptr, size_class) = alloc::allocate(Layout::new::<Self>());
+        let ptr = ptr.cast::<Self>();
+        let cell = Cell {
             header: Header {
                 state: State::new(),
                 vtable: raw::vtable::<T, S>(),
                 owner_id,
+                size_class,
             },
             core: Core {
                 scheduler,
@@ -69,7 +77,9 @@ impl<T: Future, S: Schedule> Cell<T, S> {
             trailer: Trailer {
                 waker: UnsafeCell::new(None),
             },
-        })
+        };
+        unsafe { ptr.as_ptr().write(cell) };
+        ptr
     }
 }
 
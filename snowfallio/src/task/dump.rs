@@ -0,0 +1,379 @@
+//! Registration and introspection of live tasks.
+//!
+//! When enabled with [`RuntimeBuilder::enable_task_dump`], every task spawned
+//! on the runtime is registered together with its spawn location and optional
+//! name. [`dump`] then lists the tasks that have not completed yet.
+//!
+//! [`RuntimeBuilder::enable_task_dump`]: crate::RuntimeBuilder::enable_task_dump
+
+use std::{
+    cell::{Cell, RefCell},
+    fmt,
+    panic::Location,
+    ptr::NonNull,
+    rc::Rc,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use fxhash::FxHashMap;
+
+use super::{core::Header, Task};
+use crate::{runtime::CURRENT, scheduler::LocalScheduler};
+
+thread_local! {
+    // Shared by the runtimes of the thread, so that a task released outside of
+    // `block_on` can still be unregistered.
+    static REGISTRY: Rc<Registry> = Rc::new(Registry::default());
+}
+
+/// Lists the live tasks of the current runtime.
+///
+/// Tasks are only tracked if the runtime was built with
+/// [`RuntimeBuilder::enable_task_dump`](crate::RuntimeBuilder::enable_task_dump),
+/// otherwise the dump is empty.
+///
+/// # Panics
+///
+/// This function panics if called outside of a snowfallio runtime.
+///
+/// # Examples
+///
+/// ```no_run
+/// let mut rt = snowfallio::RuntimeBuilder::<snowfallio::IoUringDriver>::new()
+///     .enable_task_dump()
+///     .build()
+///     .unwrap();
+/// rt.block_on(async {
+///     snowfallio::task::Builder::new()
+///         .name("idle")
+///         .spawn(std::future::pending::<()>());
+///     println!("{}", snowfallio::runtime::dump());
+/// });
+/// ```
+pub fn dump() -> Dump {
+    CURRENT.with(|ctx| match &ctx.task_registry {
+        Some(registry) => registry.dump(ctx.thread_id),
+        None => Dump::default(),
+    })
+}
+
+/// The registry of the current thread.
+pub(crate) fn registry() -> Rc<Registry> {
+    REGISTRY.with(Rc::clone)
+}
+
+/// Keys of the tasks released on another thread than the one of their
+/// registry, removed by the owning registry before it reads any header.
+pub(crate) type ReleaseQueue = Arc<Mutex<Vec<usize>>>;
+
+/// Unregister the task whose header is at `key`, as its memory is released.
+///
+/// `queue` belongs to the registry the task was registered in. The task can
+/// be freed on another thread, by the last waker dropped there, in which case
+/// the removal is queued for the owning registry. The lock is held until the
+/// key is queued, so a concurrent [`dump`] reading the header finishes before
+/// the memory is released.
+pub(crate) fn release(key: usize, queue: &ReleaseQueue) {
+    let released = REGISTRY
+        .try_with(|registry| {
+            let owned = Arc::ptr_eq(&registry.released, queue);
+            if owned {
+                registry.release(key);
+            }
+            owned
+        })
+        .unwrap_or(false);
+    if !released {
+        queue.lock().unwrap().push(key);
+    }
+}
+
+/// A snapshot of the live tasks of a runtime.
+#[derive(Debug, Clone, Default)]
+pub struct Dump {
+    tasks: Vec<TaskInfo>,
+}
+
+impl Dump {
+    /// The live tasks, in spawn order.
+    pub fn tasks(&self) -> &[TaskInfo] {
+        &self.tasks
+    }
+}
+
+impl fmt::Display for Dump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} live task(s)", self.tasks.len())?;
+        for task in self.tasks.iter() {
+            writeln!(f, "{task}")?;
+        }
+        Ok(())
+    }
+}
+
+/// State of a task, as stored in its header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskState {
+    /// The task is waiting to be woken.
+    Idle,
+    /// The task has been woken and is in the run queue.
+    Notified,
+    /// The task is being polled.
+    Running,
+    /// The task's future has completed.
+    Complete,
+}
+
+/// Information about a live task.
+#[derive(Debug, Clone)]
+pub struct TaskInfo {
+    id: u64,
+    name: Option<String>,
+    location: &'static Location<'static>,
+    state: TaskState,
+    join_interested: bool,
+    polls: u64,
+    age: Duration,
+    since_last_poll: Option<Duration>,
+    waiting_on: Vec<u8>,
+}
+
+impl TaskInfo {
+    /// A number identifying the task, unique within the runtime.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// The name given with [`Builder::name`](super::Builder::name).
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Where the task was spawned.
+    pub fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+
+    /// The current state of the task.
+    pub fn state(&self) -> TaskState {
+        self.state
+    }
+
+    /// Whether the task's `JoinHandle` is still alive.
+    pub fn join_interested(&self) -> bool {
+        self.join_interested
+    }
+
+    /// How many times the task has been polled.
+    pub fn polls(&self) -> u64 {
+        self.polls
+    }
+
+    /// Time elapsed since the task was spawned.
+    pub fn age(&self) -> Duration {
+        self.age
+    }
+
+    /// Time elapsed since the task was last polled, `None` if it was never
+    /// polled.
+    pub fn since_last_poll(&self) -> Option<Duration> {
+        self.since_last_poll
+    }
+
+    /// Opcodes of the uring operations the task was waiting on when it was
+    /// last polled.
+    pub fn waiting_on(&self) -> &[u8] {
+        &self.waiting_on
+    }
+}
+
+impl fmt::Display for TaskInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "task {}", self.id)?;
+        if let Some(name) = &self.name {
+            write!(f, " {name:?}")?;
+        }
+        write!(
+            f,
+            " at {}: {:?}, {} poll(s), age {:?}",
+            self.location, self.state, self.polls, self.age
+        )?;
+        if let Some(since) = self.since_last_poll {
+            write!(f, ", last polled {since:?} ago")?;
+        }
+        if !self.waiting_on.is_empty() {
+            f.write_str(", waiting on")?;
+            for opcode in self.waiting_on.iter() {
+                match opcode_name(*opcode) {
+                    Some(name) => write!(f, " {name}")?,
+                    None => write!(f, " opcode({opcode})")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the name of an io_uring opcode submitted by snowfallio.
+pub fn opcode_name(opcode: u8) -> Option<&'static str> {
+    use io_uring::opcode::*;
+
+    Some(match opcode {
+        Accept::CODE => "Accept",
+        Close::CODE => "Close",
+        Connect::CODE => "Connect",
+        Fsync::CODE => "Fsync",
+        OpenAt::CODE => "OpenAt",
+        PollAdd::CODE => "PollAdd",
+        Read::CODE => "Read",
+        Readv::CODE => "Readv",
+        Recv::CODE => "Recv",
+        RecvMsg::CODE => "RecvMsg",
+        Send::CODE => "Send",
+        SendMsg::CODE => "SendMsg",
+        Splice::CODE => "Splice",
+        Timeout::CODE => "Timeout",
+        Write::CODE => "Write",
+        Writev::CODE => "Writev",
+        _ => return None,
+    })
+}
+
+struct Registered {
+    // Valid while registered and the removals queued by other threads have
+    // been applied, the entry is removed before the task is freed.
+    header: NonNull<Header>,
+    id: u64,
+    name: Option<String>,
+    location: &'static Location<'static>,
+    spawned_at: Instant,
+    polls: u64,
+    last_poll: Option<Instant>,
+    waiting_on: Vec<u8>,
+}
+
+/// Registry of the live tasks of a thread, see [`registry`].
+#[derive(Default)]
+pub(crate) struct Registry {
+    tasks: RefCell<FxHashMap<usize, Registered>>,
+    // Tasks released on other threads.
+    released: ReleaseQueue,
+    // Task being polled.
+    current: Cell<Option<usize>>,
+    next_id: Cell<u64>,
+}
+
+impl Registry {
+    /// The queue the tasks registered here are released to from other threads.
+    pub(crate) fn release_queue(&self) -> ReleaseQueue {
+        self.released.clone()
+    }
+
+    pub(crate) fn register(
+        &self,
+        task: &Task<LocalScheduler>,
+        name: Option<String>,
+        location: &'static Location<'static>,
+    ) {
+        // A task freed on another thread may have left its memory to this
+        // one, its stale entry must not remove the new task later.
+        self.apply_released(&mut self.released.lock().unwrap());
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        self.tasks.borrow_mut().insert(
+            task.key(),
+            Registered {
+                header: NonNull::from(task.header()),
+                id,
+                name,
+                location,
+                spawned_at: Instant::now(),
+                polls: 0,
+                last_poll: None,
+                waiting_on: Vec::new(),
+            },
+        );
+    }
+
+    /// Run a poll of the task identified by `key`.
+    pub(crate) fn poll<R>(&self, key: usize, f: impl FnOnce() -> R) -> R {
+        if let Some(entry) = self.tasks.borrow_mut().get_mut(&key) {
+            entry.polls += 1;
+            entry.last_poll = Some(Instant::now());
+            entry.waiting_on.clear();
+        }
+        let prev = self.current.replace(Some(key));
+        let r = f();
+        self.current.set(prev);
+        r
+    }
+
+    /// Record that the task being polled waits on a uring op.
+    pub(crate) fn record_op_wait(&self, opcode: u8) {
+        if let Some(key) = self.current.get() {
+            if let Some(entry) = self.tasks.borrow_mut().get_mut(&key) {
+                entry.waiting_on.push(opcode);
+            }
+        }
+    }
+
+    /// Unregister the task being polled, which has just completed.
+    pub(crate) fn complete_current(&self) {
+        if let Some(key) = self.current.get() {
+            self.release(key);
+        }
+    }
+
+    fn release(&self, key: usize) {
+        self.tasks.borrow_mut().remove(&key);
+    }
+
+    fn apply_released(&self, released: &mut Vec<usize>) {
+        let mut tasks = self.tasks.borrow_mut();
+        for key in released.drain(..) {
+            tasks.remove(&key);
+        }
+    }
+
+    /// List the tasks of the runtime `owner`.
+    fn dump(&self, owner: usize) -> Dump {
+        // Hold the lock while reading the headers, so that no other thread
+        // frees a registered task meanwhile.
+        let mut released = self.released.lock().unwrap();
+        self.apply_released(&mut released);
+        let now = Instant::now();
+        let mut tasks: Vec<_> = self
+            .tasks
+            .borrow()
+            .values()
+            .map(|entry| (entry, unsafe { entry.header.as_ref() }))
+            .filter(|(_, header)| header.owner_id == owner)
+            .map(|(entry, header)| {
+                let snapshot = header.state.load();
+                let state = if snapshot.is_complete() {
+                    TaskState::Complete
+                } else if snapshot.is_running() {
+                    TaskState::Running
+                } else if snapshot.is_notified() {
+                    TaskState::Notified
+                } else {
+                    TaskState::Idle
+                };
+                TaskInfo {
+                    id: entry.id,
+                    name: entry.name.clone(),
+                    location: entry.location,
+                    state,
+                    join_interested: snapshot.is_join_interested(),
+                    polls: entry.polls,
+                    age: now - entry.spawned_at,
+                    since_last_poll: entry.last_poll.map(|t| now - t),
+                    waiting_on: entry.waiting_on.clone(),
+                }
+            })
+            .collect();
+        tasks.sort_by_key(|t| t.id);
+        Dump { tasks }
+    }
+}
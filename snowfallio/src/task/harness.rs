@@ -92,6 +92,9 @@ where
     pub(super) fn dealloc(self) {
         trace!("MONOIO DEBUG[Harness]:: dealloc");
 
+        let key = self.header() as *const Header as usize;
+        self.core().scheduler.task_released(key);
+
         // Release the join waker, if there is one.
         self.trailer().waker.with_mut(drop);
 
@@ -99,7 +102,13 @@ where
         self.core().stage.with_mut(drop);
 
         unsafe {
-            drop(Box::from_raw(self.cell.as_ptr()));
+            let size_class = self.header().size_class;
+            std::ptr::drop_in_place(self.cell.as_ptr());
+            super::alloc::deallocate(
+                self.cell.cast(),
+                std::alloc::Layout::new::<Cell<T, S>>(),
+                size_class,
+            );
         }
     }
 
@@ -275,6 +284,7 @@ where
         // stage. We transition from running to complete.
 
         let snapshot = self.header().state.transition_to_complete();
+        self.core().scheduler.task_completed();
 
         // We catch panics here in case dropping the future or waking the
         // JoinHandle panics.
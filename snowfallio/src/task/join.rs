@@ -22,12 +22,17 @@ impl<T> JoinHandle<T> {
     }
 }
 
+impl<T> Unpin for JoinHandle<T> {}
+
 impl<T> Future for JoinHandle<T> {
     type Output = T;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let mut ret = Poll::Pending;
 
+        // Keep track of task budget
+        let coop = ready!(crate::task::coop::poll_proceed(cx));
+
         // Raw should always be set. If it is not, this is due to polling after
         // completion
         let raw = self
@@ -49,6 +54,10 @@ impl<T> Future for JoinHandle<T> {
         unsafe {
             raw.try_read_output(&mut ret as *mut _ as *mut (), cx.waker());
         }
+
+        if ret.is_ready() {
+            coop.made_progress();
+        }
         ret
     }
 }
@@ -0,0 +1,259 @@
+//! A collection of tasks spawned on the current thread.
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    fmt,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+use fxhash::FxHashMap;
+
+use super::JoinHandle;
+
+/// A collection of tasks spawned on the current runtime.
+///
+/// A `JoinSet` can be used to await the completion of some or all of the tasks
+/// in the set. Tasks are returned in the order they complete.
+///
+/// When the `JoinSet` is dropped, all tasks in it are aborted.
+///
+/// # Examples
+///
+/// ```no_run
+/// use snowfallio::task::JoinSet;
+///
+/// #[snowfallio::main]
+/// async fn main() {
+///     let mut set = JoinSet::new();
+///     for i in 0..10 {
+///         set.spawn(async move { i });
+///     }
+///
+///     let mut seen = [false; 10];
+///     while let Some(i) = set.join_next().await {
+///         seen[i] = true;
+///     }
+///     assert!(seen.iter().all(|b| *b));
+/// }
+/// ```
+pub struct JoinSet<T> {
+    tasks: FxHashMap<usize, Entry<T>>,
+    shared: Rc<Shared>,
+    next_id: usize,
+}
+
+struct Entry<T> {
+    handle: JoinHandle<Option<T>>,
+    abort: Rc<AbortState>,
+}
+
+/// State shared between the set and its tasks.
+#[derive(Default)]
+struct Shared {
+    /// Ids of the tasks that have completed but are not yet joined.
+    ready: RefCell<VecDeque<usize>>,
+    /// Waker of the task calling `join_next`.
+    waker: RefCell<Option<Waker>>,
+}
+
+/// Per-task abort flag and the waker used to deliver it.
+#[derive(Default)]
+struct AbortState {
+    aborted: Cell<bool>,
+    waker: RefCell<Option<Waker>>,
+}
+
+impl AbortState {
+    fn abort(&self) {
+        self.aborted.set(true);
+        if let Some(waker) = self.waker.borrow_mut().take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> JoinSet<T> {
+    /// Create a new `JoinSet`.
+    pub fn new() -> Self {
+        Self {
+            tasks: FxHashMap::default(),
+            shared: Rc::new(Shared::default()),
+            next_id: 0,
+        }
+    }
+
+    /// Returns the number of tasks currently in the `JoinSet`.
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    /// Returns whether the `JoinSet` is empty.
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+}
+
+impl<T: 'static> JoinSet<T> {
+    /// Spawn the provided task on the current runtime and store it in this
+    /// `JoinSet`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if called outside of a snowfallio runtime.
+    #[track_caller]
+    pub fn spawn<F>(&mut self, task: F)
+    where
+        F: Future<Output = T> + 'static,
+    {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+
+        let abort = Rc::new(AbortState::default());
+        let handle = crate::spawn(Tracked {
+            future: task,
+            id,
+            abort: abort.clone(),
+            shared: self.shared.clone(),
+        });
+        self.tasks.insert(id, Entry { handle, abort });
+    }
+
+    /// Waits until one of the tasks in the set completes and returns its
+    /// output.
+    ///
+    /// Returns `None` if the set is empty.
+    pub async fn join_next(&mut self) -> Option<T> {
+        std::future::poll_fn(|cx| self.poll_join_next(cx)).await
+    }
+
+    /// Polls for one of the tasks in the set to complete.
+    ///
+    /// If this returns `Poll::Pending`, the waker of `cx` is stored and will be
+    /// notified once a task in the set completes. Only the waker from the most
+    /// recent call is scheduled to receive a wakeup.
+    ///
+    /// Returns `Poll::Ready(None)` if the set is empty.
+    pub fn poll_join_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        if self.tasks.is_empty() {
+            return Poll::Ready(None);
+        }
+
+        loop {
+            let id = match self.shared.ready.borrow_mut().pop_front() {
+                Some(id) => id,
+                None => break,
+            };
+            let mut entry = match self.tasks.remove(&id) {
+                Some(entry) => entry,
+                // The task was aborted after it completed.
+                None => continue,
+            };
+            match Pin::new(&mut entry.handle).poll(cx) {
+                Poll::Ready(Some(output)) => return Poll::Ready(Some(output)),
+                Poll::Ready(None) => continue,
+                Poll::Pending => {
+                    // The task is marked ready right before it completes; the
+                    // join handle has registered our waker in the meantime.
+                    self.tasks.insert(id, entry);
+                    self.shared.ready.borrow_mut().push_front(id);
+                    return Poll::Pending;
+                }
+            }
+        }
+
+        let mut waker = self.shared.waker.borrow_mut();
+        match waker.as_ref() {
+            Some(w) if w.will_wake(cx.waker()) => (),
+            _ => *waker = Some(cx.waker().clone()),
+        }
+        Poll::Pending
+    }
+
+    /// Aborts all tasks in this `JoinSet` and removes them from the set.
+    ///
+    /// The futures of aborted tasks are dropped the next time the runtime
+    /// polls them.
+    pub fn abort_all(&mut self) {
+        for (_, entry) in self.tasks.drain() {
+            entry.abort.abort();
+        }
+        self.shared.ready.borrow_mut().clear();
+    }
+
+    /// Aborts all tasks and waits for them to finish shutting down.
+    pub async fn shutdown(&mut self) {
+        let tasks = std::mem::take(&mut self.tasks);
+        self.shared.ready.borrow_mut().clear();
+        for entry in tasks.values() {
+            entry.abort.abort();
+        }
+        for entry in tasks.into_values() {
+            let _ = entry.handle.await;
+        }
+    }
+}
+
+impl<T> Default for JoinSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for JoinSet<T> {
+    fn drop(&mut self) {
+        for (_, entry) in self.tasks.drain() {
+            entry.abort.abort();
+        }
+    }
+}
+
+impl<T> fmt::Debug for JoinSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JoinSet").field("len", &self.len()).finish()
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// Future wrapper that reports completion to the owning `JoinSet` and
+    /// stops early when aborted.
+    struct Tracked<F> {
+        #[pin]
+        future: F,
+        id: usize,
+        abort: Rc<AbortState>,
+        shared: Rc<Shared>,
+    }
+}
+
+impl<F: Future> Future for Tracked<F> {
+    type Output = Option<F::Output>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        if this.abort.aborted.get() {
+            return Poll::Ready(None);
+        }
+
+        match this.future.poll(cx) {
+            Poll::Ready(output) => {
+                this.shared.ready.borrow_mut().push_back(*this.id);
+                if let Some(waker) = this.shared.waker.borrow_mut().take() {
+                    waker.wake();
+                }
+                Poll::Ready(Some(output))
+            }
+            Poll::Pending => {
+                let mut waker = this.abort.waker.borrow_mut();
+                match waker.as_ref() {
+                    Some(w) if w.will_wake(cx.waker()) => (),
+                    _ => *waker = Some(cx.waker().clone()),
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
@@ -3,8 +3,13 @@
 // Copyright (c) 2021 Tokio Contributors, licensed under the MIT license.
 
 mod utils;
+
+pub(crate) mod alloc;
 pub(crate) mod waker_fn;
 
+pub(crate) mod coop;
+pub use self::coop::{consume_budget, yield_now};
+
 mod core;
 use self::core::{Cell, Header};
 
@@ -15,6 +20,26 @@ mod join;
 #[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
 pub use self::join::JoinHandle;
 
+mod join_set;
+pub use self::join_set::JoinSet;
+
+mod scope;
+pub use self::scope::{scope, Scope};
+pub use crate::scheduler::Priority;
+
+mod builder;
+pub use self::builder::Builder;
+
+pub(crate) mod dump;
+pub use self::dump::{opcode_name, Dump, TaskInfo, TaskState};
+
+#[cfg(feature = "sync")]
+mod remote;
+#[cfg(feature = "sync")]
+pub(crate) use self::remote::remote_pair;
+#[cfg(feature = "sync")]
+pub use self::remote::RemoteJoinHandle;
+
 mod raw;
 use self::raw::RawTask;
 
@@ -43,6 +68,11 @@ impl<S: 'static> Task<S> {
         self.raw.header()
     }
 
+    /// Returns a key identifying the task while it is alive.
+    pub(crate) fn key(&self) -> usize {
+        self.header() as *const Header as usize
+    }
+
     pub(crate) fn run(self) {
         self.raw.poll();
     }
@@ -71,6 +101,11 @@ pub(crate) trait Schedule: Sized + 'static {
     fn yield_now(&self, task: Task<Self>) {
         self.schedule(task);
     }
+    /// Called on the owner thread when a task's future completes.
+    fn task_completed(&self) {}
+    /// Called before the memory of the task whose header is at `key` is
+    /// released.
+    fn task_released(&self, _key: usize) {}
 }
 
 pub(crate) fn new_task<T, S>(
@@ -53,8 +53,7 @@ impl RawTask {
         T: Future,
         S: Schedule,
     {
-        let ptr = Box::into_raw(Cell::new(owner_id, task, scheduler));
-        let ptr = unsafe { NonNull::new_unchecked(ptr as *mut Header) };
+        let ptr = Cell::new(owner_id, task, scheduler).cast::<Header>();
 
         RawTask { ptr }
     }
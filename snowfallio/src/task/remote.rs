@@ -0,0 +1,111 @@
+//! Handle to a task spawned on another thread.
+
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+use crate::blocking::JoinError;
+
+/// An owned permission to join on a task spawned on another runtime with
+/// [`spawn_on`](crate::spawn_on).
+///
+/// Unlike [`JoinHandle`](super::JoinHandle), it is `Send` and can be awaited
+/// from any runtime. It resolves to [`JoinError::Canceled`] if the target
+/// runtime drops the task before it completes, e.g. because it is shut down or
+/// was never registered.
+pub struct RemoteJoinHandle<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// Completes a [`RemoteJoinHandle`]. Dropping it without a value cancels the
+/// handle.
+pub(crate) struct Completer<T> {
+    shared: Arc<Shared<T>>,
+}
+
+struct Shared<T> {
+    state: Mutex<State<T>>,
+}
+
+struct State<T> {
+    value: Option<T>,
+    closed: bool,
+    waker: Option<Waker>,
+}
+
+pub(crate) fn remote_pair<T>() -> (Completer<T>, RemoteJoinHandle<T>) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            value: None,
+            closed: false,
+            waker: None,
+        }),
+    });
+    (
+        Completer {
+            shared: shared.clone(),
+        },
+        RemoteJoinHandle { shared },
+    )
+}
+
+impl<T> Shared<T> {
+    fn close(&self, value: Option<T>) {
+        let waker = {
+            let mut state = self.state.lock().unwrap();
+            state.value = value;
+            state.closed = true;
+            state.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Completer<T> {
+    pub(crate) fn complete(self, value: T) {
+        self.shared.close(Some(value));
+    }
+}
+
+impl<T> Drop for Completer<T> {
+    fn drop(&mut self) {
+        // Cancel the handle if no value was sent.
+        if !self.shared.state.lock().unwrap().closed {
+            self.shared.close(None);
+        }
+    }
+}
+
+impl<T> RemoteJoinHandle<T> {
+    /// Returns true if the task has completed or was canceled.
+    pub fn is_finished(&self) -> bool {
+        self.shared.state.lock().unwrap().closed
+    }
+}
+
+impl<T> Future for RemoteJoinHandle<T> {
+    type Output = Result<T, JoinError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.shared.state.lock().unwrap();
+        if state.closed {
+            return Poll::Ready(state.value.take().ok_or(JoinError::Canceled));
+        }
+        match &state.waker {
+            Some(waker) if waker.will_wake(cx.waker()) => {}
+            _ => state.waker = Some(cx.waker().clone()),
+        }
+        Poll::Pending
+    }
+}
+
+impl<T> std::fmt::Debug for RemoteJoinHandle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RemoteJoinHandle").finish()
+    }
+}
@@ -0,0 +1,115 @@
+//! Structured task scopes.
+
+use std::{
+    cell::{Cell, RefCell},
+    future::Future,
+    rc::Rc,
+};
+
+use super::JoinSet;
+
+/// A handle used to spawn child tasks inside a [`scope`].
+///
+/// The handle can be cloned and moved into child tasks, which may spawn
+/// further children into the same scope. Once the scope has closed, a clone
+/// that escaped it can no longer spawn.
+#[derive(Clone)]
+pub struct Scope {
+    inner: Rc<Inner>,
+}
+
+struct Inner {
+    children: RefCell<JoinSet<()>>,
+    closed: Cell<bool>,
+}
+
+impl Scope {
+    /// Spawn a child task into this scope.
+    ///
+    /// The enclosing [`scope`] call does not return until the child finishes.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the scope has already returned or been dropped.
+    #[track_caller]
+    pub fn spawn<F>(&self, task: F)
+    where
+        F: Future<Output = ()> + 'static,
+    {
+        assert!(
+            !self.inner.closed.get(),
+            "cannot spawn into a scope that has closed"
+        );
+        self.inner.children.borrow_mut().spawn(task);
+    }
+
+    /// Returns the number of children that have not been joined yet.
+    pub fn len(&self) -> usize {
+        self.inner.children.borrow().len()
+    }
+
+    /// Returns whether all children have been joined.
+    pub fn is_empty(&self) -> bool {
+        self.inner.children.borrow().is_empty()
+    }
+}
+
+/// Closes the scope once it returns, aborting the remaining children if the
+/// scope future is dropped early.
+struct AbortGuard(Scope);
+
+impl Drop for AbortGuard {
+    fn drop(&mut self) {
+        self.0.inner.closed.set(true);
+        self.0.inner.children.borrow_mut().abort_all();
+    }
+}
+
+/// Run `f` with a [`Scope`] and wait for every child spawned into it to finish
+/// before returning `f`'s output.
+///
+/// If the returned future is dropped before completion, all children that are
+/// still running are aborted, so no child outlives the scope.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::{cell::Cell, rc::Rc};
+///
+/// #[snowfallio::main]
+/// async fn main() {
+///     let counter = Rc::new(Cell::new(0));
+///     snowfallio::task::scope(|s| {
+///         let counter = counter.clone();
+///         async move {
+///             for _ in 0..10 {
+///                 let counter = counter.clone();
+///                 s.spawn(async move { counter.set(counter.get() + 1) });
+///             }
+///         }
+///     })
+///     .await;
+///     assert_eq!(counter.get(), 10);
+/// }
+/// ```
+pub async fn scope<F, Fut>(f: F) -> Fut::Output
+where
+    F: FnOnce(Scope) -> Fut,
+    Fut: Future,
+{
+    let scope = Scope {
+        inner: Rc::new(Inner {
+            children: RefCell::new(JoinSet::new()),
+            closed: Cell::new(false),
+        }),
+    };
+    let guard = AbortGuard(scope.clone());
+
+    let output = f(scope).await;
+    // Children may spawn more children, so join until the set is drained.
+    while std::future::poll_fn(|cx| guard.0.inner.children.borrow_mut().poll_join_next(cx))
+        .await
+        .is_some()
+    {}
+    output
+}
@@ -36,6 +36,13 @@ pub(crate) fn should_poll() -> bool {
     SHOULD_POLL.with(|b| b.replace(false))
 }
 
+/// Returns whether the main future has been woken, without consuming the
+/// notification.
+#[inline]
+pub(crate) fn poll_pending() -> bool {
+    SHOULD_POLL.with(|b| b.get())
+}
+
 #[inline]
 pub(crate) fn set_poll() {
     SHOULD_POLL.with(|b| {
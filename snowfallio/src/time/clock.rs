@@ -4,21 +4,190 @@
 //! `test-util` feature flag is enabled, the values returned for `now()` are
 //! configurable.
 
-use crate::time::Instant;
+#[cfg(not(feature = "test-util"))]
+mod variant {
+    use crate::time::Instant;
 
-#[derive(Default, Debug, Clone)]
-pub(crate) struct Clock {}
+    #[derive(Default, Debug, Clone)]
+    pub(crate) struct Clock {}
 
-pub(crate) fn now() -> Instant {
-    Instant::from_std(std::time::Instant::now())
+    pub(crate) fn now() -> Instant {
+        Instant::from_std(std::time::Instant::now())
+    }
+
+    impl Clock {
+        pub(crate) fn new() -> Clock {
+            Clock {}
+        }
+
+        pub(crate) fn now(&self) -> Instant {
+            now()
+        }
+
+        pub(crate) fn start_time(&self) -> Instant {
+            now()
+        }
+    }
 }
 
-impl Clock {
-    pub(crate) fn new() -> Clock {
-        Clock {}
+#[cfg(feature = "test-util")]
+mod variant {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::time::{driver::Handle, Duration, Instant};
+
+    /// A handle to a source of time, shared by the time driver of a runtime.
+    #[derive(Debug, Clone)]
+    pub(crate) struct Clock {
+        inner: Rc<RefCell<Inner>>,
+    }
+
+    #[derive(Debug)]
+    struct Inner {
+        /// Instant at which the clock was created.
+        start: std::time::Instant,
+
+        /// Instant to use as the clock's base instant.
+        base: std::time::Instant,
+
+        /// Instant at which the clock was last unfrozen, `None` while paused.
+        unfrozen: Option<std::time::Instant>,
+    }
+
+    /// Returns the time handle of the current runtime, if it has a timer.
+    fn time_handle() -> Option<Handle> {
+        crate::runtime::CURRENT
+            .try_with(|maybe_ctx| maybe_ctx.and_then(|ctx| ctx.time_handle.clone()))
+    }
+
+    fn current_handle(action: &str) -> Handle {
+        time_handle().unwrap_or_else(|| {
+            panic!("can not {action} time outside of a runtime with the timer enabled")
+        })
     }
 
-    pub(crate) fn now(&self) -> Instant {
-        now()
+    /// Pauses time.
+    ///
+    /// The current value of `Instant::now()` is saved and all subsequent calls
+    /// to `now` will return the saved value. The saved value can be changed by
+    /// [`advance`] or by the time auto-advancing once the runtime has no work
+    /// to do. This only affects the `Instant` type in snowfallio, and not the
+    /// `Instant` of std.
+    ///
+    /// If time is paused and the runtime has no work to do, the clock is
+    /// auto-advanced to the next pending timer. This means that `sleep` or
+    /// other timer-backed primitives can cause the runtime to advance the
+    /// current time when awaited.
+    ///
+    /// # Panics
+    ///
+    /// Panics if time is already frozen or if called from outside of a runtime
+    /// with the timer enabled.
+    pub fn pause() {
+        current_handle("pause").clock().pause();
+    }
+
+    /// Resumes time.
+    ///
+    /// Clears the saved `Instant::now()` value. Subsequent calls to
+    /// `Instant::now()` will return the value of the std clock, offset by the
+    /// time advanced while paused.
+    ///
+    /// # Panics
+    ///
+    /// Panics if time is not frozen or if called from outside of a runtime
+    /// with the timer enabled.
+    pub fn resume() {
+        current_handle("resume").clock().resume();
+    }
+
+    /// Advances time.
+    ///
+    /// Increments the saved `Instant::now()` value by `duration`. Subsequent
+    /// calls to `Instant::now()` will return the result of the increment. The
+    /// timers elapsed by the increment fire before the returned future
+    /// completes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if time is not frozen or if called from outside of a runtime
+    /// with the timer enabled.
+    pub async fn advance(duration: Duration) {
+        let handle = current_handle("advance");
+        handle.clock().advance(duration);
+        handle.process();
+        // Let the tasks of the elapsed timers run.
+        crate::task::yield_now().await;
+    }
+
+    /// Returns the current instant, from the clock of the current runtime if
+    /// there is one.
+    pub(crate) fn now() -> Instant {
+        match time_handle() {
+            Some(handle) => handle.clock().now(),
+            None => Instant::from_std(std::time::Instant::now()),
+        }
+    }
+
+    impl Clock {
+        /// Returns a new clock, initially not paused.
+        pub(crate) fn new() -> Clock {
+            let now = std::time::Instant::now();
+            Clock {
+                inner: Rc::new(RefCell::new(Inner {
+                    start: now,
+                    base: now,
+                    unfrozen: Some(now),
+                })),
+            }
+        }
+
+        pub(crate) fn pause(&self) {
+            let mut inner = self.inner.borrow_mut();
+            let elapsed = inner
+                .unfrozen
+                .take()
+                .expect("time is already frozen")
+                .elapsed();
+            // Freeze on a whole millisecond since the start, the resolution of
+            // the timers, so that advancing to a deadline fires its timer.
+            let since_start = (inner.base + elapsed - inner.start).as_nanos();
+            let aligned = since_start.div_ceil(1_000_000) as u64;
+            inner.base = inner.start + Duration::from_millis(aligned);
+        }
+
+        pub(crate) fn resume(&self) {
+            let mut inner = self.inner.borrow_mut();
+            assert!(inner.unfrozen.is_none(), "time is not frozen");
+            inner.unfrozen = Some(std::time::Instant::now());
+        }
+
+        pub(crate) fn is_paused(&self) -> bool {
+            self.inner.borrow().unfrozen.is_none()
+        }
+
+        pub(crate) fn advance(&self, duration: Duration) {
+            let mut inner = self.inner.borrow_mut();
+            assert!(inner.unfrozen.is_none(), "time is not frozen");
+            inner.base += duration;
+        }
+
+        pub(crate) fn now(&self) -> Instant {
+            let inner = self.inner.borrow();
+            let mut ret = inner.base;
+            if let Some(unfrozen) = inner.unfrozen {
+                ret += unfrozen.elapsed();
+            }
+            Instant::from_std(ret)
+        }
+
+        /// Returns the instant at which the clock was created.
+        pub(crate) fn start_time(&self) -> Instant {
+            Instant::from_std(self.inner.borrow().start)
+        }
     }
 }
+
+#[cfg(feature = "test-util")]
+pub use variant::{advance, pause, resume};
+pub(crate) use variant::{now, Clock};
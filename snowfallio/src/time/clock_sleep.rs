@@ -0,0 +1,140 @@
+//! Sleeps measured on kernel clocks other than the monotonic one.
+//!
+//! See [`ClockSleep`] documentation for more details.
+
+use std::{
+    future::Future,
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Duration, SystemTime},
+};
+
+use crate::driver::op::{Op, Timeout};
+
+/// Waits until the system wall clock reaches `deadline`.
+///
+/// Unlike [`sleep_until`], which follows the monotonic clock, the deadline is
+/// tracked by the kernel on `CLOCK_REALTIME`. If the wall clock is set forward
+/// past `deadline` the sleep completes right away, and if it is set backwards
+/// the sleep lasts until the wall clock catches up again. This suits cron-like
+/// jobs that must run at a given time of day.
+///
+/// The sleep is an io_uring timeout with `IORING_TIMEOUT_REALTIME`, which
+/// requires kernel 5.15+; on older kernels it completes with `EINVAL`. It does
+/// not need the runtime timer to be enabled.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::{Duration, SystemTime};
+///
+/// use snowfallio::time::sleep_until_system;
+///
+/// #[snowfallio::main]
+/// async fn main() {
+///     sleep_until_system(SystemTime::now() + Duration::from_millis(10))
+///         .await
+///         .unwrap();
+/// }
+/// ```
+///
+/// [`sleep_until`]: crate::time::sleep_until()
+pub fn sleep_until_system(deadline: SystemTime) -> ClockSleep {
+    let since_epoch = deadline
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+    ClockSleep::new(Op::timeout_realtime(since_epoch))
+}
+
+/// Waits until `duration` has elapsed on the boot time clock.
+///
+/// `CLOCK_BOOTTIME` keeps counting while the system is suspended, whereas
+/// [`sleep`] stops. Use it for deadlines that must account for the time the
+/// machine spent asleep, e.g. lease or certificate expiry.
+///
+/// The sleep is an io_uring timeout with `IORING_TIMEOUT_BOOTTIME`, which
+/// requires kernel 5.15+; on older kernels it completes with `EINVAL`. It does
+/// not need the runtime timer to be enabled.
+///
+/// [`sleep`]: crate::time::sleep()
+pub fn sleep_boottime(duration: Duration) -> ClockSleep {
+    ClockSleep::new(Op::timeout_boottime(duration))
+}
+
+/// Waits until `duration` has elapsed on the monotonic clock, without the
+/// runtime timer. Used by the crate internals which can not require the timer
+/// to be enabled.
+pub(crate) fn sleep_monotonic(duration: Duration) -> ClockSleep {
+    ClockSleep::new(Op::timeout(duration))
+}
+
+/// Future returned by [`sleep_until_system`] and [`sleep_boottime`].
+///
+/// Dropping it before completion cancels the kernel timeout.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct ClockSleep {
+    state: State,
+}
+
+enum State {
+    Waiting(Op<Timeout>),
+    Failed(io::Error),
+    Done,
+}
+
+impl ClockSleep {
+    fn new(op: io::Result<Op<Timeout>>) -> ClockSleep {
+        let state = match op {
+            Ok(op) => State::Waiting(op),
+            Err(e) => State::Failed(e),
+        };
+        ClockSleep { state }
+    }
+
+    /// Returns `true` if the sleep has completed.
+    pub fn is_elapsed(&self) -> bool {
+        matches!(self.state, State::Done)
+    }
+}
+
+impl Future for ClockSleep {
+    type Output = io::Result<()>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let res = match &mut self.state {
+            State::Waiting(op) => ready!(Pin::new(op).poll(cx)).meta.result,
+            State::Failed(_) => match std::mem::replace(&mut self.state, State::Done) {
+                State::Failed(e) => Err(e),
+                _ => unreachable!(),
+            },
+            State::Done => panic!("`ClockSleep` polled after completion"),
+        };
+        self.state = State::Done;
+
+        match res {
+            // An expired timeout completes with ETIME.
+            Err(e) if e.raw_os_error() == Some(libc::ETIME) => Poll::Ready(Ok(())),
+            Err(e) => Poll::Ready(Err(e)),
+            Ok(_) => Poll::Ready(Ok(())),
+        }
+    }
+}
+
+impl Drop for ClockSleep {
+    fn drop(&mut self) {
+        // A pending timeout would otherwise stay in the kernel until the
+        // deadline.
+        if let State::Waiting(op) = &self.state {
+            unsafe { op.op_canceller().cancel() };
+        }
+    }
+}
+
+impl std::fmt::Debug for ClockSleep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClockSleep")
+            .field("elapsed", &self.is_elapsed())
+            .finish()
+    }
+}
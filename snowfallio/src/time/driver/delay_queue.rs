@@ -0,0 +1,453 @@
+//! A queue of delayed elements.
+//!
+//! See [`DelayQueue`] for more details.
+//!
+//! [`DelayQueue`]: struct@DelayQueue
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    future::Future,
+    pin::Pin,
+    ptr::NonNull,
+    task::{self, Poll},
+};
+
+use super::{wheel::Wheel, Handle, TimerEntry, TimerHandle, TimerShared};
+use crate::{
+    io::stream::Stream,
+    time::{error::InsertError, Duration, Instant},
+    utils::{linked_list::Link, slab::Slab},
+};
+
+/// A queue of delayed elements.
+///
+/// Once an element is inserted into the `DelayQueue`, it is yielded once the
+/// specified deadline has been reached.
+///
+/// Elements are kept in a timing wheel owned by the queue, with one intrusive
+/// timer entry stored inline next to each value. Only a single timer is
+/// registered with the runtime's time driver, armed for the earliest deadline
+/// in the queue, so inserting, resetting and removing an element never
+/// allocates a [`Sleep`] or touches the driver's wheel.
+///
+/// # Usage
+///
+/// Elements are inserted into `DelayQueue` using the [`insert`] or
+/// [`insert_at`] methods. A deadline is provided with the item and a [`Key`] is
+/// returned. The key is used to remove the entry or to change the deadline at
+/// which it should be yielded back.
+///
+/// Once delays have been configured, the `DelayQueue` is used via its
+/// [`Stream`] implementation or [`poll_expired`]. The queue yields `None` once
+/// it is empty; inserting new elements makes it yield them again.
+///
+/// As with [`Sleep`], the queue operates at millisecond granularity and must be
+/// used from within the context of a runtime with the timer enabled.
+///
+/// # Examples
+///
+/// Tracking idle deadlines of connections, keyed by connection id.
+///
+/// ```
+/// use std::collections::HashMap;
+///
+/// use snowfallio::{
+///     io::stream::Stream,
+///     time::{DelayQueue, Duration, Key},
+/// };
+///
+/// #[snowfallio::main(timer_enabled = true)]
+/// async fn main() {
+///     let mut idle = DelayQueue::new();
+///     let mut keys: HashMap<u32, Key> = HashMap::new();
+///
+///     for conn in 0..3u32 {
+///         keys.insert(conn, idle.insert(conn, Duration::from_millis(10)));
+///     }
+///
+///     // Connection 1 saw traffic, push its deadline back.
+///     idle.reset(&keys[&1], Duration::from_millis(50));
+///
+///     let first = idle.next().await.unwrap().into_inner();
+///     assert_ne!(first, 1);
+/// }
+/// ```
+///
+/// [`insert`]: DelayQueue::insert
+/// [`insert_at`]: DelayQueue::insert_at
+/// [`poll_expired`]: DelayQueue::poll_expired
+/// [`Sleep`]: struct@crate::time::Sleep
+pub struct DelayQueue<T> {
+    /// Stores the elements and their timer state.
+    slab: Slab<Slot<T>>,
+
+    /// Lookup structure tracking all delays in the queue.
+    wheel: Wheel,
+
+    /// Keys of elements whose deadline had already passed when they were
+    /// inserted. They are yielded before anything in the wheel.
+    expired: VecDeque<usize>,
+
+    /// The single driver timer, armed for the next expiration of the wheel.
+    delay: Pin<Box<TimerEntry>>,
+
+    /// Tick the driver timer is currently armed for.
+    scheduled: Option<u64>,
+
+    handle: Handle,
+
+    len: usize,
+}
+
+/// An entry in the queue.
+///
+/// `timer` must stay the first field: the wheel hands back pointers to it and
+/// they are cast back to the slot.
+#[repr(C)]
+struct Slot<T> {
+    timer: TimerShared,
+    key: usize,
+    deadline: Instant,
+    value: T,
+}
+
+/// Token to a value stored in a [`DelayQueue`].
+///
+/// Instances of `Key` are returned by [`DelayQueue::insert`]. See the
+/// [`DelayQueue`] documentation for more details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    index: usize,
+}
+
+/// An entry yielded by a [`DelayQueue`] once its deadline is reached.
+#[derive(Debug)]
+pub struct Expired<T> {
+    data: T,
+    deadline: Instant,
+    key: Key,
+}
+
+impl<T> DelayQueue<T> {
+    /// Creates a new, empty, `DelayQueue`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if it is not called from a runtime with the timer
+    /// enabled.
+    pub fn new() -> DelayQueue<T> {
+        let handle = Handle::current();
+        let delay = Box::pin(TimerEntry::new(&handle, Instant::far_future()));
+
+        DelayQueue {
+            slab: Slab::new(),
+            wheel: Wheel::new(),
+            expired: VecDeque::new(),
+            delay,
+            scheduled: None,
+            handle,
+            len: 0,
+        }
+    }
+
+    /// Inserts `value` into the queue set to expire at a specific instant in
+    /// time.
+    ///
+    /// The returned [`Key`] may be used to [`remove`] the entry or [`reset`]
+    /// its deadline. It is only valid until the entry has been yielded or
+    /// removed.
+    ///
+    /// [`remove`]: DelayQueue::remove
+    /// [`reset`]: DelayQueue::reset
+    pub fn insert_at(&mut self, value: T, when: Instant) -> Key {
+        let key = self.slab.insert(Slot {
+            timer: TimerShared::new(),
+            key: 0,
+            deadline: when,
+            value,
+        });
+        self.len += 1;
+
+        let slot = self.slot_mut(&Key { index: key });
+        slot.key = key;
+        let timer = NonNull::from(&slot.timer);
+        self.register(key, timer, when);
+        Key { index: key }
+    }
+
+    /// Inserts `value` into the queue set to expire after the requested
+    /// duration elapses.
+    ///
+    /// Equivalent to `insert_at(value, Instant::now() + timeout)`.
+    pub fn insert(&mut self, value: T, timeout: Duration) -> Key {
+        self.insert_at(value, deadline_after(timeout))
+    }
+
+    /// Sets the delay of the item associated with `key` to expire at `when`.
+    ///
+    /// This function is identical to [`reset`] but takes an `Instant` instead
+    /// of a `Duration`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `key` is not contained by the queue.
+    ///
+    /// [`reset`]: DelayQueue::reset
+    pub fn reset_at(&mut self, key: &Key, when: Instant) {
+        let timer = self.unregister(key);
+        self.slot_mut(key).deadline = when;
+        self.register(key.index, timer, when);
+    }
+
+    /// Sets the delay of the item associated with `key` to expire after
+    /// `timeout`.
+    ///
+    /// The entry is kept in place, no allocation is performed.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `key` is not contained by the queue.
+    pub fn reset(&mut self, key: &Key, timeout: Duration) {
+        self.reset_at(key, deadline_after(timeout))
+    }
+
+    /// Removes the item associated with `key` from the queue.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `key` is not contained by the queue.
+    pub fn remove(&mut self, key: &Key) -> Expired<T> {
+        self.unregister(key);
+        self.take(key.index)
+    }
+
+    /// Returns the deadline of the item associated with `key`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `key` is not contained by the queue.
+    pub fn deadline(&mut self, key: &Key) -> Instant {
+        self.slot_mut(key).deadline
+    }
+
+    /// Clears the queue, removing all items.
+    pub fn clear(&mut self) {
+        // The wheel only holds intrusive links into the slab and never walks
+        // them on drop, so both can simply be replaced.
+        self.wheel = Wheel::new();
+        self.slab = Slab::new();
+        self.expired.clear();
+        self.scheduled = None;
+        self.len = 0;
+    }
+
+    /// Returns the number of items in the queue.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no items in the queue.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Attempts to pull out the next value of the delay queue, registering the
+    /// current task for wakeup if the value is not yet available, and returning
+    /// `None` if the queue is exhausted.
+    pub fn poll_expired(&mut self, cx: &mut task::Context<'_>) -> Poll<Option<Expired<T>>> {
+        // Keep track of task budget
+        let coop = ready!(crate::task::coop::poll_proceed(cx));
+
+        loop {
+            if let Some(key) = self.expired.pop_front() {
+                coop.made_progress();
+                return Poll::Ready(Some(self.take(key)));
+            }
+
+            let now = self.handle.time_source().now();
+            if let Some(item) = self.wheel.poll(now) {
+                let key = unsafe { slot_key::<T>(&item) };
+                unsafe { item.fire(Ok(())) };
+                coop.made_progress();
+                return Poll::Ready(Some(self.take(key)));
+            }
+
+            let when = match self.wheel.next_expiration_time() {
+                Some(when) => when,
+                None => {
+                    self.scheduled = None;
+                    return Poll::Ready(None);
+                }
+            };
+            if self.scheduled != Some(when) {
+                self.arm(when);
+            }
+
+            match self.delay.as_mut().poll_elapsed(cx) {
+                // The driver reached the armed tick, poll the wheel again.
+                Poll::Ready(_) => self.scheduled = None,
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+
+    /// Waits for the next expired item, returning `None` if the queue is empty.
+    pub fn expired(&mut self) -> ExpiredFuture<'_, T> {
+        ExpiredFuture { queue: self }
+    }
+
+    /// Inserts the timer of the slot at `key` into the wheel, or into the
+    /// expired list if `when` has already passed.
+    fn register(&mut self, key: usize, timer: NonNull<TimerShared>, when: Instant) {
+        let tick = self.handle.time_source().deadline_to_tick(when);
+        unsafe {
+            timer.as_ref().set_expiration(tick);
+            match self.wheel.insert(timer.as_ref().handle()) {
+                Ok(when) => {
+                    if self.scheduled.is_none_or(|scheduled| when < scheduled) {
+                        self.arm(when);
+                    }
+                }
+                Err((item, InsertError::Elapsed)) => {
+                    item.fire(Ok(()));
+                    self.expired.push_back(key);
+                    // Wake up the task polling the queue.
+                    self.arm(0);
+                }
+            }
+        }
+    }
+
+    /// Takes the timer of the slot at `key` out of the wheel or the expired
+    /// list.
+    fn unregister(&mut self, key: &Key) -> NonNull<TimerShared> {
+        let timer = NonNull::from(&self.slot_mut(key).timer);
+        unsafe {
+            if timer.as_ref().might_be_registered() {
+                self.wheel.remove(timer);
+                timer.as_ref().handle().fire(Ok(()));
+            } else {
+                self.expired.retain(|index| *index != key.index);
+            }
+        }
+        timer
+    }
+
+    fn slot_mut(&mut self, key: &Key) -> &mut Slot<T> {
+        match self.slab.get(key.index) {
+            // Slots live in pages which are never moved, the reference stays
+            // valid for as long as `self` is borrowed.
+            Some(mut slot) => unsafe { &mut *(slot.as_mut() as *mut Slot<T>) },
+            None => panic!("invalid key"),
+        }
+    }
+
+    /// Removes an unregistered slot from the slab.
+    fn take(&mut self, key: usize) -> Expired<T> {
+        let slot = self.slab.remove(key).expect("invalid key");
+        self.len -= 1;
+        Expired {
+            data: slot.value,
+            deadline: slot.deadline,
+            key: Key { index: key },
+        }
+    }
+
+    /// Arms the driver timer for `tick`.
+    fn arm(&mut self, tick: u64) {
+        let time_source = self.handle.time_source();
+        let deadline = time_source.start_time + time_source.tick_to_duration(tick);
+        self.delay.as_mut().reset(deadline);
+        self.scheduled = Some(tick);
+    }
+}
+
+impl<T> Default for DelayQueue<T> {
+    fn default() -> DelayQueue<T> {
+        DelayQueue::new()
+    }
+}
+
+impl<T> fmt::Debug for DelayQueue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DelayQueue")
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+/// Future returned by [`DelayQueue::expired`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+#[derive(Debug)]
+pub struct ExpiredFuture<'a, T> {
+    queue: &'a mut DelayQueue<T>,
+}
+
+impl<T> Future for ExpiredFuture<'_, T> {
+    type Output = Option<Expired<T>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        self.queue.poll_expired(cx)
+    }
+}
+
+impl<T> Stream for DelayQueue<T> {
+    type Item = Expired<T>;
+
+    type NextFuture<'a>
+        = ExpiredFuture<'a, T>
+    where
+        T: 'a;
+
+    fn next(&mut self) -> Self::NextFuture<'_> {
+        self.expired()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.expired.len(), Some(self.len))
+    }
+}
+
+// ===== impl Expired =====
+
+impl<T> Expired<T> {
+    /// Returns a reference to the inner value.
+    pub fn get_ref(&self) -> &T {
+        &self.data
+    }
+
+    /// Returns a mutable reference to the inner value.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.data
+    }
+
+    /// Consumes `self` and returns the inner value.
+    pub fn into_inner(self) -> T {
+        self.data
+    }
+
+    /// Returns the deadline that the expiration was set to.
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    /// Returns the key that the expiration is indexed by.
+    pub fn key(&self) -> Key {
+        self.key
+    }
+}
+
+fn deadline_after(timeout: Duration) -> Instant {
+    Instant::now()
+        .checked_add(timeout)
+        .unwrap_or_else(Instant::far_future)
+}
+
+/// Returns the key of the slot that owns the timer behind `item`.
+///
+/// SAFETY: `item` must point to the `timer` field of a live `Slot<T>`.
+unsafe fn slot_key<T>(item: &TimerHandle) -> usize {
+    let timer = <TimerShared as Link>::as_raw(item);
+    unsafe { (*timer.cast::<Slot<T>>().as_ptr()).key }
+}
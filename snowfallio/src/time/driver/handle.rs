@@ -21,6 +21,11 @@ impl Handle {
         &self.time_source
     }
 
+    /// Returns the clock of the driver
+    pub(crate) fn clock(&self) -> &crate::time::Clock {
+        &self.time_source.clock
+    }
+
     /// Access the driver's inner structure
     pub(super) fn get(&self) -> &super::Inner {
         &self.inner
@@ -16,6 +16,8 @@ mod wheel;
 
 pub(super) mod sleep;
 
+pub(super) mod delay_queue;
+
 use std::{cell::RefCell, convert::TryInto, fmt, io, num::NonZeroU64, ptr::NonNull, rc::Rc};
 
 use crate::{
@@ -101,7 +103,7 @@ pub(self) struct ClockTime {
 impl ClockTime {
     pub(self) fn new(clock: Clock) -> Self {
         Self {
-            start_time: clock.now(),
+            start_time: clock.start_time(),
             clock,
         }
     }
@@ -204,6 +206,18 @@ where
                         duration = std::cmp::min(limit, duration);
                     }
 
+                    #[cfg(feature = "test-util")]
+                    if self.time_source.clock.is_paused() {
+                        // Jump to the next timer instead of sleeping, unless
+                        // checking for io made some task ready.
+                        self.park.park_timeout(Duration::from_secs(0))?;
+                        if is_idle() {
+                            self.time_source.clock.advance(duration);
+                        }
+                        self.handle.process();
+                        return Ok(());
+                    }
+
                     self.park.park_timeout(duration)?;
                 } else {
                     self.park.park_timeout(Duration::from_secs(0))?;
@@ -225,9 +239,19 @@ where
     }
 }
 
+/// Returns true if no task of the current runtime is ready to run.
+#[cfg(feature = "test-util")]
+fn is_idle() -> bool {
+    let no_task = crate::runtime::CURRENT.try_with(|maybe_ctx| match maybe_ctx {
+        Some(ctx) => ctx.tasks.is_empty(),
+        None => true,
+    });
+    no_task && !crate::task::waker_fn::poll_pending()
+}
+
 impl Handle {
     /// Runs timer related logic, and returns the next wakeup time
-    pub(self) fn process(&self) {
+    pub(crate) fn process(&self) {
         let now = self.time_source().now();
 
         self.process_at_time(now)
@@ -327,7 +351,11 @@ where
     }
 
     fn submit(&self) -> io::Result<()> {
-        self.park.submit()
+        self.park.submit()?;
+        // Fire the elapsed timers too, so a runtime that keeps submitting
+        // without parking does not delay them.
+        self.handle.process();
+        Ok(())
     }
 
     fn park(&self) -> io::Result<()> {
@@ -260,7 +260,14 @@ impl Sleep {
 
     fn poll_elapsed(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Result<(), Error>> {
         let me = self.project();
-        me.entry.poll_elapsed(cx)
+
+        // Keep track of task budget
+        let coop = ready!(crate::task::coop::poll_proceed(cx));
+
+        me.entry.poll_elapsed(cx).map(move |r| {
+            coop.made_progress();
+            r
+        })
     }
 }
 
@@ -44,7 +44,7 @@ impl Instant {
     /// let now = Instant::now();
     /// ```
     pub fn now() -> Instant {
-        variant::now()
+        crate::time::clock::now()
     }
 
     /// Create a `snowfallio::time::Instant` from a `std::time::Instant`.
@@ -209,11 +209,3 @@ impl fmt::Debug for Instant {
         self.std.fmt(fmt)
     }
 }
-
-mod variant {
-    use super::Instant;
-
-    pub(super) fn now() -> Instant {
-        Instant::from_std(std::time::Instant::now())
-    }
-}
@@ -8,16 +8,30 @@
 //! * [`Interval`] is a stream yielding a value at a fixed period. It is initialized with a
 //!   [`Duration`] and repeatedly yields each time the duration elapses.
 //!
+//! * [`ClockSleep`] is a future that completes at a wall-clock [`SystemTime`] or after a duration
+//!   on the boot time clock, which keeps counting across suspend.
+//!
+//! * [`DelayQueue`] is a stream of values, each yielded once its own deadline is reached. Deadlines
+//!   can be reset or removed by key without allocating a timer per value.
+//!
+//! * [`RateLimiter`] is a token bucket with burst, whose waiting tasks are served in FIFO order.
+//!
 //! * [`Timeout`]: Wraps a future or stream, setting an upper bound to the amount of time it is
 //!   allowed to execute. If the future or stream does not complete in time, then it is canceled and
 //!   an error is returned.
 //!
+//! * [`TimeoutCancelable`]: Bounds a cancelable io operation. On expiry the kernel operation is
+//!   canceled through a [`Canceller`](crate::io::Canceller) and its result, buffer included, is
+//!   still returned.
+//!
 //! These types are sufficient for handling a large number of scenarios
 //! involving time.
 //!
 //! These types must be used from within the context of the
 //! [`Runtime`](crate::runtime::Runtime).
 //!
+//! [`SystemTime`]: std::time::SystemTime
+//!
 //! # Examples
 //!
 //! Wait 100ms and print "100 ms have elapsed"
@@ -88,27 +102,39 @@
 
 mod clock;
 pub(crate) use self::clock::Clock;
+#[cfg(feature = "test-util")]
+pub use clock::{advance, pause, resume};
 
 pub(crate) mod driver;
 
 #[doc(inline)]
 pub use driver::{
+    delay_queue::{DelayQueue, Expired, ExpiredFuture, Key},
     sleep::{sleep, sleep_until, Sleep},
     TimeDriver,
 };
 
 pub mod error;
 
+mod clock_sleep;
+pub(crate) use clock_sleep::sleep_monotonic;
+pub use clock_sleep::{sleep_boottime, sleep_until_system, ClockSleep};
+
 mod instant;
 pub use self::instant::Instant;
 
 mod interval;
 pub use interval::{interval, interval_at, Interval, MissedTickBehavior};
 
+mod rate_limiter;
+pub use rate_limiter::RateLimiter;
+
 mod timeout;
 // Re-export for convenience
 #[doc(no_inline)]
 pub use std::time::Duration;
 
 #[doc(inline)]
-pub use timeout::{timeout, timeout_at, Timeout};
+pub use timeout::{
+    timeout, timeout_at, timeout_at_cancelable, timeout_cancelable, Timeout, TimeoutCancelable,
+};
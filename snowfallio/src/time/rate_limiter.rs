@@ -0,0 +1,180 @@
+//! A token bucket rate limiter.
+//!
+//! See [`RateLimiter`] documentation for more details.
+
+use std::{cell::RefCell, fmt};
+
+use crate::time::{sleep, Duration, Instant};
+
+/// A token bucket rate limiter for the tasks of a thread.
+///
+/// The bucket holds up to `burst` tokens and refills at `rate` tokens per
+/// second. [`acquire`] takes tokens out of the bucket, waiting on a [`Sleep`]
+/// until enough of them have been refilled.
+///
+/// Waiting tasks are served in FIFO order: an `acquire` reserves its tokens
+/// right away, even if the bucket runs into debt, so a later `acquire` has to
+/// wait for the debt of the earlier ones to be repaid first. A task asking for
+/// many tokens is not starved by tasks asking for few. If an `acquire` is
+/// dropped before completion, its tokens are given back.
+///
+/// Share it with an `Rc` to throttle several tasks or connections together,
+/// e.g. all the requests sent to a peer. See [`Throttled`] to cap the
+/// throughput of an io.
+///
+/// # Examples
+///
+/// ```
+/// use snowfallio::time::RateLimiter;
+///
+/// #[snowfallio::main(timer_enabled = true)]
+/// async fn main() {
+///     // 100 requests per second, at most 10 at once.
+///     let limiter = RateLimiter::new(100, 10);
+///     for i in 0..20 {
+///         limiter.acquire(1).await;
+///         println!("request {i}");
+///     }
+/// }
+/// ```
+///
+/// [`acquire`]: RateLimiter::acquire
+/// [`Sleep`]: struct@crate::time::Sleep
+/// [`Throttled`]: crate::io::Throttled
+pub struct RateLimiter {
+    state: RefCell<State>,
+}
+
+struct State {
+    /// Tokens added per second.
+    rate: f64,
+    /// Capacity of the bucket.
+    burst: f64,
+    /// Tokens in the bucket, negative when acquires are waiting.
+    tokens: f64,
+    /// Last time the bucket was refilled.
+    last: Instant,
+}
+
+impl RateLimiter {
+    /// Creates a rate limiter refilling `rate` tokens per second, holding up
+    /// to `burst` tokens. The bucket starts full.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rate` or `burst` is zero.
+    pub fn new(rate: u64, burst: u64) -> RateLimiter {
+        assert!(rate > 0, "`rate` must be non-zero.");
+        assert!(burst > 0, "`burst` must be non-zero.");
+        RateLimiter {
+            state: RefCell::new(State {
+                rate: rate as f64,
+                burst: burst as f64,
+                tokens: burst as f64,
+                last: Instant::now(),
+            }),
+        }
+    }
+
+    /// Returns the number of tokens refilled per second.
+    pub fn rate(&self) -> u64 {
+        self.state.borrow().rate as u64
+    }
+
+    /// Returns the capacity of the bucket.
+    pub fn burst(&self) -> u64 {
+        self.state.borrow().burst as u64
+    }
+
+    /// Returns the number of tokens that can be taken without waiting.
+    pub fn available(&self) -> u64 {
+        let mut state = self.state.borrow_mut();
+        state.refill();
+        state.tokens.max(0.0) as u64
+    }
+
+    /// Takes `n` tokens if they are available right away.
+    ///
+    /// Returns `false` without taking any token otherwise, including when
+    /// other tasks are waiting in [`acquire`](RateLimiter::acquire).
+    pub fn try_acquire(&self, n: u64) -> bool {
+        let mut state = self.state.borrow_mut();
+        state.refill();
+        if state.tokens < n as f64 {
+            return false;
+        }
+        state.tokens -= n as f64;
+        true
+    }
+
+    /// Waits until `n` tokens are available and takes them.
+    ///
+    /// `n` may exceed the burst, the call then waits for the missing tokens to
+    /// be refilled.
+    pub async fn acquire(&self, n: u64) {
+        let wait = {
+            let mut state = self.state.borrow_mut();
+            state.refill();
+            state.tokens -= n as f64;
+            if state.tokens >= 0.0 {
+                return;
+            }
+            Duration::from_secs_f64(-state.tokens / state.rate)
+        };
+
+        let mut reservation = Reservation {
+            limiter: self,
+            tokens: n,
+        };
+        sleep(wait).await;
+        reservation.tokens = 0;
+    }
+
+    /// Takes `n` tokens without waiting, possibly running into debt which the
+    /// next acquires have to wait off.
+    pub(crate) fn consume(&self, n: u64) {
+        let mut state = self.state.borrow_mut();
+        state.refill();
+        state.tokens -= n as f64;
+    }
+
+    /// Puts back `n` tokens taken but not used.
+    pub(crate) fn release(&self, n: u64) {
+        let mut state = self.state.borrow_mut();
+        state.tokens = (state.tokens + n as f64).min(state.burst);
+    }
+}
+
+impl State {
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.saturating_duration_since(self.last);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.rate).min(self.burst);
+        self.last = now;
+    }
+}
+
+/// Gives back the tokens of a canceled acquire.
+struct Reservation<'a> {
+    limiter: &'a RateLimiter,
+    tokens: u64,
+}
+
+impl Drop for Reservation<'_> {
+    fn drop(&mut self) {
+        if self.tokens > 0 {
+            self.limiter.release(self.tokens);
+        }
+    }
+}
+
+impl fmt::Debug for RateLimiter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.borrow();
+        f.debug_struct("RateLimiter")
+            .field("rate", &state.rate)
+            .field("burst", &state.burst)
+            .field("tokens", &state.tokens)
+            .finish()
+    }
+}
@@ -12,7 +12,10 @@ use std::{
 
 use pin_project_lite::pin_project;
 
-use crate::time::{error::Elapsed, sleep_until, Duration, Instant, Sleep};
+use crate::{
+    io::{CancelHandle, Canceller},
+    time::{error::Elapsed, sleep_until, Duration, Instant, Sleep},
+};
 
 /// Require a `Future` to complete before the specified duration has elapsed.
 ///
@@ -63,6 +66,67 @@ where
     }
 }
 
+/// Require a cancelable io operation to complete before the specified duration
+/// has elapsed.
+///
+/// `f` is called with a [`CancelHandle`] which must be passed to the operation,
+/// e.g. [`cancelable_read`]. Unlike [`timeout`], the operation is not dropped
+/// when the deadline is reached. Instead, the kernel operation is canceled
+/// through a [`Canceller`] and the future keeps waiting for it to return, so
+/// the buffer handed to the operation is always given back.
+///
+/// The output is the output of the operation. When canceled, it completes with
+/// an `ECANCELED` error unless the kernel finished it first, in which case the
+/// transferred data is not lost.
+///
+/// # Examples
+///
+/// ```no_run
+/// use snowfallio::{
+///     io::CancelableAsyncReadRent,
+///     net::TcpStream,
+///     time::{timeout_cancelable, Duration},
+/// };
+///
+/// # async fn dox(mut stream: TcpStream) {
+/// let buf = vec![0; 1024];
+/// let (res, buf) = timeout_cancelable(Duration::from_secs(1), |handle| {
+///     stream.cancelable_read(buf, handle)
+/// })
+/// .await;
+///
+/// if matches!(&res, Err(e) if e.raw_os_error() == Some(libc::ECANCELED)) {
+///     println!("read timed out, buffer of {} bytes is back", buf.capacity());
+/// }
+/// # }
+/// ```
+///
+/// [`cancelable_read`]: crate::io::CancelableAsyncReadRent::cancelable_read
+pub fn timeout_cancelable<F, T>(duration: Duration, f: F) -> TimeoutCancelable<T>
+where
+    F: FnOnce(CancelHandle) -> T,
+    T: Future,
+{
+    let deadline = Instant::now().checked_add(duration);
+    let delay = match deadline {
+        Some(deadline) => Sleep::new_timeout(deadline),
+        None => Sleep::far_future(),
+    };
+    TimeoutCancelable::new_with_delay(f, delay)
+}
+
+/// Require a cancelable io operation to complete before the specified instant
+/// in time.
+///
+/// See [`timeout_cancelable`] for details.
+pub fn timeout_at_cancelable<F, T>(deadline: Instant, f: F) -> TimeoutCancelable<T>
+where
+    F: FnOnce(CancelHandle) -> T,
+    T: Future,
+{
+    TimeoutCancelable::new_with_delay(f, sleep_until(deadline))
+}
+
 pin_project! {
     /// Future returned by [`timeout`](timeout) and [`timeout_at`](timeout_at).
     #[must_use = "futures do nothing unless you `.await` or poll them"]
@@ -117,3 +181,72 @@ where
         }
     }
 }
+
+pin_project! {
+    /// Future returned by [`timeout_cancelable`](timeout_cancelable) and
+    /// [`timeout_at_cancelable`](timeout_at_cancelable).
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct TimeoutCancelable<T> {
+        #[pin]
+        value: T,
+        #[pin]
+        delay: Sleep,
+        // Taken once the deadline is reached and the operation canceled.
+        canceller: Option<Canceller>,
+    }
+}
+
+impl<T> TimeoutCancelable<T> {
+    fn new_with_delay<F>(f: F, delay: Sleep) -> TimeoutCancelable<T>
+    where
+        F: FnOnce(CancelHandle) -> T,
+    {
+        let canceller = Canceller::new();
+        let value = f(canceller.handle());
+        TimeoutCancelable {
+            value,
+            delay,
+            canceller: Some(canceller),
+        }
+    }
+
+    /// Returns `true` if the deadline was reached and the operation has been
+    /// canceled.
+    pub fn is_elapsed(&self) -> bool {
+        self.canceller.is_none()
+    }
+}
+
+impl<T> Future for TimeoutCancelable<T>
+where
+    T: Future,
+{
+    type Output = T::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let mut me = self.project();
+
+        // First, try polling the future
+        if let Poll::Ready(v) = me.value.as_mut().poll(cx) {
+            return Poll::Ready(v);
+        }
+
+        // Once canceled, only wait for the operation to hand back its result.
+        if me.canceller.is_some() && me.delay.poll(cx).is_ready() {
+            me.canceller.take().unwrap().cancel();
+            // The canceller only interrupts the operations in flight, poll
+            // again so that one not submitted yet sees the cancellation.
+            return me.value.poll(cx);
+        }
+        Poll::Pending
+    }
+}
+
+impl<T> std::fmt::Debug for TimeoutCancelable<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TimeoutCancelable")
+            .field("deadline", &self.delay.deadline())
+            .field("elapsed", &self.is_elapsed())
+            .finish()
+    }
+}
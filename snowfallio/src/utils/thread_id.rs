@@ -1,3 +1,4 @@
+#[cfg(not(feature = "sync"))]
 use std::sync::{
     atomic::{AtomicUsize, Ordering::Relaxed},
     LazyLock,
@@ -6,15 +7,24 @@ use std::sync::{
 // thread id begins from 16.
 // 0 is default thread
 // 1-15 are unused
+#[cfg(not(feature = "sync"))]
 static ID_GEN: LazyLock<AtomicUsize> = LazyLock::new(|| AtomicUsize::new(16));
 
 pub(crate) const DEFAULT_THREAD_ID: usize = 0;
 
 /// Used to generate thread id.
+#[cfg(not(feature = "sync"))]
 pub(crate) fn gen_id() -> usize {
     ID_GEN.fetch_add(1, Relaxed)
 }
 
+/// Used to generate thread id. The id reserves a slot of the thread registry,
+/// it must be released when the runtime is dropped or can not be built.
+#[cfg(feature = "sync")]
+pub(crate) fn gen_id() -> usize {
+    crate::driver::thread::reserve_id()
+}
+
 pub(crate) fn get_current_thread_id() -> usize {
     crate::runtime::CURRENT.with(|ctx| ctx.thread_id)
 }
@@ -0,0 +1,159 @@
+#![cfg(feature = "sync")]
+
+use std::{
+    sync::{Arc, Barrier},
+    time::Duration,
+};
+
+use snowfallio::{
+    blocking::{BlockingPool, JoinError},
+    IoUringDriver, RuntimeBuilder,
+};
+
+#[test]
+fn named_threads() {
+    let pool = BlockingPool::builder().thread_name("pool-test").build();
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .with_blocking_pool(pool.clone())
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let name =
+            snowfallio::spawn_blocking(|| std::thread::current().name().map(ToOwned::to_owned))
+                .await
+                .unwrap();
+        assert_eq!(name.as_deref(), Some("pool-test"));
+    });
+    let metrics = pool.metrics();
+    assert_eq!(metrics.threads, 1);
+    assert_eq!(metrics.completed_tasks, 1);
+}
+
+#[test]
+fn bounded_queue_rejects() {
+    let pool = BlockingPool::builder()
+        .max_threads(1)
+        .queue_capacity(1)
+        .build();
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .with_blocking_pool(pool.clone())
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let barrier = Arc::new(Barrier::new(2));
+        let b = barrier.clone();
+        let (started_tx, started_rx) = std::sync::mpsc::channel();
+        // Occupies the only thread.
+        let running = snowfallio::spawn_blocking(move || {
+            started_tx.send(()).unwrap();
+            b.wait();
+        });
+        started_rx.recv().unwrap();
+        // Waits in the queue.
+        let queued = snowfallio::spawn_blocking(|| 2);
+        // The queue is full.
+        let rejected = snowfallio::spawn_blocking(|| 3);
+        assert!(matches!(rejected.await, Err(JoinError::Rejected)));
+
+        let metrics = pool.metrics();
+        assert_eq!(metrics.queue_depth, 1);
+        assert_eq!(metrics.rejected_tasks, 1);
+
+        barrier.wait();
+        running.await.unwrap();
+        assert_eq!(queued.await.unwrap(), 2);
+    });
+}
+
+#[test]
+fn idle_threads_exit() {
+    let pool = BlockingPool::builder()
+        .min_threads(1)
+        .max_threads(4)
+        .keep_alive(Duration::from_millis(20))
+        .build();
+    assert_eq!(pool.metrics().threads, 1);
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .with_blocking_pool(pool.clone())
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let barrier = Arc::new(Barrier::new(4));
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let barrier = barrier.clone();
+                snowfallio::spawn_blocking(move || {
+                    barrier.wait();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.await.unwrap();
+        }
+    });
+    assert_eq!(pool.metrics().threads, 4);
+    std::thread::sleep(Duration::from_millis(200));
+    assert_eq!(pool.metrics().threads, 1);
+}
+
+#[test]
+fn shared_by_runtimes() {
+    let pool = BlockingPool::new(1, 1);
+    let threads: Vec<_> = (0..2)
+        .map(|_| {
+            let pool = pool.clone();
+            std::thread::spawn(move || {
+                let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+                    .with_blocking_pool(pool)
+                    .build()
+                    .unwrap();
+                rt.block_on(async {
+                    snowfallio::spawn_blocking(|| std::thread::current().id())
+                        .await
+                        .unwrap()
+                })
+            })
+        })
+        .collect();
+    let ids: Vec<_> = threads.into_iter().map(|t| t.join().unwrap()).collect();
+    // Both runtimes ran their task on the single pool thread.
+    assert_eq!(ids[0], ids[1]);
+}
+
+#[snowfallio::test(threads = 2, blocking_threads = 1)]
+async fn macro_blocking_threads() {
+    let n = snowfallio::spawn_blocking(|| 21 * 2).await.unwrap();
+    assert_eq!(n, 42);
+}
+
+#[snowfallio::test(threads = 2)]
+async fn macro_default_shared_pool() {
+    let n = snowfallio::spawn_blocking(|| 21 * 2).await.unwrap();
+    assert_eq!(n, 42);
+}
+
+#[test]
+fn panicking_task_replaces_thread() {
+    let pool = BlockingPool::builder().max_threads(1).build();
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .with_blocking_pool(pool.clone())
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let (started_tx, started_rx) = std::sync::mpsc::channel();
+        let (panic_tx, panic_rx) = std::sync::mpsc::channel::<()>();
+        let panicking = snowfallio::spawn_blocking(move || {
+            started_tx.send(()).unwrap();
+            panic_rx.recv().unwrap();
+            panic!("blocking task panicked");
+        });
+        started_rx.recv().unwrap();
+        // Queued behind the panicking task on the only thread.
+        let queued = snowfallio::spawn_blocking(|| 2);
+        drop(panicking);
+        panic_tx.send(()).unwrap();
+        assert_eq!(queued.await.unwrap(), 2);
+    });
+    // The panicking thread was replaced, not added to.
+    assert_eq!(pool.metrics().threads, 1);
+}
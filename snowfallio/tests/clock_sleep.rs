@@ -0,0 +1,38 @@
+use std::time::{Duration, Instant, SystemTime};
+
+use snowfallio::time::{sleep_boottime, sleep_until_system};
+
+#[snowfallio::test]
+async fn system_deadline() {
+    let start = Instant::now();
+    sleep_until_system(SystemTime::now() + Duration::from_millis(30))
+        .await
+        .unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(25));
+}
+
+#[snowfallio::test]
+async fn system_deadline_in_the_past() {
+    let start = Instant::now();
+    sleep_until_system(SystemTime::now() - Duration::from_secs(60))
+        .await
+        .unwrap();
+    sleep_until_system(SystemTime::UNIX_EPOCH).await.unwrap();
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[snowfallio::test]
+async fn boottime() {
+    let start = Instant::now();
+    sleep_boottime(Duration::from_millis(30)).await.unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(25));
+}
+
+#[snowfallio::test(timer_enabled = true)]
+async fn drop_cancels() {
+    let sleep = sleep_boottime(Duration::from_secs(3600));
+    let res = snowfallio::time::timeout(Duration::from_millis(10), sleep).await;
+    assert!(res.is_err());
+    // The canceled timeout must not keep the runtime waiting.
+    sleep_boottime(Duration::from_millis(1)).await.unwrap();
+}
@@ -0,0 +1,71 @@
+use std::{cell::Cell, rc::Rc, time::Duration};
+
+#[snowfallio::test]
+async fn consume_budget_yields() {
+    let stop = Rc::new(Cell::new(false));
+    let stop_in_task = stop.clone();
+
+    // Without a budget this task would never give the thread back.
+    let spinner = snowfallio::spawn(async move {
+        let mut iterations = 0u64;
+        while !stop_in_task.get() {
+            snowfallio::task::consume_budget().await;
+            iterations += 1;
+        }
+        iterations
+    });
+    snowfallio::spawn(async move { stop.set(true) });
+
+    assert!(spinner.await > 0);
+}
+
+#[snowfallio::test]
+async fn main_future_yields() {
+    let ran = Rc::new(Cell::new(false));
+    let ran_in_task = ran.clone();
+    snowfallio::spawn(async move { ran_in_task.set(true) });
+
+    while !ran.get() {
+        snowfallio::task::consume_budget().await;
+    }
+}
+
+#[snowfallio::test]
+async fn yield_now_goes_to_back() {
+    let order = Rc::new(Cell::new(Vec::new()));
+    let push = |order: &Rc<Cell<Vec<u8>>>, v| {
+        let mut o = order.take();
+        o.push(v);
+        order.set(o);
+    };
+
+    let o1 = order.clone();
+    let a = snowfallio::spawn(async move {
+        push(&o1, 1);
+        snowfallio::task::yield_now().await;
+        push(&o1, 3);
+    });
+    let o2 = order.clone();
+    let b = snowfallio::spawn(async move {
+        push(&o2, 2);
+    });
+    a.await;
+    b.await;
+    assert_eq!(order.take(), vec![1, 2, 3]);
+}
+
+#[snowfallio::test(timer_enabled = true)]
+async fn elapsed_sleep_consumes_budget() {
+    let stop = Rc::new(Cell::new(false));
+    let stop_in_task = stop.clone();
+
+    let spinner = snowfallio::spawn(async move {
+        let sleep = snowfallio::time::sleep(Duration::ZERO);
+        snowfallio::pin!(sleep);
+        while !stop_in_task.get() {
+            sleep.as_mut().await;
+        }
+    });
+    snowfallio::spawn(async move { stop.set(true) });
+    spinner.await;
+}
@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use snowfallio::{
+    io::stream::Stream,
+    time::{DelayQueue, Instant},
+};
+
+#[snowfallio::test(timer_enabled = true)]
+async fn expires_in_deadline_order() {
+    let mut queue = DelayQueue::new();
+    queue.insert("c", Duration::from_millis(30));
+    queue.insert("a", Duration::from_millis(10));
+    queue.insert("b", Duration::from_millis(20));
+    assert_eq!(queue.len(), 3);
+
+    let start = Instant::now();
+    let mut order = vec![];
+    while let Some(expired) = queue.next().await {
+        assert!(expired.deadline() <= Instant::now());
+        order.push(expired.into_inner());
+    }
+    assert_eq!(order, ["a", "b", "c"]);
+    assert!(start.elapsed() >= Duration::from_millis(29));
+    assert!(queue.is_empty());
+}
+
+#[snowfallio::test(timer_enabled = true)]
+async fn reset_and_remove() {
+    let mut queue = DelayQueue::new();
+    let a = queue.insert(1, Duration::from_millis(10));
+    let b = queue.insert(2, Duration::from_millis(20));
+    let c = queue.insert(3, Duration::from_millis(30));
+
+    queue.reset(&a, Duration::from_millis(40));
+    let removed = queue.remove(&b);
+    assert_eq!(removed.key(), b);
+    assert_eq!(removed.into_inner(), 2);
+
+    assert_eq!(queue.next().await.unwrap().key(), c);
+    let last = queue.next().await.unwrap();
+    assert_eq!(last.key(), a);
+    assert_eq!(last.into_inner(), 1);
+    assert!(queue.next().await.is_none());
+}
+
+#[snowfallio::test(timer_enabled = true)]
+async fn already_elapsed() {
+    let mut queue = DelayQueue::new();
+    queue.insert(1, Duration::from_secs(60));
+    let key = queue.insert_at(2, Instant::now());
+    assert_eq!(queue.next().await.unwrap().key(), key);
+
+    // Resetting an expired but not yet yielded entry moves it back.
+    let key = queue.insert(3, Duration::ZERO);
+    queue.reset(&key, Duration::from_secs(60));
+    let res = snowfallio::time::timeout(Duration::from_millis(20), queue.next()).await;
+    assert!(res.is_err());
+    assert_eq!(queue.len(), 2);
+
+    queue.clear();
+    assert!(queue.is_empty());
+    assert!(queue.next().await.is_none());
+}
+
+#[snowfallio::test(timer_enabled = true)]
+async fn many_entries() {
+    let mut queue = DelayQueue::new();
+    let keys = (0..10_000u64)
+        .map(|i| queue.insert(i, Duration::from_millis(i % 50)))
+        .collect::<Vec<_>>();
+    for key in keys.iter().step_by(2) {
+        queue.remove(key);
+    }
+
+    let mut count = 0;
+    while let Some(expired) = queue.next().await {
+        assert_eq!(expired.get_ref() % 2, 1);
+        assert!(expired.deadline() <= Instant::now());
+        count += 1;
+    }
+    assert_eq!(count, 5_000);
+}
@@ -0,0 +1,336 @@
+use std::{
+    collections::HashMap,
+    io::{ErrorKind, Read, Write},
+    net::{IpAddr, SocketAddr},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use snowfallio::net::{
+    dns::{set_resolver_config, ResolverConfig},
+    lookup_host, TcpListener, TcpStream,
+};
+
+/// A name server answering from `records`, with the given ttl.
+fn stub_server(records: &[(&str, &str)], ttl: u32) -> (SocketAddr, Arc<AtomicUsize>) {
+    failing_stub_server(records, ttl, &[])
+}
+
+/// Like `stub_server`, but replies SERVFAIL to the queries of `failing` types.
+fn failing_stub_server(
+    records: &[(&str, &str)],
+    ttl: u32,
+    failing: &'static [u16],
+) -> (SocketAddr, Arc<AtomicUsize>) {
+    let mut names = HashMap::<String, Vec<IpAddr>>::new();
+    for (name, ip) in records {
+        names
+            .entry(name.to_string())
+            .or_default()
+            .push(ip.parse().unwrap());
+    }
+    let socket = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+    let addr = socket.local_addr().unwrap();
+    let queries = Arc::new(AtomicUsize::new(0));
+    let counter = queries.clone();
+    std::thread::spawn(move || {
+        let mut buf = [0; 512];
+        while let Ok((n, peer)) = socket.recv_from(&mut buf) {
+            counter.fetch_add(1, Ordering::SeqCst);
+            let response = answer(&buf[..n], &names, ttl, failing);
+            socket.send_to(&response, peer).unwrap();
+        }
+    });
+    (addr, queries)
+}
+
+/// A name server whose UDP replies are truncated, answering in full over TCP
+/// on the same port.
+fn truncating_stub_server(records: &[(&str, &str)], ttl: u32) -> SocketAddr {
+    let mut names = HashMap::<String, Vec<IpAddr>>::new();
+    for (name, ip) in records {
+        names
+            .entry(name.to_string())
+            .or_default()
+            .push(ip.parse().unwrap());
+    }
+    let names = Arc::new(names);
+    let socket = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+    let addr = socket.local_addr().unwrap();
+    let listener = std::net::TcpListener::bind(addr).unwrap();
+
+    let udp_names = names.clone();
+    std::thread::spawn(move || {
+        let mut buf = [0; 512];
+        while let Ok((n, peer)) = socket.recv_from(&mut buf) {
+            // Keep the header and the question only, with the TC bit set.
+            let mut response = answer(&buf[..n], &udp_names, ttl, &[]);
+            response.truncate(n);
+            response[2] |= 0x02;
+            response[6..8].copy_from_slice(&[0, 0]);
+            socket.send_to(&response, peer).unwrap();
+        }
+    });
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut len = [0; 2];
+            stream.read_exact(&mut len).unwrap();
+            let mut query = vec![0; u16::from_be_bytes(len) as usize];
+            stream.read_exact(&mut query).unwrap();
+            let response = answer(&query, &names, ttl, &[]);
+            stream
+                .write_all(&(response.len() as u16).to_be_bytes())
+                .unwrap();
+            stream.write_all(&response).unwrap();
+        }
+    });
+    addr
+}
+
+fn answer(
+    query: &[u8],
+    names: &HashMap<String, Vec<IpAddr>>,
+    ttl: u32,
+    failing: &[u16],
+) -> Vec<u8> {
+    let mut labels = vec![];
+    let mut pos = 12;
+    while query[pos] != 0 {
+        let len = query[pos] as usize;
+        labels.push(std::str::from_utf8(&query[pos + 1..pos + 1 + len]).unwrap());
+        pos += len + 1;
+    }
+    let question_end = pos + 5;
+    let qtype = u16::from_be_bytes([query[pos + 1], query[pos + 2]]);
+
+    let addrs = names.get(&labels.join("."));
+    let servfail = failing.contains(&qtype);
+    let records = addrs
+        .into_iter()
+        .flatten()
+        .filter(|ip| matches!((ip, qtype), (IpAddr::V4(_), 1) | (IpAddr::V6(_), 28)))
+        .filter(|_| !servfail)
+        .collect::<Vec<_>>();
+    let rcode = match (servfail, addrs) {
+        (true, _) => 2,
+        (false, None) => 3,
+        (false, Some(_)) => 0,
+    };
+
+    let mut response = query[..2].to_vec();
+    response.extend_from_slice(&[0x81, 0x80 | rcode, 0, 1, 0, records.len() as u8, 0, 0, 0, 0]);
+    response.extend_from_slice(&query[12..question_end]);
+    for ip in records {
+        response.extend_from_slice(&[0xc0, 12]);
+        response.extend_from_slice(&qtype.to_be_bytes());
+        response.extend_from_slice(&[0, 1]);
+        response.extend_from_slice(&ttl.to_be_bytes());
+        match ip {
+            IpAddr::V4(ip) => {
+                response.extend_from_slice(&[0, 4]);
+                response.extend_from_slice(&ip.octets());
+            }
+            IpAddr::V6(ip) => {
+                response.extend_from_slice(&[0, 16]);
+                response.extend_from_slice(&ip.octets());
+            }
+        }
+    }
+    response
+}
+
+fn use_server(server: SocketAddr) -> ResolverConfig {
+    let config = ResolverConfig::default()
+        .nameservers(vec![server])
+        .timeout(Duration::from_secs(1))
+        .attempts(1)
+        .hosts_file(None);
+    set_resolver_config(config.clone());
+    config
+}
+
+#[snowfallio::test]
+async fn lookup_from_nameserver() {
+    let (server, _) = stub_server(
+        &[("example.test", "10.0.0.1"), ("example.test", "fd00::1")],
+        60,
+    );
+    use_server(server);
+
+    let addrs = lookup_host("example.test:80")
+        .await
+        .unwrap()
+        .collect::<Vec<_>>();
+    assert_eq!(
+        addrs,
+        [
+            "10.0.0.1:80".parse::<SocketAddr>().unwrap(),
+            "[fd00::1]:80".parse().unwrap()
+        ]
+    );
+
+    // Addresses are not resolved.
+    let addrs = lookup_host(("10.1.2.3", 1))
+        .await
+        .unwrap()
+        .collect::<Vec<_>>();
+    assert_eq!(addrs, ["10.1.2.3:1".parse::<SocketAddr>().unwrap()]);
+}
+
+#[snowfallio::test]
+async fn cache_follows_ttl() {
+    let (server, queries) = stub_server(
+        &[("cached.test", "10.0.0.2"), ("fresh.test", "10.0.0.3")],
+        60,
+    );
+    use_server(server);
+    lookup_host(("cached.test", 80))
+        .await
+        .unwrap()
+        .for_each(drop);
+    let sent = queries.load(Ordering::SeqCst);
+    lookup_host(("CACHED.test", 80))
+        .await
+        .unwrap()
+        .for_each(drop);
+    assert_eq!(queries.load(Ordering::SeqCst), sent);
+
+    let (server, queries) = stub_server(&[("fresh.test", "10.0.0.3")], 0);
+    use_server(server);
+    lookup_host(("fresh.test", 80))
+        .await
+        .unwrap()
+        .for_each(drop);
+    let sent = queries.load(Ordering::SeqCst);
+    lookup_host(("fresh.test", 80))
+        .await
+        .unwrap()
+        .for_each(drop);
+    assert!(queries.load(Ordering::SeqCst) > sent);
+}
+
+#[snowfallio::test]
+async fn hosts_file_and_search() {
+    let (server, _) = stub_server(
+        &[("pinned.test", "10.0.0.4"), ("short.corp.test", "10.0.0.5")],
+        60,
+    );
+    let mut hosts = tempfile::NamedTempFile::new().unwrap();
+    writeln!(hosts, "# comment\n10.9.9.9 other.test Pinned.test").unwrap();
+    let config = use_server(server)
+        .hosts_file(Some(hosts.path().to_owned()))
+        .search(vec!["corp.test".into()]);
+    set_resolver_config(config);
+
+    let addrs = lookup_host(("pinned.test", 80))
+        .await
+        .unwrap()
+        .collect::<Vec<_>>();
+    assert_eq!(addrs, ["10.9.9.9:80".parse::<SocketAddr>().unwrap()]);
+    let addrs = lookup_host(("short", 80))
+        .await
+        .unwrap()
+        .collect::<Vec<_>>();
+    assert_eq!(addrs, ["10.0.0.5:80".parse::<SocketAddr>().unwrap()]);
+}
+
+#[snowfallio::test]
+async fn unknown_name() {
+    let (server, _) = stub_server(&[], 60);
+    use_server(server);
+    let err = lookup_host(("missing.test", 80)).await.err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+}
+
+#[snowfallio::test]
+async fn failed_aaaa_keeps_a_answer() {
+    let (server, _) = failing_stub_server(
+        &[("partial.test", "10.0.0.6"), ("partial.test", "fd00::6")],
+        60,
+        &[28],
+    );
+    use_server(server);
+    let addrs = lookup_host(("partial.test", 80))
+        .await
+        .unwrap()
+        .collect::<Vec<_>>();
+    assert_eq!(addrs, ["10.0.0.6:80".parse::<SocketAddr>().unwrap()]);
+
+    // Nothing to fall back on when both queries fail.
+    let (server, _) = failing_stub_server(&[("broken.test", "10.0.0.7")], 60, &[1, 28]);
+    use_server(server);
+    assert!(lookup_host(("broken.test", 80)).await.is_err());
+}
+
+#[snowfallio::test]
+async fn truncated_answer_over_tcp() {
+    let server = truncating_stub_server(
+        &[
+            ("big.test", "10.0.0.1"),
+            ("big.test", "10.0.0.2"),
+            ("big.test", "fd00::1"),
+        ],
+        60,
+    );
+    use_server(server);
+
+    let addrs = lookup_host("big.test:80")
+        .await
+        .unwrap()
+        .collect::<Vec<_>>();
+    assert_eq!(
+        addrs,
+        [
+            "10.0.0.1:80".parse::<SocketAddr>().unwrap(),
+            "10.0.0.2:80".parse().unwrap(),
+            "[fd00::1]:80".parse().unwrap()
+        ]
+    );
+}
+
+#[snowfallio::test]
+async fn silent_nameserver_times_out() {
+    let silent = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+    let config = use_server(silent.local_addr().unwrap()).timeout(Duration::from_millis(50));
+    set_resolver_config(config);
+    let err = lookup_host(("slow.test", 80)).await.err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::TimedOut);
+}
+
+#[snowfallio::test]
+async fn connect_by_name() {
+    let (server, _) = stub_server(&[("svc.test", "127.0.0.1")], 60);
+    use_server(server);
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let (stream, accepted) = snowfallio::join!(
+        TcpStream::connect(format!("svc.test:{port}")),
+        listener.accept()
+    );
+    assert_eq!(stream.unwrap().peer_addr().unwrap().port(), port);
+    accepted.unwrap();
+}
+
+#[snowfallio::test]
+async fn connect_custom_std_addrs() {
+    struct Local(u16);
+
+    impl std::net::ToSocketAddrs for Local {
+        type Iter = std::option::IntoIter<SocketAddr>;
+
+        fn to_socket_addrs(&self) -> std::io::Result<Self::Iter> {
+            Ok(Some(SocketAddr::from(([127, 0, 0, 1], self.0))).into_iter())
+        }
+    }
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (stream, accepted) = snowfallio::join!(TcpStream::connect(Local(port)), listener.accept());
+    assert_eq!(stream.unwrap().peer_addr().unwrap().port(), port);
+    accepted.unwrap();
+}
@@ -0,0 +1,102 @@
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+use snowfallio::{IoUringDriver, RuntimeBuilder};
+
+#[test]
+fn spawn_outside_block_on() {
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new().build().unwrap();
+    let handle = rt.handle();
+    let log = Rc::new(RefCell::new(Vec::new()));
+
+    let l = log.clone();
+    let join = handle.spawn(async move {
+        l.borrow_mut().push(1);
+        2
+    });
+    // Nothing runs until the runtime is driven.
+    assert!(log.borrow().is_empty());
+
+    let n = rt.block_on(join);
+    assert_eq!(n, 2);
+    assert_eq!(*log.borrow(), [1]);
+}
+
+#[test]
+fn run_until_idle() {
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new().build().unwrap();
+    let handle = rt.handle();
+    let log = Rc::new(RefCell::new(Vec::new()));
+
+    for i in 0..3 {
+        let l = log.clone();
+        handle.spawn(async move {
+            l.borrow_mut().push(i);
+            // Tasks spawned by tasks run in the same call.
+            let l = l.clone();
+            snowfallio::spawn(async move {
+                l.borrow_mut().push(i + 10);
+            });
+        });
+    }
+    assert!(rt.run_until_idle() >= 6);
+    log.borrow_mut().sort_unstable();
+    assert_eq!(*log.borrow(), [0, 1, 2, 10, 11, 12]);
+    assert_eq!(rt.run_until_idle(), 0);
+}
+
+#[test]
+fn run_until_idle_returns_with_busy_task() {
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new().build().unwrap();
+    let handle = rt.handle();
+    let polls = Rc::new(RefCell::new(0));
+
+    let p = polls.clone();
+    handle.spawn(async move {
+        loop {
+            *p.borrow_mut() += 1;
+            snowfallio::task::yield_now().await;
+        }
+    });
+    // Each call runs a bounded pass even though the task is always ready.
+    assert!(rt.run_until_idle() > 0);
+    let first = *polls.borrow();
+    assert!(rt.run_until_idle() > 0);
+    assert!(*polls.borrow() > first);
+}
+
+#[test]
+fn turn_waits_for_timer() {
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .enable_timer()
+        .build()
+        .unwrap();
+    let handle = rt.handle();
+    let done = Rc::new(RefCell::new(false));
+
+    let d = done.clone();
+    handle.spawn(async move {
+        snowfallio::time::sleep(Duration::from_millis(20)).await;
+        *d.borrow_mut() = true;
+    });
+    // The first turn polls the task, which starts sleeping.
+    assert!(rt.turn(Some(Duration::ZERO)));
+    assert!(!*done.borrow());
+
+    let mut turns = 0;
+    while !*done.borrow() {
+        rt.turn(Some(Duration::from_millis(100)));
+        turns += 1;
+        assert!(turns < 10);
+    }
+    // Nothing left to do, the turn times out.
+    assert!(!rt.turn(Some(Duration::from_millis(1))));
+}
+
+#[test]
+#[should_panic(expected = "dropped")]
+fn spawn_after_drop() {
+    let rt = RuntimeBuilder::<IoUringDriver>::new().build().unwrap();
+    let handle = rt.handle();
+    drop(rt);
+    handle.spawn(async {});
+}
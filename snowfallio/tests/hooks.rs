@@ -0,0 +1,133 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering::SeqCst},
+    Arc,
+};
+
+use snowfallio::{IoUringDriver, RuntimeBuilder};
+
+#[derive(Default)]
+struct Counts {
+    start: AtomicUsize,
+    stop: AtomicUsize,
+    park: AtomicUsize,
+    unpark: AtomicUsize,
+}
+
+#[test]
+fn lifecycle_hooks() {
+    let counts = Arc::new(Counts::default());
+    let (c1, c2, c3, c4) = (
+        counts.clone(),
+        counts.clone(),
+        counts.clone(),
+        counts.clone(),
+    );
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .on_thread_start(move || {
+            c1.start.fetch_add(1, SeqCst);
+        })
+        .on_thread_stop(move || {
+            c2.stop.fetch_add(1, SeqCst);
+        })
+        .before_park(move || {
+            c3.park.fetch_add(1, SeqCst);
+        })
+        .after_unpark(move || {
+            let c = &c4;
+            // Every unpark follows a park.
+            assert_eq!(c.unpark.fetch_add(1, SeqCst) + 1, c.park.load(SeqCst));
+        })
+        .enable_timer()
+        .build()
+        .unwrap();
+
+    let c = counts.clone();
+    rt.block_on(async move {
+        assert_eq!(c.start.load(SeqCst), 1);
+        assert_eq!(c.stop.load(SeqCst), 0);
+        snowfallio::time::sleep(std::time::Duration::from_millis(10)).await;
+        assert!(c.park.load(SeqCst) >= 1);
+        assert_eq!(c.unpark.load(SeqCst), c.park.load(SeqCst));
+    });
+    assert_eq!(counts.start.load(SeqCst), 1);
+    assert_eq!(counts.stop.load(SeqCst), 1);
+
+    rt.block_on(async {});
+    assert_eq!(counts.start.load(SeqCst), 2);
+    assert_eq!(counts.stop.load(SeqCst), 2);
+}
+
+#[test]
+fn thread_stop_on_panic() {
+    let stops = Arc::new(AtomicUsize::new(0));
+    let s = stops.clone();
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .on_thread_stop(move || {
+            s.fetch_add(1, SeqCst);
+        })
+        .build()
+        .unwrap();
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        rt.block_on(async { panic!("boom") })
+    }));
+    assert!(res.is_err());
+    assert_eq!(stops.load(SeqCst), 1);
+}
+
+thread_local! {
+    static PARK_TX: std::cell::RefCell<Option<futures::channel::oneshot::Sender<()>>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+#[test]
+fn before_park_can_spawn() {
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .before_park(|| {
+            if let Some(tx) = PARK_TX.with(|tx| tx.borrow_mut().take()) {
+                snowfallio::spawn(async move {
+                    let _ = tx.send(());
+                });
+            }
+        })
+        .enable_timer()
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let (tx, rx) = futures::channel::oneshot::channel();
+        PARK_TX.with(|cell| *cell.borrow_mut() = Some(tx));
+        // The task spawned by the callback must run before the runtime parks
+        // until the timeout.
+        let begin = std::time::Instant::now();
+        let res = snowfallio::time::timeout(std::time::Duration::from_secs(5), rx).await;
+        assert!(res.is_ok());
+        assert!(begin.elapsed() < std::time::Duration::from_secs(1));
+    });
+}
+
+thread_local! {
+    static PARK_WAKE_TX: std::cell::RefCell<Option<futures::channel::oneshot::Sender<()>>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+#[test]
+fn before_park_can_wake_main_future() {
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .before_park(|| {
+            if let Some(tx) = PARK_WAKE_TX.with(|tx| tx.borrow_mut().take()) {
+                let _ = tx.send(());
+            }
+        })
+        .enable_timer()
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let (tx, rx) = futures::channel::oneshot::channel();
+        PARK_WAKE_TX.with(|cell| *cell.borrow_mut() = Some(tx));
+        // The main future woken by the callback must be polled before the
+        // runtime parks until the timeout.
+        let begin = std::time::Instant::now();
+        let res = snowfallio::time::timeout(std::time::Duration::from_secs(5), rx).await;
+        assert!(res.is_ok());
+        assert!(begin.elapsed() < std::time::Duration::from_secs(1));
+    });
+}
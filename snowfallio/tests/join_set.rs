@@ -0,0 +1,117 @@
+use std::{cell::Cell, rc::Rc, time::Duration};
+
+use snowfallio::task::JoinSet;
+
+#[snowfallio::test(timer_enabled = true)]
+async fn join_next_in_completion_order() {
+    let mut set = JoinSet::new();
+    for i in (0..5u64).rev() {
+        set.spawn(async move {
+            snowfallio::time::sleep(Duration::from_millis(i * 20)).await;
+            i
+        });
+    }
+    assert_eq!(set.len(), 5);
+
+    let mut order = Vec::new();
+    while let Some(i) = set.join_next().await {
+        order.push(i);
+    }
+    assert_eq!(order, vec![0, 1, 2, 3, 4]);
+    assert!(set.is_empty());
+    assert_eq!(set.join_next().await, None);
+}
+
+#[snowfallio::test(timer_enabled = true)]
+async fn abort_all() {
+    let finished = Rc::new(Cell::new(0));
+    let mut set = JoinSet::new();
+    for _ in 0..5 {
+        let finished = finished.clone();
+        set.spawn(async move {
+            snowfallio::time::sleep(Duration::from_millis(100)).await;
+            finished.set(finished.get() + 1);
+        });
+    }
+    // Let the tasks start sleeping.
+    snowfallio::time::sleep(Duration::from_millis(10)).await;
+    set.abort_all();
+    assert!(set.is_empty());
+    assert_eq!(set.join_next().await, None);
+
+    snowfallio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(finished.get(), 0);
+}
+
+#[snowfallio::test(timer_enabled = true)]
+async fn drop_aborts() {
+    struct DropFlag(Rc<Cell<bool>>);
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            self.0.set(true);
+        }
+    }
+
+    let dropped = Rc::new(Cell::new(false));
+    let mut set = JoinSet::new();
+    let flag = DropFlag(dropped.clone());
+    set.spawn(async move {
+        let _flag = flag;
+        snowfallio::time::sleep(Duration::from_secs(10)).await;
+    });
+    snowfallio::time::sleep(Duration::from_millis(10)).await;
+    assert!(!dropped.get());
+
+    drop(set);
+    snowfallio::time::sleep(Duration::from_millis(10)).await;
+    assert!(dropped.get());
+}
+
+#[snowfallio::test(timer_enabled = true)]
+async fn scope_waits_for_children() {
+    let counter = Rc::new(Cell::new(0));
+    let ret = snowfallio::task::scope(|s| {
+        let counter = counter.clone();
+        async move {
+            for i in 0..5u64 {
+                let counter = counter.clone();
+                let nested = s.clone();
+                s.spawn(async move {
+                    snowfallio::time::sleep(Duration::from_millis(i * 10)).await;
+                    let counter2 = counter.clone();
+                    nested.spawn(async move {
+                        snowfallio::time::sleep(Duration::from_millis(10)).await;
+                        counter2.set(counter2.get() + 1);
+                    });
+                    counter.set(counter.get() + 1);
+                });
+            }
+            "done"
+        }
+    })
+    .await;
+    assert_eq!(ret, "done");
+    assert_eq!(counter.get(), 10);
+}
+
+#[snowfallio::test(timer_enabled = true)]
+async fn scope_dropped_aborts_children() {
+    let finished = Rc::new(Cell::new(false));
+    let f = finished.clone();
+    let scope = snowfallio::task::scope(|s| async move {
+        s.spawn(async move {
+            snowfallio::time::sleep(Duration::from_millis(50)).await;
+            f.set(true);
+        });
+    });
+    let _ = snowfallio::time::timeout(Duration::from_millis(10), scope).await;
+    snowfallio::time::sleep(Duration::from_millis(100)).await;
+    assert!(!finished.get());
+}
+
+#[snowfallio::test]
+#[should_panic(expected = "cannot spawn into a scope that has closed")]
+async fn escaped_scope_cannot_spawn() {
+    let escaped = snowfallio::task::scope(|s| async move { s.clone() }).await;
+    escaped.spawn(async {});
+}
@@ -0,0 +1,78 @@
+use std::time::Duration;
+
+use snowfallio::{metrics::MetricsHandle, IoUringDriver, RuntimeBuilder};
+
+#[snowfallio::test]
+async fn task_counts() {
+    let handle = MetricsHandle::current();
+    let before = handle.snapshot();
+
+    let joins: Vec<_> = (0..10).map(|_| snowfallio::spawn(async {})).collect();
+    let spawned = handle.snapshot();
+    assert_eq!(spawned.spawned_tasks - before.spawned_tasks, 10);
+    assert_eq!(spawned.live_tasks - before.live_tasks, 10);
+
+    for join in joins {
+        join.await;
+    }
+    let after = handle.snapshot();
+    assert_eq!(after.live_tasks, before.live_tasks);
+    assert!(after.polls >= before.polls + 10);
+    assert!(after.ticks > before.ticks);
+}
+
+#[test]
+fn park_and_poll_time() {
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .enable_poll_time_histogram()
+        .enable_timer()
+        .build()
+        .unwrap();
+    let handle = rt.metrics_handle();
+
+    rt.block_on(async {
+        snowfallio::spawn(async {
+            std::thread::sleep(Duration::from_millis(5));
+        })
+        .await;
+        snowfallio::time::sleep(Duration::from_millis(50)).await;
+    });
+
+    // The handle can be read from another thread.
+    let metrics = std::thread::spawn(move || handle.snapshot())
+        .join()
+        .unwrap();
+    assert!(metrics.parks > 0);
+    assert!(metrics.park_duration >= Duration::from_millis(40));
+    assert!(metrics.busy_duration >= Duration::from_millis(5));
+    assert!(metrics.busy_ratio() > 0.0 && metrics.busy_ratio() < 1.0);
+
+    let histogram = &metrics.poll_time_histogram;
+    assert!(histogram.num_buckets() > 0);
+    let slow_polls: u64 = (0..histogram.num_buckets())
+        .filter(|&b| histogram.bucket_range(b).start >= Duration::from_millis(4))
+        .map(|b| histogram.bucket_count(b))
+        .sum();
+    assert_eq!(slow_polls, 1);
+    assert_eq!(histogram.iter().sum::<u64>(), metrics.polls);
+}
+
+#[test]
+fn histogram_disabled_by_default() {
+    let rt = RuntimeBuilder::<IoUringDriver>::new().build().unwrap();
+    assert_eq!(rt.metrics().poll_time_histogram.num_buckets(), 0);
+}
+
+#[cfg(feature = "sync")]
+#[snowfallio::test]
+async fn remote_wakes() {
+    let (tx, rx) = futures::channel::oneshot::channel::<()>();
+    let t = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(20));
+        tx.send(()).unwrap();
+    });
+    let handle = snowfallio::spawn(async move { rx.await.unwrap() });
+    handle.await;
+    t.join().unwrap();
+    assert!(MetricsHandle::current().snapshot().remote_wakes >= 1);
+}
@@ -0,0 +1,126 @@
+use std::{cell::RefCell, rc::Rc};
+
+use snowfallio::{task::Priority, IoUringDriver, RuntimeBuilder};
+
+fn spawn_all(
+    order: &Rc<RefCell<Vec<char>>>,
+    count: usize,
+) -> Vec<snowfallio::task::JoinHandle<()>> {
+    let mut handles = Vec::new();
+    for (priority, tag) in [
+        (Priority::Background, 'B'),
+        (Priority::Normal, 'N'),
+        (Priority::High, 'H'),
+    ] {
+        for _ in 0..count {
+            let order = order.clone();
+            handles.push(snowfallio::spawn_with_priority(priority, async move {
+                order.borrow_mut().push(tag);
+            }));
+        }
+    }
+    handles
+}
+
+#[test]
+fn weighted_order() {
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .with_priority_weights(2, 1, 1)
+        .build()
+        .unwrap();
+    let order = Rc::new(RefCell::new(Vec::new()));
+    rt.block_on(async {
+        for handle in spawn_all(&order, 4) {
+            handle.await;
+        }
+    });
+    let order: String = order.borrow().iter().collect();
+    assert_eq!(order, "HHNBHHNBNBNB");
+}
+
+#[snowfallio::test]
+async fn high_runs_first() {
+    let order = Rc::new(RefCell::new(Vec::new()));
+    for handle in spawn_all(&order, 1) {
+        handle.await;
+    }
+    assert_eq!(*order.borrow(), vec!['H', 'N', 'B']);
+}
+
+#[snowfallio::test]
+async fn woken_task_keeps_priority() {
+    let order = Rc::new(RefCell::new(Vec::new()));
+    let mut handles = Vec::new();
+    for _ in 0..3 {
+        let o = order.clone();
+        handles.push(snowfallio::spawn(async move {
+            o.borrow_mut().push('N');
+        }));
+    }
+    let o = order.clone();
+    handles.push(snowfallio::spawn_with_priority(
+        Priority::High,
+        async move {
+            o.borrow_mut().push('H');
+            snowfallio::task::yield_now().await;
+            o.borrow_mut().push('H');
+        },
+    ));
+    for handle in handles {
+        handle.await;
+    }
+    assert_eq!(*order.borrow(), vec!['H', 'H', 'N', 'N', 'N']);
+}
+
+#[snowfallio::test]
+async fn background_not_starved_by_wakeups() {
+    use std::{cell::Cell, future::poll_fn, task::Poll, task::Waker};
+
+    let waker: Rc<Cell<Option<Waker>>> = Rc::new(Cell::new(None));
+    let done = Rc::new(Cell::new(false));
+
+    // A high priority task that parks until the normal task wakes it.
+    let w = waker.clone();
+    let d = done.clone();
+    let high = snowfallio::spawn_with_priority(Priority::High, async move {
+        while !d.get() {
+            let mut parked = false;
+            poll_fn(|cx| {
+                if parked {
+                    return Poll::Ready(());
+                }
+                parked = true;
+                w.set(Some(cx.waker().clone()));
+                Poll::Pending
+            })
+            .await;
+        }
+    });
+
+    let d = done.clone();
+    let background = snowfallio::spawn_with_priority(Priority::Background, async move {
+        d.set(true);
+    });
+
+    // A normal task that wakes the high priority task on every run.
+    let w = waker.clone();
+    let d = done.clone();
+    let normal = snowfallio::spawn(async move {
+        let mut rounds = 0;
+        while !d.get() && rounds < 1000 {
+            if let Some(waker) = w.take() {
+                waker.wake();
+            }
+            rounds += 1;
+            snowfallio::task::yield_now().await;
+        }
+        rounds
+    });
+
+    assert!(normal.await < 1000, "background task was starved");
+    if let Some(waker) = waker.take() {
+        waker.wake();
+    }
+    background.await;
+    high.await;
+}
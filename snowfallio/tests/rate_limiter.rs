@@ -0,0 +1,102 @@
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+use snowfallio::{
+    io::{AsyncReadRentExt, AsyncWriteRentExt, Throttled},
+    net::{TcpListener, TcpStream},
+    time::{timeout, Instant, RateLimiter},
+};
+
+#[snowfallio::test(timer_enabled = true)]
+async fn burst_then_rate() {
+    let limiter = RateLimiter::new(1000, 10);
+    for _ in 0..10 {
+        assert!(limiter.try_acquire(1));
+    }
+    assert!(!limiter.try_acquire(1));
+
+    let start = Instant::now();
+    limiter.acquire(50).await;
+    assert!(start.elapsed() >= Duration::from_millis(45));
+    assert!(limiter.available() < 10);
+}
+
+#[snowfallio::test(timer_enabled = true)]
+async fn waiters_are_fifo() {
+    let limiter = Rc::new(RateLimiter::new(1000, 1));
+    assert!(limiter.try_acquire(1));
+    let order = Rc::new(RefCell::new(Vec::new()));
+
+    let big = {
+        let (limiter, order) = (limiter.clone(), order.clone());
+        snowfallio::spawn(async move {
+            limiter.acquire(30).await;
+            order.borrow_mut().push("big");
+        })
+    };
+    snowfallio::task::yield_now().await;
+    let small = {
+        let (limiter, order) = (limiter.clone(), order.clone());
+        snowfallio::spawn(async move {
+            limiter.acquire(1).await;
+            order.borrow_mut().push("small");
+        })
+    };
+    big.await;
+    small.await;
+    assert_eq!(*order.borrow(), ["big", "small"]);
+}
+
+#[snowfallio::test(timer_enabled = true)]
+async fn canceled_acquire_gives_back() {
+    let limiter = RateLimiter::new(10, 10);
+    assert!(limiter.try_acquire(10));
+    assert!(timeout(Duration::from_millis(10), limiter.acquire(1000))
+        .await
+        .is_err());
+    // The canceled reservation does not delay the next acquire.
+    timeout(Duration::from_millis(500), limiter.acquire(1))
+        .await
+        .unwrap();
+}
+
+async fn pair() -> (TcpStream, TcpStream) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (client, server) = snowfallio::join!(TcpStream::connect(addr), listener.accept());
+    (client.unwrap(), server.unwrap().0)
+}
+
+#[snowfallio::test(timer_enabled = true)]
+async fn throttled_write() {
+    let (client, mut server) = pair().await;
+    let limiter = Rc::new(RateLimiter::new(100_000, 5_000));
+    let mut client = Throttled::with_limiters(client, None, Some(limiter));
+
+    let start = Instant::now();
+    let write = async {
+        let (res, _) = client.write_all(vec![1u8; 20_000]).await;
+        res.unwrap();
+    };
+    let read = async {
+        let (res, buf) = server.read_exact(vec![0u8; 20_000]).await;
+        res.unwrap();
+        assert!(buf.iter().all(|b| *b == 1));
+    };
+    snowfallio::join!(write, read);
+    // The first 5000 bytes are the burst, the rest takes 150ms.
+    assert!(start.elapsed() >= Duration::from_millis(140));
+}
+
+#[snowfallio::test(timer_enabled = true)]
+async fn throttled_read() {
+    let (client, mut server) = pair().await;
+    let limiter = Rc::new(RateLimiter::new(100_000, 5_000));
+    let mut client = Throttled::new(client, limiter);
+
+    let (res, _) = server.write_all(vec![1u8; 20_000]).await;
+    res.unwrap();
+    let start = Instant::now();
+    let (res, _) = client.read_exact(vec![0u8; 20_000]).await;
+    res.unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(90));
+}
@@ -0,0 +1,185 @@
+#![cfg(feature = "sync")]
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering::SeqCst},
+        Arc,
+    },
+    task::{Context, Poll},
+    thread,
+    time::Duration,
+};
+
+use futures::task::{waker, ArcWake};
+use snowfallio::{
+    sync::remote::{mpsc, oneshot},
+    IoUringDriver, RuntimeBuilder,
+};
+
+struct CountWake(AtomicUsize);
+
+impl ArcWake for CountWake {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.0.fetch_add(1, SeqCst);
+    }
+}
+
+#[test]
+fn sends_are_coalesced() {
+    let count = Arc::new(CountWake(AtomicUsize::new(0)));
+    let waker = waker(count.clone());
+    let cx = &mut Context::from_waker(&waker);
+    let (tx, mut rx) = mpsc::unbounded_channel();
+
+    assert!(rx.poll_recv(cx).is_pending());
+    thread::spawn(move || {
+        for i in 0..100 {
+            tx.send(i).unwrap();
+        }
+    })
+    .join()
+    .unwrap();
+    // The whole burst woke the receiver once.
+    assert_eq!(count.0.load(SeqCst), 1);
+
+    for i in 0..100 {
+        assert_eq!(rx.poll_recv(cx), Poll::Ready(Some(i)));
+    }
+    assert_eq!(rx.poll_recv(cx), Poll::Ready(None));
+    assert_eq!(count.0.load(SeqCst), 1);
+}
+
+#[test]
+fn between_runtimes() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let (ack_tx, ack_rx) = oneshot::channel();
+    let sender = thread::spawn(move || {
+        let mut rt = RuntimeBuilder::<IoUringDriver>::new().build().unwrap();
+        rt.block_on(async move {
+            for i in 0..1000u64 {
+                tx.send(i).unwrap();
+                if i % 100 == 0 {
+                    snowfallio::task::yield_now().await;
+                }
+            }
+            drop(tx);
+            // The runtime parks here, the deferred unparks must be flushed.
+            ack_rx.await.unwrap()
+        })
+    });
+
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new().build().unwrap();
+    let sum = rt.block_on(async move {
+        let mut sum = 0;
+        while let Some(i) = rx.recv().await {
+            sum += i;
+        }
+        sum
+    });
+    ack_tx.send(sum).unwrap();
+    assert_eq!(sender.join().unwrap(), 999 * 1000 / 2);
+}
+
+#[test]
+fn wake_from_before_park_hook() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let (ack_tx, ack_rx) = oneshot::channel();
+    let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+    let receiver = thread::spawn(move || {
+        let mut rt = RuntimeBuilder::<IoUringDriver>::new().build().unwrap();
+        rt.block_on(async move {
+            ready_tx.send(()).unwrap();
+            ack_tx.send(rx.recv().await.unwrap()).unwrap();
+        })
+    });
+    ready_rx.recv().unwrap();
+    // Let the receiving runtime park.
+    thread::sleep(Duration::from_millis(50));
+
+    let tx = std::sync::Mutex::new(Some(tx));
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .before_park(move || {
+            if let Some(tx) = tx.lock().unwrap().take() {
+                tx.send(7).unwrap();
+            }
+        })
+        .enable_timer()
+        .build()
+        .unwrap();
+    // The wake sent by the hook must unpark the receiver before this runtime
+    // parks, not when its timer wakes it up.
+    let begin = std::time::Instant::now();
+    let ack = rt.block_on(async {
+        let guard = snowfallio::time::sleep(Duration::from_secs(2));
+        snowfallio::select! {
+            ack = ack_rx => ack.unwrap(),
+            _ = guard => panic!("the receiver was not woken"),
+        }
+    });
+    assert_eq!(ack, 7);
+    assert!(begin.elapsed() < Duration::from_secs(1));
+    receiver.join().unwrap();
+}
+
+#[test]
+fn oneshot_from_plain_thread() {
+    let (tx, rx) = oneshot::channel();
+    let t = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        tx.send("hello").unwrap();
+    });
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new().build().unwrap();
+    assert_eq!(rt.block_on(rx), Ok("hello"));
+    t.join().unwrap();
+
+    let (tx, rx) = oneshot::channel::<()>();
+    thread::spawn(move || drop(tx)).join().unwrap();
+    assert!(rt.block_on(rx).is_err());
+}
+
+#[test]
+fn oneshot_closed() {
+    let (mut tx, rx) = oneshot::channel::<u8>();
+    let t = thread::spawn(move || {
+        let mut rt = RuntimeBuilder::<IoUringDriver>::new().build().unwrap();
+        rt.block_on(async move {
+            tx.closed().await;
+            tx.send(1)
+        })
+    });
+    thread::sleep(Duration::from_millis(50));
+    drop(rx);
+    assert_eq!(t.join().unwrap(), Err(1));
+}
+
+#[test]
+fn receiver_closed() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    tx.send(1).unwrap();
+    rx.close();
+    assert!(tx.is_closed());
+    assert_eq!(tx.send(2), Err(mpsc::SendError(2)));
+    assert_eq!(rx.try_recv(), Ok(1));
+    // Nothing can be sent anymore, even though the sender is alive.
+    assert_eq!(rx.try_recv(), Err(mpsc::TryRecvError::Disconnected));
+    drop(tx);
+    assert_eq!(rx.try_recv(), Err(mpsc::TryRecvError::Disconnected));
+}
+
+#[test]
+fn receiver_closed_drains() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    tx.send(1).unwrap();
+    tx.send(2).unwrap();
+    rx.close();
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new().build().unwrap();
+    let received = rt.block_on(async move {
+        let mut received = Vec::new();
+        while let Some(v) = rx.recv().await {
+            received.push(v);
+        }
+        received
+    });
+    assert_eq!(received, [1, 2]);
+    drop(tx);
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "sync")]
+
+use std::{rc::Rc, sync::mpsc, thread, time::Duration};
+
+use snowfallio::{blocking::JoinError, IoUringDriver, RuntimeBuilder};
+
+/// Start a runtime on a new thread, which runs until `stop` is dropped.
+fn start_target() -> (
+    usize,
+    futures::channel::oneshot::Sender<()>,
+    thread::JoinHandle<()>,
+) {
+    let (id_tx, id_rx) = mpsc::channel();
+    let (stop, stopped) = futures::channel::oneshot::channel::<()>();
+    let thread = thread::spawn(move || {
+        let mut rt = RuntimeBuilder::<IoUringDriver>::new().build().unwrap();
+        id_tx.send(rt.thread_id()).unwrap();
+        rt.block_on(async move {
+            let _ = stopped.await;
+        });
+    });
+    (id_rx.recv().unwrap(), stop, thread)
+}
+
+#[test]
+fn spawn_on_other_thread() {
+    let (target, stop, thread) = start_target();
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new().build().unwrap();
+    rt.block_on(async move {
+        let handle = snowfallio::spawn_on(target, || async {
+            // Not `Send`, the future is built on the target thread.
+            let shard = Rc::new(snowfallio::current_thread_id());
+            snowfallio::task::yield_now().await;
+            *shard
+        });
+        assert_eq!(handle.await.unwrap(), target);
+
+        let handles: Vec<_> = (0..16)
+            .map(|i| snowfallio::spawn_on(target, move || async move { i * 2 }))
+            .collect();
+        for (i, handle) in handles.into_iter().enumerate() {
+            assert_eq!(handle.await.unwrap(), i * 2);
+        }
+    });
+    drop(stop);
+    thread.join().unwrap();
+}
+
+#[test]
+fn await_from_plain_thread() {
+    let (target, stop, thread) = start_target();
+    let handle = snowfallio::spawn_on(target, || async { "done" });
+    assert_eq!(futures::executor::block_on(handle).unwrap(), "done");
+    drop(stop);
+    thread.join().unwrap();
+}
+
+#[snowfallio::test]
+async fn spawn_on_self() {
+    let id = snowfallio::current_thread_id();
+    let handle = snowfallio::spawn_on(id, || async { 7 });
+    assert_eq!(handle.await.unwrap(), 7);
+}
+
+#[test]
+fn canceled() {
+    // No runtime is registered with this id.
+    let handle = snowfallio::spawn_on(usize::MAX, || async {});
+    assert!(handle.is_finished());
+    assert!(matches!(
+        futures::executor::block_on(handle),
+        Err(JoinError::Canceled)
+    ));
+
+    // The target runtime is dropped before the task completes.
+    let (target, stop, thread) = start_target();
+    let handle = snowfallio::spawn_on(target, std::future::pending::<()>);
+    thread::sleep(Duration::from_millis(50));
+    drop(stop);
+    thread.join().unwrap();
+    assert!(matches!(
+        futures::executor::block_on(handle),
+        Err(JoinError::Canceled)
+    ));
+}
@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+use snowfallio::{net::udp::UdpSocket, IoUringDriver, RuntimeBuilder};
+
+#[test]
+fn wakeup_while_spinning() {
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .spin_before_park(Duration::from_secs(5))
+        .build()
+        .unwrap();
+    let metrics = rt.metrics_handle();
+    rt.block_on(async {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = socket.local_addr().unwrap();
+        let sender = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(10));
+            let socket = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+            socket.send_to(b"ping", addr).unwrap();
+        });
+        let (res, buf) = socket.recv(vec![0; 8]).await;
+        assert_eq!(&buf[..res.unwrap()], b"ping");
+        sender.join().unwrap();
+    });
+    let metrics = metrics.snapshot();
+    assert!(metrics.spin_wakeups >= 1);
+    assert_eq!(metrics.parks, 0);
+}
+
+#[test]
+fn park_after_spinning() {
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .spin_before_park(Duration::from_millis(1))
+        .enable_timer()
+        .build()
+        .unwrap();
+    let metrics = rt.metrics_handle();
+    rt.block_on(async {
+        snowfallio::time::sleep(Duration::from_millis(50)).await;
+    });
+    assert!(metrics.snapshot().parks >= 1);
+}
+
+#[test]
+fn timer_fires_while_spinning() {
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .spin_before_park(Duration::from_secs(5))
+        .enable_timer()
+        .build()
+        .unwrap();
+    let metrics = rt.metrics_handle();
+    rt.block_on(async {
+        snowfallio::time::sleep(Duration::from_millis(10)).await;
+    });
+    let metrics = metrics.snapshot();
+    assert!(metrics.spin_wakeups >= 1);
+    assert_eq!(metrics.parks, 0);
+}
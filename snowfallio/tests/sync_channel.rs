@@ -0,0 +1,145 @@
+use snowfallio::{
+    io::stream::Stream,
+    sync::{broadcast, mpsc, oneshot, watch},
+    task::yield_now,
+};
+
+#[snowfallio::test]
+async fn oneshot_send_recv() {
+    let (tx, rx) = oneshot::channel();
+    snowfallio::spawn(async move {
+        tx.send(1).unwrap();
+    });
+    assert_eq!(rx.await, Ok(1));
+}
+
+#[snowfallio::test]
+async fn oneshot_closed() {
+    let (tx, rx) = oneshot::channel::<()>();
+    drop(tx);
+    assert!(rx.await.is_err());
+
+    let (mut tx, rx) = oneshot::channel::<()>();
+    snowfallio::spawn(async move {
+        drop(rx);
+    });
+    tx.closed().await;
+    assert_eq!(tx.send(()), Err(()));
+}
+
+#[snowfallio::test]
+async fn mpsc_bounded_backpressure() {
+    let (tx, mut rx) = mpsc::channel(2);
+    tx.send(1).await.unwrap();
+    tx.send(2).await.unwrap();
+    assert!(matches!(tx.try_send(3), Err(mpsc::TrySendError::Full(3))));
+    assert_eq!(tx.capacity(), 0);
+
+    let sender = snowfallio::spawn({
+        let tx = tx.clone();
+        async move { tx.send(3).await.unwrap() }
+    });
+    assert_eq!(rx.recv().await, Some(1));
+    sender.await;
+    drop(tx);
+    assert_eq!(rx.recv().await, Some(2));
+    assert_eq!(rx.recv().await, Some(3));
+    assert_eq!(rx.recv().await, None);
+}
+
+#[snowfallio::test]
+async fn mpsc_unbounded_stream() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    for i in 0..4 {
+        tx.send(i).unwrap();
+    }
+    assert_eq!(rx.len(), 4);
+    drop(tx);
+    let mut sum = 0;
+    while let Some(i) = rx.next().await {
+        sum += i;
+    }
+    assert_eq!(sum, 6);
+    assert_eq!(rx.try_recv(), Err(mpsc::TryRecvError::Disconnected));
+}
+
+#[snowfallio::test]
+async fn mpsc_receiver_closed() {
+    let (tx, mut rx) = mpsc::channel::<u32>(1);
+    rx.close();
+    assert!(tx.is_closed());
+    assert_eq!(tx.send(1).await, Err(mpsc::SendError(1)));
+    tx.closed().await;
+}
+
+#[snowfallio::test]
+async fn mpsc_close_then_drain() {
+    let (tx, mut rx) = mpsc::channel(4);
+    tx.send(1).await.unwrap();
+    tx.send(2).await.unwrap();
+    rx.close();
+    let mut received = Vec::new();
+    while let Some(v) = rx.recv().await {
+        received.push(v);
+    }
+    assert_eq!(received, [1, 2]);
+    assert_eq!(rx.try_recv(), Err(mpsc::TryRecvError::Disconnected));
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    tx.send(1).unwrap();
+    rx.close();
+    assert_eq!(rx.recv().await, Some(1));
+    assert_eq!(rx.recv().await, None);
+    drop(tx);
+}
+
+#[snowfallio::test]
+async fn watch_latest_value() {
+    let (tx, mut rx) = watch::channel(0);
+    assert!(!rx.has_changed().unwrap());
+    tx.send(1).unwrap();
+    tx.send(2).unwrap();
+    assert!(rx.has_changed().unwrap());
+    rx.changed().await.unwrap();
+    // Intermediate values are skipped.
+    assert_eq!(*rx.borrow(), 2);
+
+    let waiter = snowfallio::spawn(async move {
+        rx.changed().await.unwrap();
+        let value = *rx.borrow_and_update();
+        (value, rx.changed().await.is_err())
+    });
+    yield_now().await;
+    tx.send_modify(|v| *v += 1);
+    drop(tx);
+    assert_eq!(waiter.await, (3, true));
+}
+
+#[snowfallio::test]
+async fn broadcast_every_receiver() {
+    let (tx, mut rx1) = broadcast::channel(4);
+    let mut rx2 = tx.subscribe();
+    assert_eq!(tx.send(1), Ok(2));
+    assert_eq!(rx1.recv().await, Ok(1));
+    assert_eq!(rx2.recv().await, Ok(1));
+
+    let waiter = snowfallio::spawn(async move { rx2.recv().await });
+    yield_now().await;
+    tx.send(2).unwrap();
+    assert_eq!(waiter.await, Ok(2));
+    assert_eq!(rx1.recv().await, Ok(2));
+    drop(tx);
+    assert_eq!(rx1.recv().await, Err(broadcast::RecvError::Closed));
+}
+
+#[snowfallio::test]
+async fn broadcast_lagged() {
+    let (tx, mut rx) = broadcast::channel(2);
+    for i in 0..5 {
+        tx.send(i).unwrap();
+    }
+    assert_eq!(rx.recv().await, Err(broadcast::RecvError::Lagged(3)));
+    assert_eq!(rx.recv().await, Ok(3));
+    assert_eq!(rx.try_recv(), Ok(4));
+    assert_eq!(rx.try_recv(), Err(broadcast::TryRecvError::Empty));
+}
@@ -0,0 +1,134 @@
+use std::{cell::Cell, rc::Rc};
+
+use snowfallio::{
+    sync::{Mutex, Notify, RwLock, Semaphore, TryAcquireError},
+    task::yield_now,
+};
+
+#[snowfallio::test]
+async fn mutex_serializes_tasks() {
+    let mutex = Rc::new(Mutex::new(0));
+    let mut handles = Vec::new();
+    for _ in 0..10 {
+        let mutex = mutex.clone();
+        handles.push(snowfallio::spawn(async move {
+            let mut guard = mutex.lock().await;
+            let value = *guard;
+            // Hold the lock across a suspension point.
+            yield_now().await;
+            *guard = value + 1;
+        }));
+    }
+    for handle in handles {
+        handle.await;
+    }
+    assert_eq!(*mutex.lock().await, 10);
+}
+
+#[snowfallio::test]
+async fn mutex_try_lock() {
+    let mutex = Mutex::new(1);
+    let guard = mutex.try_lock().unwrap();
+    assert!(mutex.try_lock().is_err());
+    drop(guard);
+    *mutex.try_lock().unwrap() += 1;
+    assert_eq!(mutex.into_inner(), 2);
+}
+
+#[snowfallio::test]
+async fn rwlock_readers_and_writer() {
+    let lock = Rc::new(RwLock::new(0));
+    let r1 = lock.read().await;
+    let r2 = lock.try_read().unwrap();
+    assert!(lock.try_write().is_err());
+
+    let writer = snowfallio::spawn({
+        let lock = lock.clone();
+        async move {
+            *lock.write().await += 1;
+        }
+    });
+    yield_now().await;
+    // A waiting writer blocks new readers.
+    assert!(lock.try_read().is_err());
+    assert_eq!(*r1 + *r2, 0);
+    drop((r1, r2));
+
+    writer.await;
+    assert_eq!(*lock.read().await, 1);
+}
+
+#[snowfallio::test]
+async fn semaphore_fifo() {
+    let sem = Rc::new(Semaphore::new(1));
+    let order = Rc::new(std::cell::RefCell::new(Vec::new()));
+    let permit = sem.acquire().await.unwrap();
+    let mut handles = Vec::new();
+    for i in 0..3 {
+        let (sem, order) = (sem.clone(), order.clone());
+        handles.push(snowfallio::spawn(async move {
+            let _permit = sem.acquire().await.unwrap();
+            order.borrow_mut().push(i);
+        }));
+        yield_now().await;
+    }
+    drop(permit);
+    for handle in handles {
+        handle.await;
+    }
+    assert_eq!(*order.borrow(), vec![0, 1, 2]);
+    assert_eq!(sem.available_permits(), 1);
+}
+
+#[snowfallio::test]
+async fn semaphore_many_and_close() {
+    let sem = Rc::new(Semaphore::new(3));
+    let permit = sem.try_acquire_many(2).unwrap();
+    assert_eq!(permit.num_permits(), 2);
+    assert_eq!(
+        sem.try_acquire_many(2).unwrap_err(),
+        TryAcquireError::NoPermits
+    );
+    permit.forget();
+    assert_eq!(sem.available_permits(), 1);
+    sem.add_permits(1);
+
+    let owned = sem.clone().acquire_many_owned(2).await.unwrap();
+    let waiter = snowfallio::spawn({
+        let sem = sem.clone();
+        async move { sem.acquire().await.is_err() }
+    });
+    yield_now().await;
+    sem.close();
+    assert!(waiter.await);
+    assert_eq!(sem.try_acquire().unwrap_err(), TryAcquireError::Closed);
+    drop(owned);
+}
+
+#[snowfallio::test]
+async fn notify_one_stores_permit() {
+    let notify = Notify::new();
+    notify.notify_one();
+    // The stored permit completes the next wait immediately.
+    notify.notified().await;
+}
+
+#[snowfallio::test]
+async fn notify_waiters_wakes_all() {
+    let notify = Rc::new(Notify::new());
+    let woken = Rc::new(Cell::new(0));
+    let mut handles = Vec::new();
+    for _ in 0..3 {
+        let (notify, woken) = (notify.clone(), woken.clone());
+        handles.push(snowfallio::spawn(async move {
+            notify.notified().await;
+            woken.set(woken.get() + 1);
+        }));
+    }
+    yield_now().await;
+    notify.notify_waiters();
+    for handle in handles {
+        handle.await;
+    }
+    assert_eq!(woken.get(), 3);
+}
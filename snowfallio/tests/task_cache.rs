@@ -0,0 +1,181 @@
+use std::{cell::Cell, rc::Rc};
+
+use snowfallio::{
+    task::{yield_now, JoinSet},
+    IoUringDriver, Runtime, RuntimeBuilder,
+};
+
+/// Counts the drops of futures and outputs.
+#[derive(Clone, Default)]
+struct Drops(Rc<Cell<usize>>);
+
+struct Guard<const N: usize> {
+    drops: Drops,
+    _pad: [u8; N],
+}
+
+impl<const N: usize> Guard<N> {
+    fn new(drops: &Drops) -> Self {
+        Self {
+            drops: drops.clone(),
+            _pad: [0; N],
+        }
+    }
+}
+
+impl<const N: usize> Drop for Guard<N> {
+    fn drop(&mut self) {
+        self.drops.0.set(self.drops.0.get() + 1);
+    }
+}
+
+#[repr(align(64))]
+struct OverAligned {
+    _guard: Guard<8>,
+}
+
+fn runtime(capacity: usize) -> Runtime<IoUringDriver> {
+    RuntimeBuilder::<IoUringDriver>::new()
+        .task_cache_capacity(capacity)
+        .build()
+        .unwrap()
+}
+
+/// Spawn tasks of every size class and beyond, each returning an output that
+/// is also counted. Returns the number of guards created.
+fn spawn_mixed(drops: &Drops, round: usize) -> usize {
+    macro_rules! spawn_sized {
+        ($($n:literal),*) => {{
+            let mut count = 0;
+            $(
+                let guard = Guard::<$n>::new(drops);
+                let output = Guard::<$n>::new(drops);
+                let join = snowfallio::spawn(async move {
+                    yield_now().await;
+                    drop(guard);
+                    output
+                });
+                // Detach half of the tasks before they complete.
+                if round % 2 == 0 {
+                    drop(join);
+                } else {
+                    snowfallio::spawn(async move { drop(join.await) });
+                }
+                count += 2;
+            )*
+            count
+        }};
+    }
+    spawn_sized!(0, 32, 100, 200, 500, 1000, 3000, 6000, 20000)
+}
+
+fn mixed_sizes(capacity: usize) {
+    let drops = Drops::default();
+    let mut rt = runtime(capacity);
+    let created = rt.block_on(async {
+        let mut created = 0;
+        for round in 0..64 {
+            created += spawn_mixed(&drops, round);
+            yield_now().await;
+        }
+        // Let the last tasks complete.
+        for _ in 0..4 {
+            yield_now().await;
+        }
+        created
+    });
+    assert_eq!(drops.0.get(), created);
+}
+
+#[test]
+fn mixed_sizes_with_cache() {
+    mixed_sizes(8);
+}
+
+#[test]
+fn mixed_sizes_without_cache() {
+    mixed_sizes(0);
+}
+
+#[test]
+fn join_after_completion() {
+    let drops = Drops::default();
+    let mut rt = runtime(4);
+    rt.block_on(async {
+        for _ in 0..100 {
+            let output = Guard::<64>::new(&drops);
+            let join = snowfallio::spawn(async move { output });
+            // The task completes before the handle reads or drops the output.
+            yield_now().await;
+            yield_now().await;
+            drop(join);
+        }
+        assert_eq!(drops.0.get(), 100);
+
+        for _ in 0..100 {
+            let output = Guard::<64>::new(&drops);
+            drop(snowfallio::spawn(async move { output }).await);
+        }
+    });
+    assert_eq!(drops.0.get(), 200);
+}
+
+#[test]
+fn over_aligned_futures() {
+    let drops = Drops::default();
+    let mut rt = runtime(4);
+    rt.block_on(async {
+        for _ in 0..100 {
+            let guard = OverAligned {
+                _guard: Guard::new(&drops),
+            };
+            let out = snowfallio::spawn(async move {
+                yield_now().await;
+                let ptr = &guard as *const OverAligned as usize;
+                drop(guard);
+                ptr
+            })
+            .await;
+            assert_eq!(out % 64, 0);
+        }
+    });
+    assert_eq!(drops.0.get(), 100);
+}
+
+#[test]
+fn queued_tasks_dropped_with_runtime() {
+    let drops = Drops::default();
+    let rt = runtime(4);
+    let handle = rt.handle();
+    for _ in 0..100 {
+        let guard = Guard::<128>::new(&drops);
+        handle.spawn(async move { drop(guard) });
+    }
+    // The tasks never run, they are freed outside of the runtime.
+    drop(rt);
+    assert_eq!(drops.0.get(), 100);
+}
+
+#[test]
+fn aborted_tasks() {
+    let drops = Drops::default();
+    let mut rt = runtime(4);
+    rt.block_on(async {
+        for _ in 0..10 {
+            let mut set = JoinSet::new();
+            for _ in 0..10 {
+                let guard = Guard::<256>::new(&drops);
+                set.spawn(async move {
+                    let _guard = guard;
+                    std::future::pending::<()>().await
+                });
+            }
+            yield_now().await;
+            assert_eq!(set.len(), 10);
+            set.abort_all();
+            // The aborted futures are dropped when polled again.
+            yield_now().await;
+        }
+    });
+    assert_eq!(drops.0.get(), 100);
+}
@@ -0,0 +1,138 @@
+use std::{cell::Cell, rc::Rc};
+
+use snowfallio::{
+    net::udp::UdpSocket,
+    task::{opcode_name, Builder, TaskState},
+    IoUringDriver, RuntimeBuilder,
+};
+
+fn runtime() -> snowfallio::Runtime<snowfallio::time::TimeDriver<IoUringDriver>> {
+    RuntimeBuilder::<IoUringDriver>::new()
+        .enable_task_dump()
+        .enable_timer()
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn named_task_location() {
+    runtime().block_on(async {
+        let builder = Builder::new().name("idle");
+        let line = line!() + 1;
+        let handle = builder.spawn(std::future::pending::<()>());
+        let dump = snowfallio::runtime::dump();
+        assert_eq!(dump.tasks().len(), 1);
+        let task = &dump.tasks()[0];
+        assert_eq!(task.name(), Some("idle"));
+        assert_eq!(task.location().file(), file!());
+        assert_eq!(task.location().line(), line);
+        assert_eq!(task.polls(), 0);
+        assert_eq!(task.state(), TaskState::Notified);
+        assert!(task.join_interested());
+        assert!(task.since_last_poll().is_none());
+
+        snowfallio::task::yield_now().await;
+        let dump = snowfallio::runtime::dump();
+        let task = &dump.tasks()[0];
+        assert_eq!(task.polls(), 1);
+        assert_eq!(task.state(), TaskState::Idle);
+        assert!(task.since_last_poll().is_some());
+        assert!(dump.to_string().contains("\"idle\""));
+        drop(handle);
+    });
+}
+
+#[test]
+fn completed_tasks_are_removed() {
+    runtime().block_on(async {
+        let joins: Vec<_> = (0..4)
+            .map(|i| snowfallio::spawn(async move { i }))
+            .collect();
+        assert_eq!(snowfallio::runtime::dump().tasks().len(), 4);
+        for join in joins {
+            join.await;
+        }
+        assert!(snowfallio::runtime::dump().tasks().is_empty());
+    });
+}
+
+#[test]
+fn blocked_on_op() {
+    runtime().block_on(async {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = socket.local_addr().unwrap();
+        let handle = snowfallio::spawn(async move { socket.recv(vec![0; 16]).await.0.unwrap() });
+        snowfallio::time::sleep(std::time::Duration::from_millis(10)).await;
+
+        let dump = snowfallio::runtime::dump();
+        let task = &dump.tasks()[0];
+        assert_eq!(task.waiting_on().len(), 1);
+        let name = opcode_name(task.waiting_on()[0]).unwrap();
+        assert!(name == "Recv" || name == "RecvMsg", "{name}");
+        assert!(dump.to_string().contains("waiting on"));
+
+        let sender = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        sender.send_to(b"hello", addr).unwrap();
+        assert_eq!(handle.await, 5);
+        assert!(snowfallio::runtime::dump().tasks().is_empty());
+    });
+}
+
+#[test]
+fn forgotten_tasks_are_released() {
+    struct Released(Rc<Cell<bool>>);
+
+    impl Drop for Released {
+        fn drop(&mut self) {
+            self.0.set(true);
+        }
+    }
+
+    runtime().block_on(async {
+        let released = Rc::new(Cell::new(false));
+        let guard = Released(released.clone());
+        // Never woken, and nobody holds its handle.
+        drop(snowfallio::spawn(async move {
+            let _guard = guard;
+            std::future::pending::<()>().await;
+        }));
+        assert_eq!(snowfallio::runtime::dump().tasks().len(), 1);
+
+        snowfallio::task::yield_now().await;
+        assert!(released.get());
+        assert!(snowfallio::runtime::dump().tasks().is_empty());
+    });
+}
+
+#[snowfallio::test]
+async fn disabled_by_default() {
+    let _handle = snowfallio::spawn(std::future::pending::<()>());
+    assert!(snowfallio::runtime::dump().tasks().is_empty());
+}
+
+#[cfg(feature = "sync")]
+#[test]
+fn released_on_another_thread() {
+    use std::{
+        sync::{Arc, Mutex},
+        task::{Poll, Waker},
+    };
+
+    runtime().block_on(async {
+        let waker: Arc<Mutex<Option<Waker>>> = Arc::default();
+        let stored = waker.clone();
+        let handle = snowfallio::spawn(std::future::poll_fn(move |cx| {
+            *stored.lock().unwrap() = Some(cx.waker().clone());
+            Poll::<()>::Pending
+        }));
+        snowfallio::task::yield_now().await;
+        drop(handle);
+        assert_eq!(snowfallio::runtime::dump().tasks().len(), 1);
+
+        // The last reference to the task is dropped, and the task freed, on
+        // another thread.
+        let waker = waker.lock().unwrap().take().unwrap();
+        std::thread::spawn(move || drop(waker)).join().unwrap();
+        assert!(snowfallio::runtime::dump().tasks().is_empty());
+    });
+}
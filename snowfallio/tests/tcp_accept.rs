@@ -9,7 +9,7 @@ macro_rules! test_accept {
             async fn $ident() {
                 let listener = TcpListener::bind($target).unwrap();
                 let addr = listener.local_addr().unwrap();
-                let (tx, rx) = local_sync::oneshot::channel();
+                let (tx, rx) = snowfallio::sync::oneshot::channel();
                 snowfallio::spawn(async move {
                     let (socket, _) = listener.accept().await.unwrap();
                     assert!(tx.send(socket).is_ok());
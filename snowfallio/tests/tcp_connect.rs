@@ -1,6 +1,9 @@
-use std::net::{IpAddr, SocketAddr};
+use std::{
+    net::{IpAddr, SocketAddr},
+    time::{Duration, Instant},
+};
 
-use snowfallio::net::{TcpListener, TcpStream};
+use snowfallio::net::{ConnectConfig, TcpListener, TcpStream};
 
 macro_rules! test_connect_ip {
     ($(($ident:ident, $target:expr, $addr_f:path),)*) => {
@@ -11,7 +14,7 @@ macro_rules! test_connect_ip {
                 let addr = listener.local_addr().unwrap();
                 assert!($addr_f(&addr));
 
-                let (tx, rx) = local_sync::oneshot::channel();
+                let (tx, rx) = snowfallio::sync::oneshot::channel();
 
                 snowfallio::spawn(async move {
                     let (socket, addr) = listener.accept().await.unwrap();
@@ -163,3 +166,78 @@ impl DropFlag {
         assert!(*self.0.borrow());
     }
 }
+
+/// A loopback address whose connects hang: the accept queue of the listener
+/// is full, so the kernel drops the SYNs.
+fn blackhole() -> (socket2::Socket, Vec<socket2::Socket>, SocketAddr) {
+    let socket = socket2::Socket::new(socket2::Domain::IPV4, socket2::Type::STREAM, None).unwrap();
+    socket
+        .bind(&"127.0.0.1:0".parse::<SocketAddr>().unwrap().into())
+        .unwrap();
+    socket.listen(0).unwrap();
+    let addr = socket.local_addr().unwrap();
+    let queued = (0..2)
+        .map(|_| {
+            let queued =
+                socket2::Socket::new(socket2::Domain::IPV4, socket2::Type::STREAM, None).unwrap();
+            queued.set_nonblocking(true).unwrap();
+            let _ = queued.connect(&addr);
+            queued
+        })
+        .collect();
+    // Let the handshakes fill the queue.
+    std::thread::sleep(Duration::from_millis(10));
+    (socket, queued, addr.as_socket().unwrap())
+}
+
+fn refused() -> SocketAddr {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.local_addr().unwrap()
+}
+
+#[snowfallio::test]
+async fn connect_falls_back() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addrs = [refused(), listener.local_addr().unwrap()];
+    let (stream, accepted) = snowfallio::join!(TcpStream::connect(&addrs[..]), listener.accept());
+    assert_eq!(stream.unwrap().peer_addr().unwrap(), addrs[1]);
+    accepted.unwrap();
+}
+
+#[snowfallio::test]
+async fn connect_returns_last_error() {
+    let addrs = [refused(), refused()];
+    let err = TcpStream::connect(&addrs[..]).await.err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::ConnectionRefused);
+}
+
+#[snowfallio::test]
+async fn connect_races_stalled_attempt() {
+    let (_blackhole, _queued, stalled) = blackhole();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addrs = [stalled, listener.local_addr().unwrap()];
+    let config = ConnectConfig::default().attempt_delay(Duration::from_millis(20));
+
+    let start = Instant::now();
+    let (stream, accepted) = snowfallio::join!(
+        TcpStream::connect_with_config(&addrs[..], &config),
+        listener.accept()
+    );
+    assert_eq!(stream.unwrap().peer_addr().unwrap(), addrs[1]);
+    assert!(start.elapsed() >= Duration::from_millis(19));
+    accepted.unwrap();
+}
+
+#[snowfallio::test]
+async fn connect_attempt_timeout() {
+    let (_blackhole, _queued, stalled) = blackhole();
+    let config = ConnectConfig::default().attempt_timeout(Duration::from_millis(50));
+
+    let start = Instant::now();
+    let err = TcpStream::connect_with_config(stalled, &config)
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+    assert!(start.elapsed() >= Duration::from_millis(49));
+}
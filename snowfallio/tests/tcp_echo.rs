@@ -6,7 +6,7 @@ use snowfallio::{
 async fn echo_server() {
     const ITER: usize = 1024;
 
-    let (tx, rx) = local_sync::oneshot::channel();
+    let (tx, rx) = snowfallio::sync::oneshot::channel();
 
     let srv = TcpListener::bind("127.0.0.1:0").unwrap();
     let addr = srv.local_addr().unwrap();
@@ -0,0 +1,87 @@
+use std::{net::SocketAddr, time::Duration};
+
+use snowfallio::{
+    io::{AsyncReadRentExt, AsyncWriteRentExt},
+    net::{TcpSocket, TcpStream},
+};
+
+#[snowfallio::test]
+async fn listen_and_connect_from_bound_port() {
+    let socket = TcpSocket::new_v4().unwrap();
+    socket.set_reuseaddr(true).unwrap();
+    socket.bind("127.0.0.1:0".parse().unwrap()).unwrap();
+    let listener = socket.listen(16).unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let client = TcpSocket::new_v4().unwrap();
+    client.bind("127.0.0.1:0".parse().unwrap()).unwrap();
+    let source = client.local_addr().unwrap();
+    assert_ne!(source.port(), 0);
+
+    let (stream, accepted) = snowfallio::join!(client.connect(addr), listener.accept());
+    let mut stream = stream.unwrap();
+    let (mut accepted, peer) = accepted.unwrap();
+    assert_eq!(peer, source);
+    assert_eq!(stream.local_addr().unwrap(), source);
+
+    let (res, _) = stream.write_all(b"ping").await;
+    res.unwrap();
+    let (res, buf) = accepted.read_exact(vec![0; 4]).await;
+    res.unwrap();
+    assert_eq!(buf, b"ping");
+}
+
+#[snowfallio::test]
+async fn connect_v6() {
+    let socket = TcpSocket::new_v6().unwrap();
+    socket.bind("[::1]:0".parse().unwrap()).unwrap();
+    let listener = socket.listen(16).unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let (stream, accepted) = snowfallio::join!(
+        TcpSocket::new_v6().unwrap().connect(addr),
+        listener.accept()
+    );
+    assert_eq!(stream.unwrap().peer_addr().unwrap(), addr);
+    accepted.unwrap();
+}
+
+#[snowfallio::test]
+async fn connect_refused() {
+    let addr: SocketAddr = {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap()
+    };
+    let err = TcpSocket::new_v4()
+        .unwrap()
+        .connect(addr)
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::ConnectionRefused);
+    assert!(TcpStream::connect(addr).await.is_err());
+}
+
+#[test]
+fn options() {
+    let socket = TcpSocket::new_v4().unwrap();
+
+    socket.set_reuseaddr(true).unwrap();
+    assert!(socket.reuseaddr().unwrap());
+    socket.set_reuseport(true).unwrap();
+    assert!(socket.reuseport().unwrap());
+
+    socket.set_send_buffer_size(64 << 10).unwrap();
+    assert!(socket.send_buffer_size().unwrap() >= 64 << 10);
+    socket.set_recv_buffer_size(64 << 10).unwrap();
+    assert!(socket.recv_buffer_size().unwrap() >= 64 << 10);
+
+    socket.set_linger(Some(Duration::from_secs(1))).unwrap();
+    assert_eq!(socket.linger().unwrap(), Some(Duration::from_secs(1)));
+    socket.set_linger(None).unwrap();
+    assert_eq!(socket.linger().unwrap(), None);
+
+    socket.set_tos(0x10).unwrap();
+    assert_eq!(socket.tos().unwrap(), 0x10);
+
+    assert_eq!(socket.device().unwrap(), None);
+}
@@ -0,0 +1,59 @@
+#![cfg(feature = "sync")]
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering::SeqCst},
+        mpsc, Arc,
+    },
+    task::Poll,
+    thread,
+};
+
+use snowfallio::{blocking::JoinError, IoUringDriver, RuntimeBuilder};
+
+#[test]
+fn stale_thread_id() {
+    let id = RuntimeBuilder::<IoUringDriver>::new()
+        .build()
+        .unwrap()
+        .thread_id();
+    // A new runtime does not get the id of the dropped one.
+    let rt = RuntimeBuilder::<IoUringDriver>::new().build().unwrap();
+    assert_ne!(rt.thread_id(), id);
+
+    let handle = snowfallio::spawn_on(id, || async {});
+    assert!(handle.is_finished());
+    assert!(matches!(
+        futures::executor::block_on(handle),
+        Err(JoinError::Canceled)
+    ));
+}
+
+#[test]
+fn wake_dropped_runtime() {
+    let (waker_tx, waker_rx) = mpsc::channel();
+    let done = Arc::new(AtomicBool::new(false));
+    let d = done.clone();
+    let thread = thread::spawn(move || {
+        let mut rt = RuntimeBuilder::<IoUringDriver>::new().build().unwrap();
+        rt.block_on(std::future::poll_fn(|cx| {
+            if d.load(SeqCst) {
+                return Poll::Ready(());
+            }
+            let _ = waker_tx.send(cx.waker().clone());
+            Poll::Pending
+        }));
+    });
+    let waker = waker_rx.recv().unwrap();
+
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new().build().unwrap();
+    rt.block_on(async move {
+        // The registration of the other thread is cached by the first wake.
+        done.store(true, SeqCst);
+        waker.wake_by_ref();
+        thread.join().unwrap();
+        // The cached registration is now stale, the wake is dropped.
+        waker.wake_by_ref();
+        waker.wake();
+    });
+}
@@ -0,0 +1,68 @@
+#![cfg(feature = "test-util")]
+
+use std::{cell::Cell, rc::Rc, time::Duration};
+
+use snowfallio::time::{self, sleep, timeout, Instant};
+
+#[snowfallio::test(timer_enabled = true)]
+async fn pause_freezes_now() {
+    time::pause();
+    let start = Instant::now();
+    std::thread::sleep(Duration::from_millis(10));
+    assert_eq!(Instant::now(), start);
+
+    time::advance(Duration::from_secs(5)).await;
+    assert_eq!(Instant::now() - start, Duration::from_secs(5));
+
+    time::resume();
+    std::thread::sleep(Duration::from_millis(10));
+    assert!(Instant::now() - start > Duration::from_secs(5));
+}
+
+#[snowfallio::test(timer_enabled = true)]
+async fn advance_fires_timers() {
+    time::pause();
+    let fired = Rc::new(Cell::new(false));
+    let f = fired.clone();
+    let _task = snowfallio::spawn(async move {
+        sleep(Duration::from_secs(60)).await;
+        f.set(true);
+    });
+    snowfallio::task::yield_now().await;
+
+    time::advance(Duration::from_secs(59)).await;
+    assert!(!fired.get());
+    time::advance(Duration::from_secs(1)).await;
+    snowfallio::task::yield_now().await;
+    assert!(fired.get());
+}
+
+#[snowfallio::test(timer_enabled = true)]
+async fn auto_advance_when_idle() {
+    let real = std::time::Instant::now();
+    time::pause();
+    let start = Instant::now();
+
+    // An hour of timers completes at once.
+    sleep(Duration::from_secs(3600)).await;
+    assert!(Instant::now() - start >= Duration::from_secs(3600));
+    assert!(
+        timeout(Duration::from_secs(30), std::future::pending::<()>())
+            .await
+            .is_err()
+    );
+    assert!(real.elapsed() < Duration::from_secs(10));
+}
+
+#[test]
+#[should_panic(expected = "time is already frozen")]
+fn pause_twice() {
+    let mut rt = snowfallio::RuntimeBuilder::<snowfallio::IoUringDriver>::new()
+        .enable_timer()
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        time::pause();
+        time::pause();
+    });
+}
@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use snowfallio::{
+    io::{AsyncWriteRentExt, CancelableAsyncReadRent},
+    net::{TcpListener, TcpStream},
+    time::{timeout_cancelable, Instant},
+};
+
+async fn pair() -> (TcpStream, TcpStream) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (client, server) = snowfallio::join!(TcpStream::connect(addr), listener.accept());
+    (client.unwrap(), server.unwrap().0)
+}
+
+#[snowfallio::test(timer_enabled = true)]
+async fn expiry_returns_buffer() {
+    let (mut client, _server) = pair().await;
+
+    let start = Instant::now();
+    let buf = vec![0u8; 64];
+    let (res, buf) = timeout_cancelable(Duration::from_millis(20), |handle| {
+        client.cancelable_read(buf, handle)
+    })
+    .await;
+    assert!(start.elapsed() >= Duration::from_millis(19));
+    assert_eq!(res.unwrap_err().raw_os_error(), Some(libc::ECANCELED));
+    assert_eq!(buf.capacity(), 64);
+}
+
+#[snowfallio::test(timer_enabled = true)]
+async fn completes_before_deadline() {
+    let (mut client, mut server) = pair().await;
+
+    let write = async {
+        let (res, _) = server.write_all(b"hello").await;
+        res.unwrap();
+    };
+    let read = timeout_cancelable(Duration::from_secs(5), |handle| {
+        client.cancelable_read(vec![0u8; 64], handle)
+    });
+    let (_, (res, buf)) = snowfallio::join!(write, read);
+    assert_eq!(res.unwrap(), 5);
+    assert_eq!(&buf[..5], b"hello");
+}
+
+#[snowfallio::test(timer_enabled = true)]
+async fn expiry_before_submission() {
+    let (mut client, _server) = pair().await;
+
+    let start = Instant::now();
+    let (res, _) = timeout_cancelable(Duration::from_millis(20), |handle| async move {
+        // The read is only submitted after the deadline.
+        snowfallio::time::sleep(Duration::from_millis(50)).await;
+        client.cancelable_read(vec![0u8; 64], handle).await
+    })
+    .await;
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert_eq!(res.unwrap_err().raw_os_error(), Some(libc::ECANCELED));
+}
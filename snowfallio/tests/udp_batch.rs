@@ -0,0 +1,65 @@
+use snowfallio::net::udp::UdpSocket;
+
+fn datagrams() -> Vec<u8> {
+    // Three datagrams of 100 bytes and a last one of 50.
+    (0..350).map(|i| (i / 100) as u8).collect()
+}
+
+#[snowfallio::test]
+async fn send_batch_segments() {
+    let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+    sender
+        .connect(receiver.local_addr().unwrap())
+        .await
+        .unwrap();
+
+    let (res, _) = sender.send_batch(datagrams(), 100).await;
+    assert_eq!(res.unwrap(), 350);
+
+    for (i, len) in [100, 100, 100, 50].into_iter().enumerate() {
+        let (res, buf) = receiver.recv_from(vec![0; 1500]).await;
+        let (n, addr) = res.unwrap();
+        assert_eq!(n, len);
+        assert_eq!(addr, sender.local_addr().unwrap());
+        assert!(buf.iter().all(|b| *b == i as u8));
+    }
+}
+
+#[snowfallio::test]
+async fn recv_batch_coalesced() {
+    let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+    receiver.set_gro(true).unwrap();
+    assert!(receiver.gro().unwrap());
+    let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+
+    let (res, _) = sender
+        .send_batch_to(datagrams(), 100, receiver.local_addr().unwrap())
+        .await;
+    assert_eq!(res.unwrap(), 350);
+
+    let mut received = vec![];
+    while received.len() < 350 {
+        let (res, buf) = receiver.recv_batch(vec![0; 1 << 16]).await;
+        let (n, addr, segment_size) = res.unwrap();
+        assert_eq!(addr, sender.local_addr().unwrap());
+        assert!(segment_size == 100 || n == 50, "{n} {segment_size}");
+        received.extend_from_slice(&buf[..n]);
+    }
+    assert_eq!(received, datagrams());
+}
+
+#[snowfallio::test]
+async fn recv_batch_without_gro() {
+    let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let (res, _) = sender
+        .send_to(vec![7; 120], receiver.local_addr().unwrap())
+        .await;
+    res.unwrap();
+
+    let (res, buf) = receiver.recv_batch(vec![0; 1500]).await;
+    let (n, _, segment_size) = res.unwrap();
+    assert_eq!((n, segment_size), (120, 120));
+    assert_eq!(buf, vec![7; 120]);
+}
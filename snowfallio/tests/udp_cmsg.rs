@@ -0,0 +1,97 @@
+use std::{
+    net::{IpAddr, SocketAddr},
+    time::{Duration, SystemTime},
+};
+
+use snowfallio::net::udp::{ControlMessages, Ecn, PacketInfo, UdpSocket};
+
+async fn roundtrip(addr: &str) {
+    let receiver = UdpSocket::bind(addr).unwrap();
+    receiver.set_recv_pktinfo(true).unwrap();
+    receiver.set_recv_ttl(true).unwrap();
+    receiver.set_recv_ecn(true).unwrap();
+    receiver.set_recv_timestamp(true).unwrap();
+    let receiver_addr = receiver.local_addr().unwrap();
+    let sender = UdpSocket::bind(addr).unwrap();
+
+    let control = ControlMessages {
+        ttl: Some(17),
+        ecn: Some(Ecn::Ect0),
+        ..Default::default()
+    };
+    let (res, _) = sender
+        .send_msg("hello", Some(receiver_addr), &control)
+        .await;
+    assert_eq!(res.unwrap(), 5);
+
+    let (res, buf) = receiver.recv_msg(vec![0; 64]).await;
+    let (n, origin, control) = res.unwrap();
+    assert_eq!(&buf[..n], b"hello");
+    assert_eq!(origin, sender.local_addr().unwrap());
+
+    let pktinfo = control.pktinfo.unwrap();
+    assert_eq!(pktinfo.addr, receiver_addr.ip());
+    assert_ne!(pktinfo.interface, 0);
+    assert_eq!(control.ttl, Some(17));
+    assert_eq!(control.ecn, Some(Ecn::Ect0));
+    let elapsed = SystemTime::now()
+        .duration_since(control.timestamp.unwrap())
+        .unwrap();
+    assert!(elapsed < Duration::from_secs(5));
+    assert_eq!(control.segment_size, None);
+}
+
+#[snowfallio::test]
+async fn roundtrip_v4() {
+    roundtrip("127.0.0.1:0").await;
+}
+
+#[snowfallio::test]
+async fn roundtrip_v6() {
+    roundtrip("[::1]:0").await;
+}
+
+#[snowfallio::test]
+async fn options_disabled() {
+    let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+    sender
+        .connect(receiver.local_addr().unwrap())
+        .await
+        .unwrap();
+    let (res, _) = sender
+        .send_msg("hello", None, &ControlMessages::default())
+        .await;
+    res.unwrap();
+
+    let (res, _) = receiver.recv_msg(vec![0; 64]).await;
+    let (n, _, control) = res.unwrap();
+    assert_eq!(n, 5);
+    assert_eq!(control, ControlMessages::default());
+}
+
+#[snowfallio::test]
+async fn send_from_source_address() {
+    let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let sender = UdpSocket::bind("0.0.0.0:0").unwrap();
+    let source: IpAddr = "127.0.0.2".parse().unwrap();
+
+    let control = ControlMessages {
+        pktinfo: Some(PacketInfo {
+            addr: source,
+            interface: 0,
+        }),
+        ..Default::default()
+    };
+    let (res, _) = sender
+        .send_msg("hello", Some(receiver.local_addr().unwrap()), &control)
+        .await;
+    res.unwrap();
+
+    let (res, _) = receiver.recv_from(vec![0; 64]).await;
+    let (_, origin) = res.unwrap();
+    assert_eq!(
+        origin,
+        SocketAddr::new(source, sender.local_addr().unwrap().port())
+    );
+}
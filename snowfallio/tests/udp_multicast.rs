@@ -0,0 +1,93 @@
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use snowfallio::net::udp::UdpSocket;
+
+const GROUP_V4: Ipv4Addr = Ipv4Addr::new(239, 255, 42, 1);
+const GROUP_V6: Ipv6Addr = Ipv6Addr::new(0xff12, 0, 0, 0, 0, 0, 0x42, 1);
+
+/// Index of the loopback interface.
+fn loopback_index() -> u32 {
+    std::fs::read_to_string("/sys/class/net/lo/ifindex")
+        .map(|index| index.trim().parse().unwrap())
+        .unwrap_or(1)
+}
+
+#[snowfallio::test]
+async fn multicast_v4() {
+    let receiver = UdpSocket::bind("0.0.0.0:0").unwrap();
+    receiver
+        .join_multicast_v4(GROUP_V4, Ipv4Addr::LOCALHOST)
+        .unwrap();
+    let port = receiver.local_addr().unwrap().port();
+
+    // Send through the loopback interface.
+    let sender = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket2::SockRef::from(&sender)
+        .set_multicast_if_v4(&Ipv4Addr::LOCALHOST)
+        .unwrap();
+    let sender = UdpSocket::from_std(sender);
+    sender.set_multicast_loop_v4(true).unwrap();
+    assert!(sender.multicast_loop_v4().unwrap());
+    sender.set_multicast_ttl_v4(2).unwrap();
+    assert_eq!(sender.multicast_ttl_v4().unwrap(), 2);
+
+    let (res, _) = sender.send_to("hello", (GROUP_V4, port).into()).await;
+    res.unwrap();
+    let (res, buf) = receiver.recv_from(vec![0; 64]).await;
+    let (n, origin) = res.unwrap();
+    assert_eq!(&buf[..n], b"hello");
+    assert_eq!(origin, sender.local_addr().unwrap());
+
+    receiver
+        .leave_multicast_v4(GROUP_V4, Ipv4Addr::LOCALHOST)
+        .unwrap();
+    assert!(receiver
+        .leave_multicast_v4(GROUP_V4, Ipv4Addr::LOCALHOST)
+        .is_err());
+}
+
+#[test]
+fn multicast_v6() {
+    // The loopback interface has no IPv6 multicast route, so only the
+    // membership is exercised.
+    let interface = loopback_index();
+    let socket = UdpSocket::from_std(std::net::UdpSocket::bind("[::]:0").unwrap());
+    socket.join_multicast_v6(&GROUP_V6, interface).unwrap();
+    socket.set_multicast_loop_v6(false).unwrap();
+    assert!(!socket.multicast_loop_v6().unwrap());
+    socket.set_multicast_loop_v6(true).unwrap();
+    assert!(socket.multicast_loop_v6().unwrap());
+
+    socket.leave_multicast_v6(&GROUP_V6, interface).unwrap();
+    assert!(socket.leave_multicast_v6(&GROUP_V6, interface).is_err());
+}
+
+#[snowfallio::test]
+async fn broadcast() {
+    let receiver = UdpSocket::bind("0.0.0.0:0").unwrap();
+    let target: SocketAddr = (
+        Ipv4Addr::new(127, 255, 255, 255),
+        receiver.local_addr().unwrap().port(),
+    )
+        .into();
+    let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+
+    assert!(!sender.broadcast().unwrap());
+    let (res, _) = sender.send_to("hello", target).await;
+    assert_eq!(res.unwrap_err().raw_os_error(), Some(libc::EACCES));
+
+    sender.set_broadcast(true).unwrap();
+    assert!(sender.broadcast().unwrap());
+    let (res, _) = sender.send_to("hello", target).await;
+    res.unwrap();
+    let (res, buf) = receiver.recv_from(vec![0; 64]).await;
+    let (n, _) = res.unwrap();
+    assert_eq!(&buf[..n], b"hello");
+}
+
+#[test]
+fn ttl() {
+    let socket = UdpSocket::from_std(std::net::UdpSocket::bind("127.0.0.1:0").unwrap());
+    socket.set_ttl(42).unwrap();
+    assert_eq!(socket.ttl().unwrap(), 42);
+}
@@ -9,7 +9,7 @@ async fn zero_copy_for_tcp() {
 
     const MSG: &[u8] = b"copy for split";
     let srv = snowfallio::net::TcpListener::bind("127.0.0.1:0").unwrap();
-    let (mut c_tx, mut c_rx) = local_sync::oneshot::channel::<()>();
+    let (mut c_tx, mut c_rx) = snowfallio::sync::oneshot::channel::<()>();
     let addr = srv.local_addr().unwrap();
     snowfallio::spawn(async move {
         let mut stream = TcpStream::connect(&addr).await.unwrap();
@@ -44,7 +44,7 @@ async fn zero_copy_for_uds() {
         .unwrap();
     let sock_path = dir.path().join("zero_copy.sock");
     let srv = snowfallio::net::UnixListener::bind(&sock_path).unwrap();
-    let (mut c_tx, mut c_rx) = local_sync::oneshot::channel::<()>();
+    let (mut c_tx, mut c_rx) = snowfallio::sync::oneshot::channel::<()>();
     snowfallio::spawn(async move {
         let mut stream = UnixStream::connect(&sock_path).await.unwrap();
         let (mut rx, mut tx) = stream.split();